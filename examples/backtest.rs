@@ -7,9 +7,10 @@
 
 use kraken_regime::prelude::*;
 use kraken_regime::regime::{RegimeConfig, RegimeDetector};
+use kraken_regime::sizing::{FixedFractional, KellySizer, OrderSizer, SizingContext, VolatilityTargetSizer};
 use kraken_regime::strategy::router::RouterStats;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 #[derive(Debug, Deserialize)]
 struct OhlcRecord {
@@ -85,18 +86,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Run full backtest with mixed conditions
     println!("\n📈 Running full backtest with mixed market conditions...\n");
-    
+
     let mixed_data = generate_mixed_market(2000, 50000.0);
-    let results = run_backtest(&mixed_data);
+    let results = run_backtest(&mixed_data, &FixedFractional::default());
     results.print_summary();
-    
+
     // Compare with static strategies
     println!("📊 Comparison with static strategies:\n");
     compare_strategies(&mixed_data);
-    
+
+    // Compare sizing regimes on the same regime-aware signals
+    println!("📊 Comparison of position-sizing regimes:\n");
+    compare_sizers(&mixed_data);
+
     Ok(())
 }
 
+/// Compare `OrderSizer` implementations against the same regime-aware signals
+fn compare_sizers(data: &[Candle]) {
+    let fixed = run_backtest(data, &FixedFractional::default());
+    let vol_target = run_backtest(data, &VolatilityTargetSizer::default());
+    let kelly = run_backtest(data, &KellySizer::default());
+
+    println!("┌─────────────────────┬─────────────┬─────────────┬─────────────┐");
+    println!("│ Metric              │ Fixed 1%    │ Vol-Target  │ Kelly (25%) │");
+    println!("├─────────────────────┼─────────────┼─────────────┼─────────────┤");
+    println!("│ Total Trades        │ {:>11} │ {:>11} │ {:>11} │",
+        fixed.total_trades, vol_target.total_trades, kelly.total_trades);
+    println!("│ Win Rate            │ {:>10.1}% │ {:>10.1}% │ {:>10.1}% │",
+        fixed.win_rate(), vol_target.win_rate(), kelly.win_rate());
+    println!("│ Total P&L           │ ${:>10.2} │ ${:>10.2} │ ${:>10.2} │",
+        fixed.total_pnl, vol_target.total_pnl, kelly.total_pnl);
+    println!("│ Max Drawdown        │ ${:>10.2} │ ${:>10.2} │ ${:>10.2} │",
+        fixed.max_drawdown, vol_target.max_drawdown, kelly.max_drawdown);
+    println!("└─────────────────────┴─────────────┴─────────────┴─────────────┘");
+}
+
 /// Test regime detection accuracy
 fn test_regime_detection(name: &str, data: &[Candle]) {
     let mut detector = RegimeDetector::crypto_optimized();
@@ -117,16 +142,39 @@ fn test_regime_detection(name: &str, data: &[Candle]) {
     println!();
 }
 
-/// Run full backtest
-fn run_backtest(data: &[Candle]) -> BacktestResults {
+/// Rolling window of recent trade returns used to estimate `KellySizer`'s
+/// win rate and average win/loss ahead of the next entry
+const SIZING_LOOKBACK: usize = 20;
+
+/// Recent win rate and average win/loss pct (both positive) over the last
+/// `SIZING_LOOKBACK` closed trades, the inputs `KellySizer` needs
+fn recent_trade_stats(recent_returns: &VecDeque<f64>) -> (f64, f64, f64) {
+    if recent_returns.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let wins: Vec<f64> = recent_returns.iter().copied().filter(|&r| r > 0.0).collect();
+    let losses: Vec<f64> = recent_returns.iter().copied().filter(|&r| r <= 0.0).collect();
+    let win_rate = wins.len() as f64 / recent_returns.len() as f64;
+    let avg_win = if wins.is_empty() { 0.0 } else { wins.iter().sum::<f64>() / wins.len() as f64 };
+    let avg_loss = if losses.is_empty() {
+        0.0
+    } else {
+        losses.iter().map(|r| r.abs()).sum::<f64>() / losses.len() as f64
+    };
+    (win_rate, avg_win, avg_loss)
+}
+
+/// Run full backtest, sizing every entry with `sizer`
+fn run_backtest(data: &[Candle], sizer: &dyn OrderSizer) -> BacktestResults {
     let config = KrakenIntegrationConfig::default();
     let mut trader = KrakenRegimeTrader::new(config);
-    
+
     let mut results = BacktestResults::default();
     let mut position: Option<Position> = None;
     let mut equity = 10000.0;
     let mut peak_equity = equity;
-    
+    let mut recent_returns: VecDeque<f64> = VecDeque::with_capacity(SIZING_LOOKBACK);
+
     for candle in data {
         // Update regime counts
         if let Some(regime) = trader.get_regime("BTC/USD") {
@@ -152,11 +200,25 @@ fn run_backtest(data: &[Candle]) -> BacktestResults {
                 reason: action.reason.clone(),
                 stop_loss: action.stop_loss,
                 take_profit: action.take_profit,
+                direction: kraken_regime::regime::PositionDirection::Long,
+                leverage: 1.0,
+                risk_halted: false,
             });
             
             match action.action {
                 TradeType::Buy if position.is_none() => {
-                    let size = equity * 0.01 * action.size_factor;  // 1% risk adjusted by factor
+                    let (recent_win_rate, recent_avg_win_pct, recent_avg_loss_pct) =
+                        recent_trade_stats(&recent_returns);
+                    let size = sizer.size(&SizingContext {
+                        equity,
+                        entry_price: candle.close,
+                        stop_loss: action.stop_loss,
+                        size_factor: action.size_factor,
+                        atr: None,
+                        recent_win_rate,
+                        recent_avg_win_pct,
+                        recent_avg_loss_pct,
+                    });
                     position = Some(Position {
                         entry_price: candle.close,
                         size,
@@ -166,63 +228,81 @@ fn run_backtest(data: &[Candle]) -> BacktestResults {
                 }
                 TradeType::Sell if position.is_some() => {
                     if let Some(pos) = position.take() {
-                        let pnl = (candle.close - pos.entry_price) / pos.entry_price * pos.size;
+                        let return_pct = (candle.close - pos.entry_price) / pos.entry_price;
+                        let pnl = return_pct * pos.size;
                         equity += pnl;
                         results.total_pnl += pnl;
                         results.total_trades += 1;
-                        
+
                         if pnl > 0.0 {
                             results.winning_trades += 1;
                         } else {
                             results.losing_trades += 1;
                         }
-                        
+
                         peak_equity = peak_equity.max(equity);
                         let drawdown = peak_equity - equity;
                         results.max_drawdown = results.max_drawdown.max(drawdown);
+
+                        recent_returns.push_back(return_pct);
+                        if recent_returns.len() > SIZING_LOOKBACK {
+                            recent_returns.pop_front();
+                        }
                     }
                 }
                 _ => {}
             }
-            
+
             // Check stops on existing position
             if let Some(ref pos) = position {
                 if let Some(stop) = pos.stop_loss {
                     if candle.low <= stop {
-                        let pnl = (stop - pos.entry_price) / pos.entry_price * pos.size;
+                        let return_pct = (stop - pos.entry_price) / pos.entry_price;
+                        let pnl = return_pct * pos.size;
                         equity += pnl;
                         results.total_pnl += pnl;
                         results.total_trades += 1;
                         results.losing_trades += 1;
                         position = None;
-                        
+
                         let drawdown = peak_equity - equity;
                         results.max_drawdown = results.max_drawdown.max(drawdown);
+
+                        recent_returns.push_back(return_pct);
+                        if recent_returns.len() > SIZING_LOOKBACK {
+                            recent_returns.pop_front();
+                        }
                     }
                 }
                 if let Some(tp) = pos.take_profit {
                     if candle.high >= tp {
-                        let pnl = (tp - pos.entry_price) / pos.entry_price * pos.size;
+                        let return_pct = (tp - pos.entry_price) / pos.entry_price;
+                        let pnl = return_pct * pos.size;
                         equity += pnl;
                         results.total_pnl += pnl;
                         results.total_trades += 1;
                         results.winning_trades += 1;
                         position = None;
-                        
+
                         peak_equity = peak_equity.max(equity);
+
+                        recent_returns.push_back(return_pct);
+                        if recent_returns.len() > SIZING_LOOKBACK {
+                            recent_returns.pop_front();
+                        }
                     }
                 }
             }
         }
     }
-    
+
     results
 }
 
 /// Compare regime-aware strategy vs static strategies
 fn compare_strategies(data: &[Candle]) {
     // Regime-aware backtest
-    let regime_aware = run_backtest(data);
+    let regime_aware = run_backtest(data, &FixedFractional::default());
     
     // Static trend-following (simple MA crossover)
     let trend_only = run_static_trend_backtest(data);
@@ -372,6 +452,7 @@ fn generate_trending_market(bars: usize, start_price: f64, trend_per_bar: f64) -
             low,
             close: price,
             volume: 100.0 + rand::random::<f64>() * 50.0,
+            ..Default::default()
         });
     }
     
@@ -397,6 +478,7 @@ fn generate_ranging_market(bars: usize, center_price: f64, range_pct: f64) -> Ve
             low,
             close: price,
             volume: 100.0 + rand::random::<f64>() * 50.0,
+            ..Default::default()
         });
     }
     
@@ -423,6 +505,7 @@ fn generate_volatile_market(bars: usize, center_price: f64, volatility_pct: f64)
             low,
             close: price,
             volume: 100.0 + rand::random::<f64>() * 100.0,  // Higher volume in volatile markets
+            ..Default::default()
         });
     }
     
@@ -185,6 +185,7 @@ fn print_results(
                 MarketRegime::Trending(TrendDirection::Bearish) => "Trending↓",
                 MarketRegime::MeanReverting => "Ranging",
                 MarketRegime::Volatile => "Volatile",
+                MarketRegime::Squeeze => "Squeeze",
                 MarketRegime::Uncertain => "Uncertain",
             };
             *counts.entry(key.to_string()).or_insert(0) += 1;
@@ -194,7 +195,7 @@ fn print_results(
     
     fn format_counts(counts: &HashMap<String, usize>, total: usize) -> String {
         let mut result = Vec::new();
-        for regime in ["Trending↑", "Trending↓", "Ranging", "Volatile", "Uncertain"] {
+        for regime in ["Trending↑", "Trending↓", "Ranging", "Volatile", "Squeeze", "Uncertain"] {
             if let Some(&count) = counts.get(regime) {
                 let pct = count as f64 / total as f64 * 100.0;
                 result.push(format!("{}: {:.0}%", regime, pct));
@@ -240,6 +241,7 @@ fn regime_type(r: &MarketRegime) -> &str {
         MarketRegime::Trending(_) => "Trending",
         MarketRegime::MeanReverting => "Ranging",
         MarketRegime::Volatile => "Volatile",
+        MarketRegime::Squeeze => "Squeeze",
         MarketRegime::Uncertain => "Uncertain",
     }
 }
@@ -5,19 +5,101 @@
 //!
 //! Run with: cargo run --example live_trading
 
+use backoff::{backoff::Backoff, ExponentialBackoff};
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
+use kraken_regime::integration::{KRAKEN_WS_URL_V1, KRAKEN_WS_URL_V2};
 use kraken_regime::prelude::*;
-use kraken_regime::TradeType;
+use kraken_regime::{DataFrame, KrakenWsFrame, KrakenWsFrameV2, PairStatus, TradeType};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::env;
-use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use std::fmt;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{self, Message},
+    MaybeTlsStream, WebSocketStream,
+};
 use tracing::{error, info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
+use url::Url;
 
-const KRAKEN_WS_URL: &str = "wss://ws.kraken.com";
-#[allow(dead_code)]
-const KRAKEN_WS_URL_BETA: &str = "wss://beta-ws.kraken.com";
+type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// A single WebSocket frame was malformed, or didn't match any shape we
+/// understand. Recoverable: log it and keep reading from the same socket.
+#[derive(Debug)]
+struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse WebSocket message: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        ParseError(e.to_string())
+    }
+}
+
+/// The socket itself is gone — closed by the server, or a transport-level
+/// failure. Fatal for the current connection: `run_websocket_loop` breaks
+/// out of the read loop and reconnects.
+#[derive(Debug)]
+enum ConnectionError {
+    ClosedByServer,
+    Transport(tungstenite::Error),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::ClosedByServer => write!(f, "connection closed by server"),
+            ConnectionError::Transport(e) => write!(f, "transport error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+impl From<tungstenite::Error> for ConnectionError {
+    fn from(e: tungstenite::Error) -> Self {
+        ConnectionError::Transport(e)
+    }
+}
+
+/// Latest observable state of the bot, broadcast over a `watch` channel so
+/// other tasks can read it on demand instead of polling `print_status`.
+/// A new subscriber gets the most recent variant immediately, then every
+/// update after; `Failed` is terminal and is only ever sent once, right
+/// before the bot gives up for good.
+#[derive(Debug, Clone)]
+enum TraderUpdate {
+    /// Per-pair regime/strategy snapshot, refreshed after each processed candle.
+    Status(HashMap<String, PairStatus>),
+    /// The most recent non-`Hold` trade signal.
+    Trade(TradeAction),
+    /// `run_websocket_loop` has stopped retrying and exited for good.
+    Failed(String),
+}
+
+/// How long to wait without receiving any frame (data, heartbeat, or pong)
+/// before assuming the connection is half-open and forcing a reconnect.
+const HEARTBEAT_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+/// How often we proactively ping the server, so a half-open connection is
+/// caught by `HEARTBEAT_TIMEOUT` well before Kraken would notice on its own.
+const CLIENT_PING_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(7);
+/// How often to re-fetch recent OHLC history and replay it through warmup,
+/// correcting the router for any candle the WebSocket silently dropped.
+const REWARMUP_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(30 * 60);
+/// UTC hour (0-23) the daily summary is sent at.
+const DAILY_SUMMARY_UTC_HOUR: u64 = 0;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -32,6 +114,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("🦑 Kraken Regime-Aware Trading Bot Starting...");
 
     // Configuration
+    // Allow pointing at the beta server, a local mock, or Kraken's v2
+    // endpoint without a rebuild.
+    let use_v2 = env::var("KRAKEN_WS_USE_V2")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    let default_ws_url = if use_v2 {
+        KRAKEN_WS_URL_V2
+    } else {
+        KRAKEN_WS_URL_V1
+    };
+    let ws_url = env::var("KRAKEN_WS_URL")
+        .ok()
+        .and_then(|s| Url::parse(&s).ok())
+        .unwrap_or_else(|| Url::parse(default_ws_url).expect("built-in WS URL is valid"));
+
     let config = KrakenIntegrationConfig {
         pairs: vec![
             "BTC/USD".to_string(),
@@ -43,6 +140,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         min_trade_usd: 10.0,
         max_trade_usd: 250.0,
         risk_per_trade_pct: 1.0,
+        ws_url,
+        use_v2,
         ..Default::default()
     };
 
@@ -53,6 +152,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let (signal_tx, mut signal_rx) = mpsc::channel::<TradeAction>(100);
     trader.set_signal_channel(signal_tx);
 
+    // Watch channel publishing the trader's latest status/trade/failure so
+    // other tasks can observe it on demand instead of polling print_status.
+    let (update_tx, mut update_rx) = watch::channel(TraderUpdate::Status(HashMap::new()));
+
     // Warmup with historical data
     info!("📊 Fetching historical data for warmup...");
     for pair in &config.pairs {
@@ -72,9 +175,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    // Spawn an update-channel subscriber to demonstrate event-driven
+    // observation: it gets the current value immediately, then every
+    // subsequent change, with no polling interval of its own.
+    tokio::spawn(async move {
+        while update_rx.changed().await.is_ok() {
+            match &*update_rx.borrow() {
+                TraderUpdate::Status(status) => {
+                    info!("🔄 Status update for {} pair(s)", status.len());
+                }
+                TraderUpdate::Trade(action) => {
+                    info!("🔔 Trade signal observed: {} {:?}", action.symbol, action.action);
+                }
+                TraderUpdate::Failed(reason) => {
+                    error!("💀 Bot stopped for good: {}", reason);
+                }
+            }
+        }
+    });
+
     // Connect to WebSocket and start processing
     info!("🔌 Connecting to Kraken WebSocket...");
-    run_websocket_loop(&config, trader).await?;
+    if let Err(e) = run_websocket_loop(&config, trader, update_tx.clone()).await {
+        update_tx.send_replace(TraderUpdate::Failed(e.to_string()));
+        return Err(e);
+    }
 
     Ok(())
 }
@@ -121,6 +246,7 @@ async fn warmup_pair(
                                 low: parse_price(&ohlc[3]),
                                 close: parse_price(&ohlc[4]),
                                 volume: parse_price(&ohlc[6]),
+                                vwap: parse_price(&ohlc[5]),
                             };
                             candles.push(candle);
                         }
@@ -144,131 +270,361 @@ fn parse_price(value: &Value) -> f64 {
         .unwrap_or(0.0)
 }
 
-/// Run the main WebSocket loop
-async fn run_websocket_loop(
+/// Connect to the Kraken WebSocket and (re-)subscribe to OHLC + ticker for
+/// every configured pair. Called on startup and again after every
+/// reconnect, so all subscriptions must be replayed here rather than once.
+async fn connect_and_subscribe(
     config: &KrakenIntegrationConfig,
-    mut trader: KrakenRegimeTrader,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let (ws_stream, _) = connect_async(KRAKEN_WS_URL).await?;
-    let (mut write, mut read) = ws_stream.split();
+) -> Result<(WsWriter, WsReader), ConnectionError> {
+    let (ws_stream, _) = connect_async(config.ws_url.as_str()).await?;
+    let (mut write, read) = ws_stream.split();
+
+    info!("✅ Connected to Kraken WebSocket ({})", config.ws_url);
+
+    if config.use_v2 {
+        // v2's subscription shape: {"method": "subscribe", "params": {...}}
+        for pair in &config.pairs {
+            let sub_msg = json!({
+                "method": "subscribe",
+                "params": {
+                    "channel": "ohlc",
+                    "symbol": [pair],
+                    "interval": config.timeframe_minutes
+                }
+            });
+
+            write.send(Message::Text(sub_msg.to_string())).await?;
+            info!("📡 Subscribed to {} OHLC (v2)", pair);
+        }
+    } else {
+        // Subscribe to OHLC channels for each pair
+        for pair in &config.pairs {
+            let sub_msg = json!({
+                "event": "subscribe",
+                "pair": [pair],
+                "subscription": {
+                    "name": "ohlc",
+                    "interval": config.timeframe_minutes
+                }
+            });
 
-    info!("✅ Connected to Kraken WebSocket");
+            write.send(Message::Text(sub_msg.to_string())).await?;
+            info!("📡 Subscribed to {} OHLC", pair);
+        }
 
-    // Subscribe to OHLC channels for each pair
-    for pair in &config.pairs {
-        let sub_msg = json!({
+        // Also subscribe to ticker for real-time price updates
+        let ticker_sub = json!({
             "event": "subscribe",
-            "pair": [pair],
+            "pair": config.pairs,
             "subscription": {
-                "name": "ohlc",
-                "interval": config.timeframe_minutes
+                "name": "ticker"
             }
         });
-
-        write.send(Message::Text(sub_msg.to_string())).await?;
-        info!("📡 Subscribed to {} OHLC", pair);
+        write.send(Message::Text(ticker_sub.to_string())).await?;
     }
 
-    // Also subscribe to ticker for real-time price updates
-    let ticker_sub = json!({
-        "event": "subscribe",
-        "pair": config.pairs,
-        "subscription": {
-            "name": "ticker"
-        }
-    });
-    write.send(Message::Text(ticker_sub.to_string())).await?;
+    Ok((write, read))
+}
 
-    // Process messages
-    let mut status_interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+/// Run the main WebSocket loop with automatic reconnection.
+///
+/// On any connection-level failure (a dropped socket, a server-initiated
+/// close, or a failed `connect_async`) this reconnects and replays the
+/// OHLC/ticker subscriptions instead of ending the bot. Reconnect delay
+/// follows an `ExponentialBackoff` with no elapsed-time cap, so the bot
+/// keeps retrying indefinitely; the backoff resets the moment a new
+/// connection successfully receives a message, so a brief blip doesn't
+/// inflate the delay before the next one.
+async fn run_websocket_loop(
+    config: &KrakenIntegrationConfig,
+    mut trader: KrakenRegimeTrader,
+    update_tx: watch::Sender<TraderUpdate>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut backoff = ExponentialBackoff {
+        max_elapsed_time: None,
+        ..ExponentialBackoff::default()
+    };
+    let mut status_interval =
+        tokio::time::interval(tokio::time::Duration::from_secs(config.status_interval_secs));
+    let mut rewarmup_interval = tokio::time::interval(REWARMUP_INTERVAL);
+    rewarmup_interval.reset(); // skip the immediate first tick; warmup_pair already ran once at startup
+    let mut daily_summary_at = tokio::time::Instant::now() + duration_until_next_daily_summary();
+
+    // Per-pair emitted-signal counts and regime-change baseline since the
+    // last daily summary, so the summary reports "today" rather than the
+    // lifetime total already tracked by `status_summary()`.
+    let mut signals_today: HashMap<String, u32> = HashMap::new();
+    let mut regime_changes_baseline: HashMap<String, u32> = trader
+        .status_summary()
+        .into_iter()
+        .map(|(pair, status)| (pair, status.regime_changes))
+        .collect();
 
     loop {
-        tokio::select! {
-            Some(msg) = read.next() => {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        if let Err(e) = process_ws_message(&text, &mut trader) {
-                            warn!("Error processing message: {}", e);
+        let (mut write, mut read) = match connect_and_subscribe(config).await {
+            Ok(streams) => streams,
+            Err(e) => {
+                error!("Failed to connect to Kraken WebSocket: {}", e);
+                let wait = backoff.next_backoff().unwrap_or(backoff.max_interval);
+                warn!("Reconnecting in {:?}...", wait);
+                tokio::time::sleep(wait).await;
+                continue;
+            }
+        };
+
+        let mut ping_interval = tokio::time::interval(CLIENT_PING_INTERVAL);
+        let mut last_frame_at = tokio::time::Instant::now();
+
+        loop {
+            tokio::select! {
+                Some(msg) = read.next() => {
+                    last_frame_at = tokio::time::Instant::now();
+
+                    let connection_result: Result<(), ConnectionError> = match msg {
+                        Ok(Message::Text(text)) => {
+                            backoff.reset();
+                            if let Err(e) = process_ws_message(
+                                &text,
+                                &mut trader,
+                                &update_tx,
+                                config.use_v2,
+                                &mut signals_today,
+                            ) {
+                                warn!("{}", e);
+                            }
+                            Ok(())
                         }
-                    }
-                    Ok(Message::Ping(data)) => {
-                        let _ = write.send(Message::Pong(data)).await;
-                    }
-                    Ok(Message::Close(_)) => {
-                        warn!("WebSocket closed by server");
+                        Ok(Message::Ping(data)) => {
+                            backoff.reset();
+                            let _ = write.send(Message::Pong(data)).await;
+                            Ok(())
+                        }
+                        Ok(Message::Close(_)) => Err(ConnectionError::ClosedByServer),
+                        Err(e) => Err(e.into()),
+                        _ => Ok(()),
+                    };
+
+                    if let Err(e) = connection_result {
+                        warn!("{}, will reconnect", e);
                         break;
                     }
-                    Err(e) => {
-                        error!("WebSocket error: {}", e);
-                        break;
+                }
+                _ = ping_interval.tick() => {
+                    let _ = write.send(Message::Ping(Vec::new())).await;
+                }
+                _ = tokio::time::sleep_until(last_frame_at + HEARTBEAT_TIMEOUT) => {
+                    warn!(
+                        "No frames (including heartbeats) received in {:?}, connection looks dead, will reconnect",
+                        HEARTBEAT_TIMEOUT
+                    );
+                    break;
+                }
+                _ = status_interval.tick() => {
+                    print_status(&trader);
+                    let _ = update_tx.send(TraderUpdate::Status(trader.status_summary()));
+                }
+                _ = rewarmup_interval.tick() => {
+                    info!("🔄 Re-warming up from recent OHLC history...");
+                    for pair in &config.pairs {
+                        if let Err(e) = warmup_pair(&mut trader, pair).await {
+                            warn!("⚠️ {} re-warmup failed: {}", pair, e);
+                        }
                     }
-                    _ => {}
                 }
-            }
-            _ = status_interval.tick() => {
-                print_status(&trader);
+                _ = tokio::time::sleep_until(daily_summary_at) => {
+                    send_daily_summary(&trader, &signals_today, &regime_changes_baseline).await;
+                    signals_today.clear();
+                    regime_changes_baseline = trader
+                        .status_summary()
+                        .into_iter()
+                        .map(|(pair, status)| (pair, status.regime_changes))
+                        .collect();
+                    daily_summary_at = tokio::time::Instant::now() + duration_until_next_daily_summary();
+                }
             }
         }
+
+        let wait = backoff.next_backoff().unwrap_or(backoff.max_interval);
+        warn!("Reconnecting in {:?}...", wait);
+        tokio::time::sleep(wait).await;
     }
+}
 
-    Ok(())
+/// Duration from now until the next `DAILY_SUMMARY_UTC_HOUR:00:00 UTC`.
+fn duration_until_next_daily_summary() -> tokio::time::Duration {
+    let target_secs_of_day = DAILY_SUMMARY_UTC_HOUR * 3600;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs_of_day = now.as_secs() % 86_400;
+
+    let wait = if secs_of_day < target_secs_of_day {
+        target_secs_of_day - secs_of_day
+    } else {
+        86_400 - secs_of_day + target_secs_of_day
+    };
+
+    tokio::time::Duration::from_secs(wait)
 }
 
-/// Process a WebSocket message
+/// Aggregate regime changes and emitted signals per pair since the last
+/// summary and push them to Discord in the same embed format trade signals
+/// use.
+async fn send_daily_summary(
+    trader: &KrakenRegimeTrader,
+    signals_today: &HashMap<String, u32>,
+    regime_changes_baseline: &HashMap<String, u32>,
+) {
+    let Ok(webhook_url) = env::var("DISCORD_WEBHOOK_URL") else {
+        return;
+    };
+
+    let fields: Vec<Value> = trader
+        .status_summary()
+        .into_iter()
+        .map(|(pair, status)| {
+            let baseline = regime_changes_baseline.get(&pair).copied().unwrap_or(0);
+            let changes_today = status.regime_changes.saturating_sub(baseline);
+            let signals = signals_today.get(&pair).copied().unwrap_or(0);
+            json!({
+                "name": pair,
+                "value": format!("Regime changes: {} | Signals: {}", changes_today, signals),
+                "inline": false,
+            })
+        })
+        .collect();
+
+    let payload = json!({
+        "embeds": [{
+            "title": "📅 Daily Summary",
+            "color": 0x5865f2,
+            "fields": fields,
+            "footer": {"text": "Kraken Regime-Aware Trading Bot"}
+        }]
+    });
+
+    if let Err(e) = reqwest::Client::new()
+        .post(&webhook_url)
+        .json(&payload)
+        .send()
+        .await
+    {
+        warn!("Failed to send daily summary notification: {}", e);
+    }
+}
+
+/// Process a WebSocket message. `use_v2` selects which protocol generation
+/// the frame is decoded as — it must match whatever `connect_and_subscribe`
+/// subscribed with.
 fn process_ws_message(
     text: &str,
     trader: &mut KrakenRegimeTrader,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let value: Value = serde_json::from_str(text)?;
-
-    // Skip heartbeats and status messages
-    if value.get("event").is_some() {
+    update_tx: &watch::Sender<TraderUpdate>,
+    use_v2: bool,
+    signals_today: &mut HashMap<String, u32>,
+) -> Result<(), ParseError> {
+    let Some((pair, candle)) = (if use_v2 {
+        parse_v2_candle(text)?
+    } else {
+        parse_v1_candle(text)?
+    }) else {
         return Ok(());
-    }
+    };
 
-    // OHLC format: [channelId, [time, etime, open, high, low, close, vwap, volume, count], "ohlc-15", "XBT/USD"]
-    if let Some(arr) = value.as_array() {
-        if arr.len() >= 4 {
-            let channel = arr.get(2).and_then(|v| v.as_str()).unwrap_or("");
-
-            if channel.starts_with("ohlc") {
-                if let (Some(data), Some(pair)) = (
-                    arr.get(1).and_then(|v| v.as_array()),
-                    arr.get(3).and_then(|v| v.as_str()),
-                ) {
-                    if data.len() >= 8 {
-                        let candle = Candle {
-                            timestamp: parse_price(&data[0]) as i64,
-                            open: parse_price(&data[2]),
-                            high: parse_price(&data[3]),
-                            low: parse_price(&data[4]),
-                            close: parse_price(&data[5]),
-                            volume: parse_price(&data[7]),
-                        };
-
-                        // Convert Kraken pair format
-                        let normalized_pair = normalize_pair(pair);
-
-                        if let Some(action) = trader.process_candle(&normalized_pair, &candle) {
-                            if action.action != TradeType::Hold {
-                                info!(
-                                    "📊 {} | Regime: {} | Strategy: {} | Signal: {:?}",
-                                    normalized_pair,
-                                    action.regime,
-                                    action.source_strategy,
-                                    action.action
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+    // Convert Kraken pair format
+    let normalized_pair = normalize_pair(&pair);
+
+    if let Some(action) = trader.process_candle(&normalized_pair, &candle) {
+        if action.action != TradeType::Hold {
+            info!(
+                "📊 {} | Regime: {} | Strategy: {} | Signal: {:?}",
+                normalized_pair, action.regime, action.source_strategy, action.action
+            );
+            *signals_today.entry(normalized_pair.clone()).or_insert(0) += 1;
+            let _ = update_tx.send(TraderUpdate::Trade(action));
         }
     }
+    let _ = update_tx.send(TraderUpdate::Status(trader.status_summary()));
 
     Ok(())
 }
 
+/// Decode a v1 frame (`[channelId, payload, channelName, pair]`) into the
+/// pair/candle it carries, if it's an OHLC update.
+fn parse_v1_candle(text: &str) -> Result<Option<(String, Candle)>, ParseError> {
+    let frame: KrakenWsFrame = serde_json::from_str(text)?;
+
+    Ok(match frame {
+        // Heartbeats, subscription acks, and errors carry no candle data.
+        KrakenWsFrame::Control(_) => None,
+        KrakenWsFrame::Data(DataFrame::Ohlc { payload, pair, .. }) => Some((
+            pair,
+            Candle {
+                timestamp: payload.time as i64,
+                open: payload.open,
+                high: payload.high,
+                low: payload.low,
+                close: payload.close,
+                volume: payload.volume,
+                vwap: payload.vwap,
+            },
+        )),
+        // Ticker/unrecognized channels don't feed the regime router (yet).
+        KrakenWsFrame::Data(DataFrame::Ticker { .. } | DataFrame::Other { .. }) => None,
+    })
+}
+
+/// Decode a v2 frame (`{"channel": ..., "data": [...]}`) into the
+/// pair/candle it carries, if it's an OHLC update.
+fn parse_v2_candle(text: &str) -> Result<Option<(String, Candle)>, ParseError> {
+    let frame: KrakenWsFrameV2 = serde_json::from_str(text)?;
+
+    Ok(match frame {
+        KrakenWsFrameV2::Other(_) => None,
+        KrakenWsFrameV2::Ohlc(ohlc) => ohlc.data.into_iter().next().map(|d| {
+            (
+                d.symbol,
+                Candle {
+                    timestamp: parse_rfc3339_epoch(&d.timestamp),
+                    open: d.open,
+                    high: d.high,
+                    low: d.low,
+                    close: d.close,
+                    volume: d.volume,
+                    vwap: d.vwap,
+                },
+            )
+        }),
+    })
+}
+
+/// Minimal `YYYY-MM-DDTHH:MM:SS(.fraction)?Z` to Unix-seconds conversion —
+/// just enough for Kraken v2's timestamp strings, without pulling in a
+/// date/time crate for one field. Returns 0 for anything it doesn't
+/// recognize.
+fn parse_rfc3339_epoch(ts: &str) -> i64 {
+    if ts.len() < 19 {
+        return 0;
+    }
+    let digits = |s: &str| s.parse::<i64>().unwrap_or(0);
+    let (year, month, day) = (digits(&ts[0..4]), digits(&ts[5..7]), digits(&ts[8..10]));
+    let (hour, minute, second) = (digits(&ts[11..13]), digits(&ts[14..16]), digits(&ts[17..19]));
+
+    days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+}
+
+/// Howard Hinnant's `days_from_civil`: days since the Unix epoch for a
+/// given (proleptic Gregorian) calendar date.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
 /// Normalize Kraken pair format to standard format
 fn normalize_pair(kraken_pair: &str) -> String {
     kraken_pair.replace("XBT", "BTC").replace("/", "/")
@@ -282,6 +638,7 @@ async fn handle_trade_signal(action: TradeAction) {
             info!("   Pair: {}", action.symbol);
             info!("   Price: ${:.2}", action.price);
             info!("   Size Factor: {:.1}%", action.size_factor * 100.0);
+            info!("   Order Size: ${:.2}", action.order_size_usd);
             info!(
                 "   Stop Loss: {:?}",
                 action.stop_loss.map(|p| format!("${:.2}", p))
@@ -347,6 +704,7 @@ async fn send_discord_notification(action: &TradeAction) {
                     {"name": "Strategy", "value": &action.source_strategy, "inline": true},
                     {"name": "Confidence", "value": format!("{:.1}%", action.confidence * 100.0), "inline": true},
                     {"name": "Position Size", "value": format!("{:.0}%", action.size_factor * 100.0), "inline": true},
+                    {"name": "Order Size", "value": format!("${:.2}", action.order_size_usd), "inline": true},
                     {"name": "Reason", "value": &action.reason, "inline": false},
                 ],
                 "footer": {"text": "Kraken Regime-Aware Trading Bot"}
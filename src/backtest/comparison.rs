@@ -0,0 +1,501 @@
+//! Cross-Method Regime Detection Comparison Backtest
+//!
+//! `examples/compare_methods.rs` only tallies regime distribution per
+//! detection method - useful for eyeballing how often each one calls
+//! `Trending`/`Volatile`/etc, but it never traded, so there's no answer to
+//! "which method actually makes money, and in which regime". `run_comparison`
+//! replays the same candle series through a fresh `EnhancedRouter` for each
+//! of `DetectionMethod::Indicators`/`HMM`/`Ensemble`, fee/slippage-adjusts
+//! every fill the way `Engine` does for `StrategyRouter`, and reports the
+//! same risk-adjusted metrics per method and per regime.
+
+use std::collections::HashMap;
+
+use crate::integration::Candle;
+use crate::regime::{MarketRegime, RecommendedStrategy};
+use crate::strategy::enhanced_router::{ActiveStrategy, DetectionMethod, EnhancedRouter};
+use crate::strategy::mean_reversion::Signal;
+
+/// Fee/slippage assumptions and stop-model choice applied identically to
+/// every `DetectionMethod` under comparison, so differences in the
+/// resulting metrics reflect the detector, not the cost model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComparisonConfig {
+    /// Fee charged on a resting (maker) fill, in basis points of notional
+    pub maker_fee_bps: f64,
+    /// Fee charged on a market (taker) fill, in basis points of notional
+    pub taker_fee_bps: f64,
+    /// Adverse price movement applied to every fill, in basis points
+    pub slippage_bps: f64,
+    /// Bars per year, used to annualize Sharpe/Sortino
+    pub bars_per_year: f64,
+    /// Override whatever stop-loss/take-profit the signal carried with
+    /// `RecommendedStrategy::risk_parameters()` applied to the active
+    /// detector's live ATR (falling back to `close * 0.02` for `HMM`,
+    /// which doesn't track one) - puts every method on identical risk
+    /// sizing instead of whatever its own strategy branch happened to use
+    pub use_atr_stop_model: bool,
+}
+
+impl Default for ComparisonConfig {
+    /// Kraken's default spot fee schedule, 5bps of slippage, 15-minute
+    /// bars, and the ATR stop model on
+    fn default() -> Self {
+        Self {
+            maker_fee_bps: 16.0,
+            taker_fee_bps: 26.0,
+            slippage_bps: 5.0,
+            bars_per_year: 365.0 * 24.0 * 4.0,
+            use_atr_stop_model: true,
+        }
+    }
+}
+
+/// A single closed trade
+#[derive(Debug, Clone)]
+pub struct ComparisonTrade {
+    pub entry_bar: usize,
+    pub exit_bar: usize,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub duration_bars: usize,
+    /// Fee/slippage-adjusted return, e.g. 0.02 for +2%
+    pub return_pct: f64,
+    pub strategy: ActiveStrategy,
+    /// Regime in effect when the position was opened
+    pub regime: MarketRegime,
+}
+
+/// Aggregated performance for one grouping (a regime)
+#[derive(Debug, Clone, Default)]
+pub struct ComparisonGroupStats {
+    pub trades: u32,
+    pub wins: u32,
+    /// Cumulative compounded return across trades in this group, e.g. 0.15 for +15%
+    pub cumulative_return_pct: f64,
+    pub avg_duration_bars: f64,
+}
+
+impl ComparisonGroupStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.trades as f64
+    }
+
+    fn record(&mut self, trades: &[&ComparisonTrade]) {
+        self.trades = trades.len() as u32;
+        if self.trades == 0 {
+            return;
+        }
+
+        self.wins = trades.iter().filter(|t| t.return_pct > 0.0).count() as u32;
+        self.avg_duration_bars = trades.iter().map(|t| t.duration_bars as f64).sum::<f64>()
+            / self.trades as f64;
+        self.cumulative_return_pct = trades.iter().fold(1.0, |equity, t| equity * (1.0 + t.return_pct)) - 1.0;
+    }
+}
+
+/// Risk-adjusted performance for one `DetectionMethod`'s run
+#[derive(Debug, Clone, Default)]
+pub struct ComparisonMetrics {
+    pub total_return_pct: f64,
+    /// `mean(r) / std(r) * sqrt(bars_per_year)` over per-bar returns
+    pub sharpe_ratio: f64,
+    /// Gross profit divided by gross loss across all trades
+    pub profit_factor: f64,
+    pub max_drawdown_pct: f64,
+    pub win_rate: f64,
+    pub avg_trade_duration_bars: f64,
+    /// P&L attribution by the regime in effect when each trade opened
+    pub by_regime: HashMap<MarketRegime, ComparisonGroupStats>,
+}
+
+/// Trades, equity curve, and derived metrics for one `DetectionMethod`
+#[derive(Debug, Clone, Default)]
+pub struct MethodReport {
+    pub trades: Vec<ComparisonTrade>,
+    /// Equity after each bar, normalized to start at 1.0
+    pub equity_curve: Vec<f64>,
+    pub metrics: ComparisonMetrics,
+}
+
+/// Side-by-side backtest of every `DetectionMethod` over the same candles
+#[derive(Debug, Clone, Default)]
+pub struct ComparisonReport {
+    pub by_method: HashMap<DetectionMethod, MethodReport>,
+}
+
+/// Whether a fill rests in the book (`Limit` - no slippage, maker fee) or
+/// crosses the spread to fill immediately (`Market` - full slippage, taker
+/// fee). A take-profit is a resting order at a known price, so it fills as
+/// a `Limit`; everything else fills as a `Market`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OrderType {
+    Limit,
+    Market,
+}
+
+/// Position currently open in the simulation, fill prices already include slippage
+struct OpenPosition {
+    entry_bar: usize,
+    entry_price: f64,
+    entry_order: OrderType,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    strategy: ActiveStrategy,
+    regime: MarketRegime,
+}
+
+/// Replay `candles` for `symbol` through a fresh `EnhancedRouter` for each
+/// of `DetectionMethod::Indicators`/`HMM`/`Ensemble`, under identical fee,
+/// slippage, and stop-model assumptions
+pub fn run_comparison(symbol: &str, candles: &[Candle], config: &ComparisonConfig) -> ComparisonReport {
+    let mut by_method = HashMap::new();
+
+    for (method, mut router) in [
+        (DetectionMethod::Indicators, EnhancedRouter::with_indicators()),
+        (DetectionMethod::HMM, EnhancedRouter::with_hmm()),
+        (DetectionMethod::Ensemble, EnhancedRouter::with_ensemble()),
+    ] {
+        by_method.insert(method, run_one(&mut router, symbol, candles, config));
+    }
+
+    ComparisonReport { by_method }
+}
+
+fn run_one(
+    router: &mut EnhancedRouter,
+    symbol: &str,
+    candles: &[Candle],
+    config: &ComparisonConfig,
+) -> MethodReport {
+    let mut trades = Vec::new();
+    let mut position: Option<OpenPosition> = None;
+    let mut equity_base = 1.0_f64;
+    let mut equity_curve = Vec::with_capacity(candles.len());
+
+    for (bar, candle) in candles.iter().enumerate() {
+        let Some(signal) = router.update(symbol, candle.high, candle.low, candle.close) else {
+            equity_curve.push(mark_to_market(&position, equity_base, candle.close));
+            continue;
+        };
+
+        let (stop_loss, take_profit) = stop_levels(router, symbol, &signal, candle.close, config);
+
+        if let Some(pos) = &position {
+            if let Some(stop) = pos.stop_loss {
+                if candle.low <= stop {
+                    let (trade, realized) = close_trade(config, pos, bar, stop, OrderType::Market);
+                    equity_base *= 1.0 + realized;
+                    trades.push(trade);
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            if let Some(target) = pos.take_profit {
+                if candle.high >= target {
+                    let (trade, realized) = close_trade(config, pos, bar, target, OrderType::Limit);
+                    equity_base *= 1.0 + realized;
+                    trades.push(trade);
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            if signal.strategy == ActiveStrategy::NoTrade && signal.signal != Signal::Sell {
+                let (trade, realized) = close_trade(config, pos, bar, candle.close, OrderType::Market);
+                equity_base *= 1.0 + realized;
+                trades.push(trade);
+                position = None;
+            }
+        }
+
+        match signal.signal {
+            Signal::Buy if position.is_none() => {
+                position = Some(OpenPosition {
+                    entry_bar: bar,
+                    entry_price: entry_fill(config, candle.close, OrderType::Market),
+                    entry_order: OrderType::Market,
+                    stop_loss,
+                    take_profit,
+                    strategy: signal.strategy,
+                    regime: signal.regime,
+                });
+            }
+            Signal::Sell if position.is_some() => {
+                if let Some(pos) = &position {
+                    let (trade, realized) = close_trade(config, pos, bar, candle.close, OrderType::Market);
+                    equity_base *= 1.0 + realized;
+                    trades.push(trade);
+                }
+                position = None;
+            }
+            _ => {}
+        }
+
+        equity_curve.push(mark_to_market(&position, equity_base, candle.close));
+    }
+
+    if let Some(pos) = &position {
+        let last_bar = candles.len().saturating_sub(1);
+        let last_price = candles.last().map(|c| c.close).unwrap_or(pos.entry_price);
+        let (trade, realized) = close_trade(config, pos, last_bar, last_price, OrderType::Market);
+        equity_base *= 1.0 + realized;
+        trades.push(trade);
+        if let Some(last) = equity_curve.last_mut() {
+            *last = equity_base;
+        }
+    }
+
+    let metrics = compute_metrics(config, &trades, &equity_curve);
+    MethodReport {
+        trades,
+        equity_curve,
+        metrics,
+    }
+}
+
+/// `signal`'s own stop/take-profit, or - when `config.use_atr_stop_model`
+/// is on - levels derived from `RecommendedStrategy::risk_parameters()` for
+/// `signal.regime` applied to the detector's live ATR.
+fn stop_levels(
+    router: &EnhancedRouter,
+    symbol: &str,
+    signal: &crate::strategy::enhanced_router::EnhancedSignal,
+    close: f64,
+    config: &ComparisonConfig,
+) -> (Option<f64>, Option<f64>) {
+    if !config.use_atr_stop_model || signal.strategy == ActiveStrategy::NoTrade {
+        return (signal.stop_loss, signal.take_profit);
+    }
+
+    let Some(risk) = RecommendedStrategy::from(&signal.regime).risk_parameters() else {
+        return (signal.stop_loss, signal.take_profit);
+    };
+    let atr = router.atr_value(symbol).unwrap_or(close * 0.02);
+    let stop_distance = risk.stop_atr_multiple * atr;
+    let take_profit_distance = stop_distance * risk.reward_risk_ratio;
+
+    match signal.signal {
+        Signal::Sell => (Some(close + stop_distance), Some(close - take_profit_distance)),
+        _ => (Some(close - stop_distance), Some(close + take_profit_distance)),
+    }
+}
+
+fn entry_fill(config: &ComparisonConfig, price: f64, order_type: OrderType) -> f64 {
+    match order_type {
+        OrderType::Market => price * (1.0 + config.slippage_bps / 10_000.0),
+        OrderType::Limit => price,
+    }
+}
+
+fn exit_fill(config: &ComparisonConfig, price: f64, order_type: OrderType) -> f64 {
+    match order_type {
+        OrderType::Market => price * (1.0 - config.slippage_bps / 10_000.0),
+        OrderType::Limit => price,
+    }
+}
+
+fn fee_bps(config: &ComparisonConfig, order_type: OrderType) -> f64 {
+    match order_type {
+        OrderType::Market => config.taker_fee_bps,
+        OrderType::Limit => config.maker_fee_bps,
+    }
+}
+
+/// Apply slippage and fees (maker or taker, depending on `exit_order`) to a
+/// raw exit price, returning the closed trade and its net realized return
+fn close_trade(
+    config: &ComparisonConfig,
+    pos: &OpenPosition,
+    exit_bar: usize,
+    exit_price_raw: f64,
+    exit_order: OrderType,
+) -> (ComparisonTrade, f64) {
+    let exit_price = exit_fill(config, exit_price_raw, exit_order);
+    let gross_return = (exit_price - pos.entry_price) / pos.entry_price;
+    let round_trip_fees = (fee_bps(config, pos.entry_order) + fee_bps(config, exit_order)) / 10_000.0;
+    let net_return = gross_return - round_trip_fees;
+
+    let trade = ComparisonTrade {
+        entry_bar: pos.entry_bar,
+        exit_bar,
+        entry_price: pos.entry_price,
+        exit_price,
+        duration_bars: exit_bar.saturating_sub(pos.entry_bar),
+        return_pct: net_return,
+        strategy: pos.strategy,
+        regime: pos.regime,
+    };
+    (trade, net_return)
+}
+
+fn mark_to_market(position: &Option<OpenPosition>, equity_base: f64, mark_price: f64) -> f64 {
+    match position {
+        Some(pos) => {
+            let unrealized = (mark_price - pos.entry_price) / pos.entry_price;
+            equity_base * (1.0 + unrealized)
+        }
+        None => equity_base,
+    }
+}
+
+fn compute_metrics(config: &ComparisonConfig, trades: &[ComparisonTrade], equity_curve: &[f64]) -> ComparisonMetrics {
+    let final_equity = equity_curve.last().copied().unwrap_or(1.0);
+    let total_return_pct = final_equity - 1.0;
+
+    let bar_returns: Vec<f64> = equity_curve
+        .windows(2)
+        .map(|w| if w[0] > 0.0 { w[1] / w[0] - 1.0 } else { 0.0 })
+        .collect();
+    let mean_return = mean(&bar_returns);
+    let return_std = std_dev(&bar_returns, mean_return);
+    let sharpe_ratio = if return_std > 0.0 {
+        mean_return / return_std * config.bars_per_year.sqrt()
+    } else {
+        0.0
+    };
+
+    let gross_profit: f64 = trades.iter().filter(|t| t.return_pct > 0.0).map(|t| t.return_pct).sum();
+    let gross_loss: f64 = trades
+        .iter()
+        .filter(|t| t.return_pct < 0.0)
+        .map(|t| t.return_pct.abs())
+        .sum();
+    let profit_factor = if gross_loss > 0.0 {
+        gross_profit / gross_loss
+    } else if gross_profit > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+
+    let wins = trades.iter().filter(|t| t.return_pct > 0.0).count();
+    let win_rate = if trades.is_empty() { 0.0 } else { wins as f64 / trades.len() as f64 };
+    let avg_trade_duration_bars = mean(&trades.iter().map(|t| t.duration_bars as f64).collect::<Vec<_>>());
+
+    ComparisonMetrics {
+        total_return_pct,
+        sharpe_ratio,
+        profit_factor,
+        max_drawdown_pct: max_drawdown(equity_curve),
+        win_rate,
+        avg_trade_duration_bars,
+        by_regime: regime_attribution(trades),
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn std_dev(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt()
+}
+
+fn max_drawdown(equity_curve: &[f64]) -> f64 {
+    let mut peak = equity_curve.first().copied().unwrap_or(1.0);
+    let mut max_dd = 0.0_f64;
+    for &equity in equity_curve {
+        peak = peak.max(equity);
+        max_dd = max_dd.max((peak - equity) / peak);
+    }
+    max_dd
+}
+
+fn regime_attribution(trades: &[ComparisonTrade]) -> HashMap<MarketRegime, ComparisonGroupStats> {
+    let mut by_regime: HashMap<MarketRegime, Vec<&ComparisonTrade>> = HashMap::new();
+    for trade in trades {
+        by_regime.entry(trade.regime).or_default().push(trade);
+    }
+
+    by_regime
+        .into_iter()
+        .map(|(regime, group)| {
+            let mut stats = ComparisonGroupStats::default();
+            stats.record(&group);
+            (regime, stats)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(ts: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: ts,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_comparison_report_covers_every_method() {
+        let report = run_comparison("BTC/USD", &[], &ComparisonConfig::default());
+        assert_eq!(report.by_method.len(), 3);
+        for method in report.by_method.values() {
+            assert!(method.trades.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_comparison_runs_all_three_methods_on_trending_data() {
+        let mut candles = Vec::new();
+        let mut price = 50000.0;
+        for i in 0..300 {
+            price += 15.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+
+        let report = run_comparison("BTC/USD", &candles, &ComparisonConfig::default());
+
+        assert!(report.by_method.contains_key(&DetectionMethod::Indicators));
+        assert!(report.by_method.contains_key(&DetectionMethod::HMM));
+        assert!(report.by_method.contains_key(&DetectionMethod::Ensemble));
+        for method in report.by_method.values() {
+            for trade in &method.trades {
+                assert!(trade.exit_bar >= trade.entry_bar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fees_and_slippage_drag_on_a_round_trip() {
+        let config = ComparisonConfig::default();
+        let pos = OpenPosition {
+            entry_bar: 0,
+            entry_price: entry_fill(&config, 100.0, OrderType::Market),
+            entry_order: OrderType::Market,
+            stop_loss: None,
+            take_profit: None,
+            strategy: ActiveStrategy::TrendFollowing,
+            regime: MarketRegime::Trending(crate::regime::TrendDirection::Bullish),
+        };
+
+        let (_trade, realized) = close_trade(&config, &pos, 10, 100.0, OrderType::Market);
+        assert!(realized < 0.0);
+    }
+
+    #[test]
+    fn test_max_drawdown_measures_peak_to_trough() {
+        let curve = vec![1.0, 1.1, 1.0, 0.9, 0.95, 1.2];
+        assert!((max_drawdown(&curve) - (1.1 - 0.9) / 1.1).abs() < 1e-9);
+    }
+}
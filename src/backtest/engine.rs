@@ -0,0 +1,511 @@
+//! Fee/slippage-aware backtest engine with risk-adjusted metrics
+//!
+//! `run_backtest` reports trades and simple compounded group stats, which is
+//! enough to validate a signal but not enough to judge whether a strategy is
+//! fundable: it ignores exchange fees and slippage, and stops short of the
+//! risk-adjusted metrics (Sharpe, Sortino, CAGR, drawdown duration) any real
+//! evaluation needs. `Engine` replays the same `StrategyRouter` loop but
+//! marks the position to market every bar, so a full equity curve - and the
+//! metrics derived from it - fall out of a single pass.
+
+use std::collections::HashMap;
+
+use crate::integration::Candle;
+use crate::regime::MarketRegime;
+use crate::strategy::mean_reversion::Signal;
+use crate::strategy::router::StrategyRouter;
+
+use super::{ExitReason, GroupStats, Trade};
+
+/// Trading costs and the annualization basis for `Engine::run`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineConfig {
+    /// Fee charged on a resting (maker) fill, in basis points of notional
+    pub maker_fee_bps: f64,
+    /// Fee charged on a market (taker) fill, in basis points of notional
+    pub taker_fee_bps: f64,
+    /// Adverse price movement applied to every fill, in basis points
+    pub slippage_bps: f64,
+    /// Bars per year, used to annualize CAGR/Sharpe/Sortino (e.g. 35,040 for
+    /// 15-minute candles)
+    pub bars_per_year: f64,
+}
+
+impl Default for EngineConfig {
+    /// Kraken's default spot fee schedule (0.16%/0.26% maker/taker), 5bps of
+    /// slippage, and 15-minute bars
+    fn default() -> Self {
+        Self {
+            maker_fee_bps: 16.0,
+            taker_fee_bps: 26.0,
+            slippage_bps: 5.0,
+            bars_per_year: 365.0 * 24.0 * 4.0,
+        }
+    }
+}
+
+/// Risk-adjusted performance derived from an `Engine::run` equity curve
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// Total compounded return across the whole run, e.g. 0.15 for +15%
+    pub total_return_pct: f64,
+    /// Annualized return implied by `total_return_pct` over the run's length
+    pub cagr_pct: f64,
+    /// `mean(r) / std(r) * sqrt(bars_per_year)` over per-bar returns
+    pub sharpe_ratio: f64,
+    /// Like `sharpe_ratio`, but the denominator is the standard deviation of
+    /// only the negative per-bar returns
+    pub sortino_ratio: f64,
+    /// Gross profit divided by gross loss across all trades
+    pub profit_factor: f64,
+    pub max_drawdown_pct: f64,
+    pub max_drawdown_duration_bars: usize,
+    pub avg_win_pct: f64,
+    pub avg_loss_pct: f64,
+    pub trade_count: usize,
+    /// Fraction of trades with `return_pct > 0.0`, `0.0` on a trade-free run
+    pub win_rate: f64,
+    /// Per-regime P&L attribution, same shape as `BacktestReport::by_regime`
+    pub by_regime: HashMap<MarketRegime, GroupStats>,
+}
+
+/// Trades, equity curve, and derived `Metrics` from an `Engine::run`
+#[derive(Debug, Clone, Default)]
+pub struct EngineReport {
+    pub trades: Vec<Trade>,
+    /// Equity after each bar, normalized to start at 1.0 and marked to
+    /// market while a position is open
+    pub equity_curve: Vec<f64>,
+    pub metrics: Metrics,
+}
+
+/// Whether a fill rests in the book (`Limit` - no slippage, maker fee) or
+/// crosses the spread to fill immediately (`Market` - full slippage, taker
+/// fee). A take-profit is a resting order at a known price, so it fills as
+/// a `Limit`; everything else (entries, stop-losses, regime/signal exits)
+/// needs to act on the current bar right away and fills as a `Market`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OrderType {
+    Limit,
+    Market,
+}
+
+/// Position currently open in the simulation, fill prices already include
+/// slippage
+struct OpenPosition {
+    entry_bar: usize,
+    entry_price: f64,
+    entry_order: OrderType,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    strategy: crate::strategy::router::ActiveStrategy,
+    regime: MarketRegime,
+}
+
+/// Replays candles through a `StrategyRouter` with fees and slippage applied
+/// to every fill, producing a bar-by-bar equity curve and `Metrics`
+#[derive(Debug, Clone, Default)]
+pub struct Engine {
+    config: EngineConfig,
+}
+
+impl Engine {
+    pub fn new(config: EngineConfig) -> Self {
+        Self { config }
+    }
+
+    /// Replay `candles` for `symbol` through `router`
+    pub fn run(&self, router: &mut StrategyRouter, symbol: &str, candles: &[Candle]) -> EngineReport {
+        let mut trades = Vec::new();
+        let mut position: Option<OpenPosition> = None;
+        let mut equity_base = 1.0_f64;
+        let mut equity_curve = Vec::with_capacity(candles.len());
+
+        for (bar, candle) in candles.iter().enumerate() {
+            let Some(routed) = router.update(symbol, candle.high, candle.low, candle.close) else {
+                equity_curve.push(self.mark_to_market(&position, equity_base, candle.close));
+                continue;
+            };
+
+            if let Some(pos) = &position {
+                if let Some(stop) = pos.stop_loss {
+                    if candle.low <= stop {
+                        let (trade, realized) =
+                            self.close_trade(pos, bar, stop, ExitReason::StopLoss, OrderType::Market);
+                        equity_base *= 1.0 + realized;
+                        trades.push(trade);
+                        position = None;
+                    }
+                }
+            }
+            if let Some(pos) = &position {
+                if let Some(target) = pos.take_profit {
+                    if candle.high >= target {
+                        let (trade, realized) =
+                            self.close_trade(pos, bar, target, ExitReason::TakeProfit, OrderType::Limit);
+                        equity_base *= 1.0 + realized;
+                        trades.push(trade);
+                        position = None;
+                    }
+                }
+            }
+            if let Some(pos) = &position {
+                if routed.source_strategy != pos.strategy && routed.signal != Signal::Sell {
+                    let (trade, realized) =
+                        self.close_trade(pos, bar, candle.close, ExitReason::RegimeExit, OrderType::Market);
+                    equity_base *= 1.0 + realized;
+                    trades.push(trade);
+                    position = None;
+                }
+            }
+
+            match routed.signal {
+                Signal::Buy if position.is_none() => {
+                    position = Some(OpenPosition {
+                        entry_bar: bar,
+                        entry_price: self.entry_fill(candle.close, OrderType::Market),
+                        entry_order: OrderType::Market,
+                        stop_loss: routed.stop_loss,
+                        take_profit: routed.take_profit,
+                        strategy: routed.source_strategy,
+                        regime: routed.regime,
+                    });
+                }
+                Signal::Sell if position.is_some() => {
+                    if let Some(pos) = &position {
+                        let (trade, realized) = self.close_trade(
+                            pos,
+                            bar,
+                            candle.close,
+                            ExitReason::SignalReversal,
+                            OrderType::Market,
+                        );
+                        equity_base *= 1.0 + realized;
+                        trades.push(trade);
+                    }
+                    position = None;
+                }
+                _ => {}
+            }
+
+            equity_curve.push(self.mark_to_market(&position, equity_base, candle.close));
+        }
+
+        if let Some(pos) = &position {
+            let last_bar = candles.len().saturating_sub(1);
+            let last_price = candles.last().map(|c| c.close).unwrap_or(pos.entry_price);
+            let (trade, realized) =
+                self.close_trade(pos, last_bar, last_price, ExitReason::EndOfData, OrderType::Market);
+            equity_base *= 1.0 + realized;
+            trades.push(trade);
+            if let Some(last) = equity_curve.last_mut() {
+                *last = equity_base;
+            }
+        }
+
+        let metrics = self.compute_metrics(&trades, &equity_curve);
+        EngineReport {
+            trades,
+            equity_curve,
+            metrics,
+        }
+    }
+
+    fn entry_fill(&self, price: f64, order_type: OrderType) -> f64 {
+        match order_type {
+            OrderType::Market => price * (1.0 + self.config.slippage_bps / 10_000.0),
+            OrderType::Limit => price,
+        }
+    }
+
+    fn exit_fill(&self, price: f64, order_type: OrderType) -> f64 {
+        match order_type {
+            OrderType::Market => price * (1.0 - self.config.slippage_bps / 10_000.0),
+            OrderType::Limit => price,
+        }
+    }
+
+    fn fee_bps(&self, order_type: OrderType) -> f64 {
+        match order_type {
+            OrderType::Market => self.config.taker_fee_bps,
+            OrderType::Limit => self.config.maker_fee_bps,
+        }
+    }
+
+    /// Apply slippage and fees (maker or taker, depending on `exit_order`)
+    /// to a raw exit price, returning the closed `Trade` and its net
+    /// realized return
+    fn close_trade(
+        &self,
+        pos: &OpenPosition,
+        exit_bar: usize,
+        exit_price_raw: f64,
+        reason: ExitReason,
+        exit_order: OrderType,
+    ) -> (Trade, f64) {
+        let exit_price = self.exit_fill(exit_price_raw, exit_order);
+        let gross_return = (exit_price - pos.entry_price) / pos.entry_price;
+        let round_trip_fees = (self.fee_bps(pos.entry_order) + self.fee_bps(exit_order)) / 10_000.0;
+        let net_return = gross_return - round_trip_fees;
+
+        let trade = Trade {
+            entry_bar: pos.entry_bar,
+            exit_bar,
+            entry_price: pos.entry_price,
+            exit_price,
+            duration_bars: exit_bar.saturating_sub(pos.entry_bar),
+            return_pct: net_return,
+            strategy: pos.strategy,
+            regime: pos.regime,
+            exit_reason: reason,
+        };
+        (trade, net_return)
+    }
+
+    fn mark_to_market(&self, position: &Option<OpenPosition>, equity_base: f64, mark_price: f64) -> f64 {
+        match position {
+            Some(pos) => {
+                let unrealized = (mark_price - pos.entry_price) / pos.entry_price;
+                equity_base * (1.0 + unrealized)
+            }
+            None => equity_base,
+        }
+    }
+
+    fn compute_metrics(&self, trades: &[Trade], equity_curve: &[f64]) -> Metrics {
+        let final_equity = equity_curve.last().copied().unwrap_or(1.0);
+        let total_return_pct = final_equity - 1.0;
+
+        let years = equity_curve.len() as f64 / self.config.bars_per_year;
+        let cagr_pct = if years > 0.0 && final_equity > 0.0 {
+            final_equity.powf(1.0 / years) - 1.0
+        } else {
+            0.0
+        };
+
+        let bar_returns: Vec<f64> = equity_curve
+            .windows(2)
+            .map(|w| if w[0] > 0.0 { w[1] / w[0] - 1.0 } else { 0.0 })
+            .collect();
+        let mean_return = mean(&bar_returns);
+        let return_std = std_dev(&bar_returns, mean_return);
+        let sharpe_ratio = if return_std > 0.0 {
+            mean_return / return_std * self.config.bars_per_year.sqrt()
+        } else {
+            0.0
+        };
+
+        let downside_returns: Vec<f64> = bar_returns.iter().copied().filter(|&r| r < 0.0).collect();
+        let downside_std = std_dev(&downside_returns, mean(&downside_returns));
+        let sortino_ratio = if downside_std > 0.0 {
+            mean_return / downside_std * self.config.bars_per_year.sqrt()
+        } else {
+            0.0
+        };
+
+        let gross_profit: f64 = trades.iter().filter(|t| t.return_pct > 0.0).map(|t| t.return_pct).sum();
+        let gross_loss: f64 = trades
+            .iter()
+            .filter(|t| t.return_pct < 0.0)
+            .map(|t| t.return_pct.abs())
+            .sum();
+        let profit_factor = if gross_loss > 0.0 {
+            gross_profit / gross_loss
+        } else if gross_profit > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+
+        let (max_drawdown_pct, max_drawdown_duration_bars) = drawdown_stats(equity_curve);
+
+        let wins: Vec<f64> = trades.iter().filter(|t| t.return_pct > 0.0).map(|t| t.return_pct).collect();
+        let losses: Vec<f64> = trades.iter().filter(|t| t.return_pct < 0.0).map(|t| t.return_pct).collect();
+        let win_rate = if trades.is_empty() { 0.0 } else { wins.len() as f64 / trades.len() as f64 };
+
+        Metrics {
+            total_return_pct,
+            cagr_pct,
+            sharpe_ratio,
+            sortino_ratio,
+            profit_factor,
+            max_drawdown_pct,
+            max_drawdown_duration_bars,
+            avg_win_pct: mean(&wins),
+            avg_loss_pct: mean(&losses),
+            trade_count: trades.len(),
+            win_rate,
+            by_regime: regime_attribution(trades),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn std_dev(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt()
+}
+
+/// Max peak-to-trough decline in the equity curve and how many bars the
+/// drawdown took to reach its trough
+fn drawdown_stats(equity_curve: &[f64]) -> (f64, usize) {
+    if equity_curve.is_empty() {
+        return (0.0, 0);
+    }
+
+    let mut peak = equity_curve[0];
+    let mut peak_bar = 0;
+    let mut max_drawdown = 0.0_f64;
+    let mut max_drawdown_duration = 0;
+
+    for (bar, &equity) in equity_curve.iter().enumerate() {
+        if equity > peak {
+            peak = equity;
+            peak_bar = bar;
+        }
+        let drawdown = (peak - equity) / peak;
+        if drawdown > max_drawdown {
+            max_drawdown = drawdown;
+            max_drawdown_duration = bar - peak_bar;
+        }
+    }
+
+    (max_drawdown, max_drawdown_duration)
+}
+
+fn regime_attribution(trades: &[Trade]) -> HashMap<MarketRegime, GroupStats> {
+    let mut by_regime: HashMap<MarketRegime, Vec<&Trade>> = HashMap::new();
+    for trade in trades {
+        by_regime.entry(trade.regime).or_default().push(trade);
+    }
+
+    by_regime
+        .into_iter()
+        .map(|(regime, group)| {
+            let mut stats = GroupStats::default();
+            stats.record(&group);
+            (regime, stats)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::router::StrategyRouterConfig;
+
+    fn candle(ts: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: ts,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_run_has_zeroed_metrics() {
+        let mut router = StrategyRouter::new(StrategyRouterConfig::default());
+        let engine = Engine::new(EngineConfig::default());
+        let report = engine.run(&mut router, "BTC/USD", &[]);
+
+        assert!(report.trades.is_empty());
+        assert!(report.equity_curve.is_empty());
+        assert_eq!(report.metrics.total_return_pct, 0.0);
+        assert_eq!(report.metrics.profit_factor, 0.0);
+        assert_eq!(report.metrics.trade_count, 0);
+        assert_eq!(report.metrics.win_rate, 0.0);
+    }
+
+    #[test]
+    fn test_equity_curve_has_one_entry_per_bar() {
+        let mut router = StrategyRouter::new(StrategyRouterConfig::default());
+        let engine = Engine::new(EngineConfig::default());
+        let mut candles = Vec::new();
+        let mut price = 50000.0;
+        for i in 0..300 {
+            price += 15.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+
+        let report = engine.run(&mut router, "BTC/USD", &candles);
+        assert_eq!(report.equity_curve.len(), candles.len());
+    }
+
+    #[test]
+    fn test_fees_and_slippage_drag_on_a_round_trip() {
+        let config = EngineConfig {
+            maker_fee_bps: 16.0,
+            taker_fee_bps: 26.0,
+            slippage_bps: 5.0,
+            bars_per_year: 35_040.0,
+        };
+        let engine = Engine::new(config);
+        let pos = OpenPosition {
+            entry_bar: 0,
+            entry_price: engine.entry_fill(100.0, OrderType::Market),
+            entry_order: OrderType::Market,
+            stop_loss: None,
+            take_profit: None,
+            strategy: crate::strategy::router::ActiveStrategy::TrendFollowing,
+            regime: MarketRegime::Trending(crate::regime::TrendDirection::Bullish),
+        };
+
+        let (_trade, realized) =
+            engine.close_trade(&pos, 10, 100.0, ExitReason::SignalReversal, OrderType::Market);
+        // Flat round-trip at the same raw price should lose money to fees + slippage
+        assert!(realized < 0.0);
+    }
+
+    #[test]
+    fn test_take_profit_fills_as_a_limit_order_at_the_maker_fee() {
+        let config = EngineConfig {
+            maker_fee_bps: 16.0,
+            taker_fee_bps: 26.0,
+            slippage_bps: 5.0,
+            bars_per_year: 35_040.0,
+        };
+        let engine = Engine::new(config);
+        let pos = OpenPosition {
+            entry_bar: 0,
+            entry_price: engine.entry_fill(100.0, OrderType::Market),
+            entry_order: OrderType::Market,
+            stop_loss: None,
+            take_profit: Some(110.0),
+            strategy: crate::strategy::router::ActiveStrategy::TrendFollowing,
+            regime: MarketRegime::Trending(crate::regime::TrendDirection::Bullish),
+        };
+
+        let (limit_trade, limit_return) =
+            engine.close_trade(&pos, 10, 110.0, ExitReason::TakeProfit, OrderType::Limit);
+        let (market_trade, market_return) =
+            engine.close_trade(&pos, 10, 110.0, ExitReason::StopLoss, OrderType::Market);
+
+        // Same raw exit price, but the limit fill skips slippage and pays
+        // the cheaper maker fee, so it nets strictly more than a market fill.
+        assert!(limit_return > market_return);
+        assert_eq!(limit_trade.exit_price, 110.0);
+        assert!(market_trade.exit_price < 110.0);
+    }
+
+    #[test]
+    fn test_max_drawdown_duration_measures_peak_to_trough() {
+        let curve = vec![1.0, 1.1, 1.0, 0.9, 0.95, 1.2];
+        let (max_dd, duration) = drawdown_stats(&curve);
+        assert!((max_dd - (1.1 - 0.9) / 1.1).abs() < 1e-9);
+        assert_eq!(duration, 2);
+    }
+}
@@ -0,0 +1,284 @@
+//! Enhanced Backtesting Engine
+//!
+//! Mirrors `backtest::run_backtest` but replays through `EnhancedRouter`
+//! instead of the original `StrategyRouter`, so the exact signal stream that
+//! drives `examples/live_trading.rs` also drives the backtest. Adds
+//! per-regime P&L attribution so `DetectionMethod::HMM`/`Ensemble` can be
+//! judged against `DetectionMethod::Indicators` on identical data instead of
+//! taken on faith.
+
+use crate::integration::Candle;
+use crate::regime::MarketRegime;
+use crate::strategy::enhanced_router::{ActiveStrategy, EnhancedRouter};
+use crate::strategy::mean_reversion::Signal;
+use std::collections::HashMap;
+
+/// Why an enhanced-backtest position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// `take_profit` level touched intrabar
+    TakeProfit,
+    /// `stop_loss` level touched intrabar
+    StopLoss,
+    /// The router's active strategy flipped to `NoTrade` - confidence fell
+    /// below `min_confidence`, or the regime became `Squeeze` or `Uncertain`
+    RegimeFlipToNoTrade,
+    /// An opposite signal (Sell while long) closed the position
+    OppositeSignal,
+    /// Historical data ran out with a position still open
+    EndOfData,
+}
+
+/// A single closed trade
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub entry_bar: usize,
+    pub exit_bar: usize,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub duration_bars: usize,
+    /// Price return, e.g. 0.02 for +2%
+    pub return_pct: f64,
+    pub strategy: ActiveStrategy,
+    /// Regime in effect when the position was opened
+    pub regime: MarketRegime,
+    pub exit_reason: ExitReason,
+}
+
+/// Aggregated performance for one grouping (all trades, an exit reason, or a regime)
+#[derive(Debug, Clone, Default)]
+pub struct GroupStats {
+    pub trades: u32,
+    pub wins: u32,
+    pub losses: u32,
+    /// Simple (non-compounded) sum of each trade's `return_pct`
+    pub total_return_pct: f64,
+    pub avg_return_pct: f64,
+    pub median_duration_bars: f64,
+}
+
+impl GroupStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.trades as f64
+    }
+
+    fn record(&mut self, trades: &[&Trade]) {
+        self.trades = trades.len() as u32;
+        if self.trades == 0 {
+            return;
+        }
+
+        self.wins = trades.iter().filter(|t| t.return_pct > 0.0).count() as u32;
+        self.losses = self.trades - self.wins;
+        self.total_return_pct = trades.iter().map(|t| t.return_pct).sum();
+        self.avg_return_pct = self.total_return_pct / self.trades as f64;
+        self.median_duration_bars = median(trades.iter().map(|t| t.duration_bars as f64).collect());
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Per-symbol enhanced backtest output
+#[derive(Debug, Clone, Default)]
+pub struct EnhancedBacktestReport {
+    pub symbol: String,
+    pub trades: Vec<Trade>,
+    pub overall: GroupStats,
+    pub by_exit_reason: HashMap<ExitReason, GroupStats>,
+    /// P&L attribution by the regime in effect when each trade opened -
+    /// shows whether HMM/Ensemble routing is adding value over raw indicators
+    pub by_regime: HashMap<MarketRegime, GroupStats>,
+}
+
+/// Position currently open in the simulation
+struct OpenPosition {
+    entry_bar: usize,
+    entry_price: f64,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    strategy: ActiveStrategy,
+    regime: MarketRegime,
+}
+
+/// Replay `candles` for `symbol` through `router` and produce a trade report
+pub fn run_enhanced_backtest(
+    router: &mut EnhancedRouter,
+    symbol: &str,
+    candles: &[Candle],
+) -> EnhancedBacktestReport {
+    let mut trades = Vec::new();
+    let mut position: Option<OpenPosition> = None;
+
+    for (bar, candle) in candles.iter().enumerate() {
+        let Some(signal) = router.update(symbol, candle.high, candle.low, candle.close) else {
+            continue;
+        };
+
+        // Check intrabar stop/target before anything else - a trade can't
+        // survive past the level it was designed to exit at.
+        if let Some(pos) = &position {
+            if let Some(stop) = pos.stop_loss {
+                if candle.low <= stop {
+                    trades.push(close_trade(pos, bar, stop, ExitReason::StopLoss));
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            if let Some(target) = pos.take_profit {
+                if candle.high >= target {
+                    trades.push(close_trade(pos, bar, target, ExitReason::TakeProfit));
+                    position = None;
+                }
+            }
+        }
+
+        // Regime flip to NoTrade: the router lost confidence in the regime
+        // that the open position was sized for.
+        if let Some(pos) = &position {
+            if signal.strategy == ActiveStrategy::NoTrade && signal.signal != Signal::Sell {
+                trades.push(close_trade(pos, bar, candle.close, ExitReason::RegimeFlipToNoTrade));
+                position = None;
+            }
+        }
+
+        match signal.signal {
+            Signal::Buy if position.is_none() => {
+                position = Some(OpenPosition {
+                    entry_bar: bar,
+                    entry_price: candle.close,
+                    stop_loss: signal.stop_loss,
+                    take_profit: signal.take_profit,
+                    strategy: signal.strategy,
+                    regime: signal.regime,
+                });
+            }
+            Signal::Sell if position.is_some() => {
+                if let Some(pos) = &position {
+                    trades.push(close_trade(pos, bar, candle.close, ExitReason::OppositeSignal));
+                }
+                position = None;
+            }
+            _ => {}
+        }
+    }
+
+    // Anything left open at the end of the data closes at the last price
+    if let Some(pos) = &position {
+        let last_bar = candles.len().saturating_sub(1);
+        let last_price = candles.last().map(|c| c.close).unwrap_or(pos.entry_price);
+        trades.push(close_trade(pos, last_bar, last_price, ExitReason::EndOfData));
+    }
+
+    build_report(symbol, trades)
+}
+
+fn close_trade(pos: &OpenPosition, exit_bar: usize, exit_price: f64, reason: ExitReason) -> Trade {
+    Trade {
+        entry_bar: pos.entry_bar,
+        exit_bar,
+        entry_price: pos.entry_price,
+        exit_price,
+        duration_bars: exit_bar.saturating_sub(pos.entry_bar),
+        return_pct: (exit_price - pos.entry_price) / pos.entry_price,
+        strategy: pos.strategy,
+        regime: pos.regime,
+        exit_reason: reason,
+    }
+}
+
+fn build_report(symbol: &str, trades: Vec<Trade>) -> EnhancedBacktestReport {
+    let mut by_exit_reason: HashMap<ExitReason, Vec<&Trade>> = HashMap::new();
+    let mut by_regime: HashMap<MarketRegime, Vec<&Trade>> = HashMap::new();
+
+    for trade in &trades {
+        by_exit_reason.entry(trade.exit_reason).or_default().push(trade);
+        by_regime.entry(trade.regime).or_default().push(trade);
+    }
+
+    let mut overall = GroupStats::default();
+    overall.record(&trades.iter().collect::<Vec<_>>());
+
+    let by_exit_reason = by_exit_reason
+        .into_iter()
+        .map(|(k, v)| {
+            let mut stats = GroupStats::default();
+            stats.record(&v);
+            (k, stats)
+        })
+        .collect();
+
+    let by_regime = by_regime
+        .into_iter()
+        .map(|(k, v)| {
+            let mut stats = GroupStats::default();
+            stats.record(&v);
+            (k, stats)
+        })
+        .collect();
+
+    EnhancedBacktestReport {
+        symbol: symbol.to_string(),
+        trades,
+        overall,
+        by_exit_reason,
+        by_regime,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(ts: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: ts,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_enhanced_backtest_report() {
+        let mut router = EnhancedRouter::with_indicators();
+        let report = run_enhanced_backtest(&mut router, "BTC/USD", &[]);
+        assert!(report.trades.is_empty());
+        assert_eq!(report.overall.trades, 0);
+    }
+
+    #[test]
+    fn test_enhanced_backtest_on_trending_data() {
+        let mut router = EnhancedRouter::with_indicators();
+        let mut candles = Vec::new();
+        let mut price = 50000.0;
+        for i in 0..300 {
+            price += 15.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+
+        let report = run_enhanced_backtest(&mut router, "BTC/USD", &candles);
+
+        for trade in &report.trades {
+            assert!(trade.exit_bar >= trade.entry_bar);
+        }
+        assert_eq!(report.overall.trades as usize, report.trades.len());
+    }
+}
@@ -0,0 +1,424 @@
+//! Backtesting Engine
+//!
+//! Replays a historical OHLC series through `StrategyRouter` and produces a
+//! trade-level report, grouped by strategy and by regime, so the 20-40%
+//! regime-aware uplift claimed in the crate docs can be validated against
+//! real data instead of taken on faith.
+//!
+//! Modeled loosely on freqtrade's backtest output: a position opens on a
+//! `Buy` signal and closes when an opposite signal fires or the
+//! `stop_loss`/`take_profit` levels are touched intrabar. Each trade's
+//! return is the unsized price return; `position_size_factor` is not
+//! applied.
+
+use crate::integration::Candle;
+use crate::regime::MarketRegime;
+use crate::strategy::mean_reversion::Signal;
+use crate::strategy::router::{ActiveStrategy, StrategyRouter};
+use std::collections::{BTreeMap, HashMap};
+
+mod enhanced;
+pub use enhanced::{
+    run_enhanced_backtest, EnhancedBacktestReport, ExitReason as EnhancedExitReason,
+    GroupStats as EnhancedGroupStats, Trade as EnhancedTrade,
+};
+
+mod engine;
+pub use engine::{Engine, EngineConfig, EngineReport, Metrics};
+
+mod backtester;
+pub use backtester::{Backtester, BacktesterConfig};
+
+mod portfolio;
+pub use portfolio::{run_portfolio_backtest, PortfolioBacktestConfig, PortfolioBacktestReport, PortfolioTrade};
+
+mod comparison;
+pub use comparison::{
+    run_comparison, ComparisonConfig, ComparisonGroupStats, ComparisonMetrics, ComparisonReport,
+    ComparisonTrade, MethodReport,
+};
+
+/// Why a backtest position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// `take_profit` level touched intrabar
+    TakeProfit,
+    /// `stop_loss` level touched intrabar
+    StopLoss,
+    /// An opposite signal (Sell while long) closed the position
+    SignalReversal,
+    /// The regime moved away from the one that opened the position
+    RegimeExit,
+    /// `ExitPolicy::trailing_distance` level touched intrabar
+    TrailingStop,
+    /// `ExitPolicy::minimal_roi` threshold for the current holding duration was met
+    MinimalRoi,
+    /// Historical data ran out with a position still open
+    EndOfData,
+}
+
+/// Holding-duration (in bars) -> required return threshold, e.g.
+/// `{0: 0.05, 20: 0.02, 60: 0.0}` demands 5% profit to exit immediately,
+/// only 2% after 20 bars, and break-even or better after 60 bars
+pub type MinimalRoiTable = BTreeMap<usize, f64>;
+
+/// Trailing-stop and time-based ROI exit management, applied inside
+/// `run_backtest` on top of each position's own `stop_loss`/`take_profit`
+#[derive(Debug, Clone, Default)]
+pub struct ExitPolicy {
+    /// Unrealized profit (fraction of entry) that must be exceeded before
+    /// the trailing stop activates. `None` disables the trailing stop.
+    pub trailing_activation_offset: Option<f64>,
+    /// Once active, the trailing stop ratchets to `peak_price * (1 -
+    /// trailing_distance)` on every new high and never moves down
+    pub trailing_distance: f64,
+    /// See `MinimalRoiTable`. An empty table disables ROI-based exits.
+    pub minimal_roi: MinimalRoiTable,
+}
+
+impl ExitPolicy {
+    /// The required-return threshold for a position held `duration_bars`,
+    /// i.e. the value at the largest key not greater than `duration_bars`
+    fn roi_threshold(&self, duration_bars: usize) -> Option<f64> {
+        self.minimal_roi
+            .range(..=duration_bars)
+            .next_back()
+            .map(|(_, &threshold)| threshold)
+    }
+}
+
+/// A single closed trade
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub entry_bar: usize,
+    pub exit_bar: usize,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub duration_bars: usize,
+    /// Price return, e.g. 0.02 for +2%
+    pub return_pct: f64,
+    pub strategy: ActiveStrategy,
+    /// Regime in effect when the position was opened
+    pub regime: MarketRegime,
+    pub exit_reason: ExitReason,
+}
+
+/// Aggregated performance for one grouping (a strategy or a regime)
+#[derive(Debug, Clone, Default)]
+pub struct GroupStats {
+    pub trades: u32,
+    pub wins: u32,
+    /// Cumulative compounded return across trades in this group, e.g. 0.15 for +15%
+    pub cumulative_return_pct: f64,
+    pub avg_duration_bars: f64,
+    /// Max drawdown of the group's own equity curve, e.g. 0.08 for -8%
+    pub max_drawdown_pct: f64,
+}
+
+impl GroupStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.trades as f64
+    }
+
+    fn record(&mut self, trades: &[&Trade]) {
+        self.trades = trades.len() as u32;
+        if self.trades == 0 {
+            return;
+        }
+
+        self.wins = trades.iter().filter(|t| t.return_pct > 0.0).count() as u32;
+        self.avg_duration_bars = trades.iter().map(|t| t.duration_bars as f64).sum::<f64>()
+            / self.trades as f64;
+
+        let mut equity = 1.0;
+        let mut peak = equity;
+        let mut max_dd = 0.0_f64;
+        for t in trades {
+            equity *= 1.0 + t.return_pct;
+            peak = peak.max(equity);
+            max_dd = max_dd.max((peak - equity) / peak);
+        }
+        self.cumulative_return_pct = equity - 1.0;
+        self.max_drawdown_pct = max_dd;
+    }
+}
+
+/// Full backtest output
+#[derive(Debug, Clone, Default)]
+pub struct BacktestReport {
+    pub trades: Vec<Trade>,
+    pub by_strategy: HashMap<ActiveStrategy, GroupStats>,
+    pub by_regime: HashMap<MarketRegime, GroupStats>,
+}
+
+/// Position currently open in the simulation
+struct OpenPosition {
+    entry_bar: usize,
+    entry_price: f64,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    strategy: ActiveStrategy,
+    regime: MarketRegime,
+    /// Highest price seen since entry, used to ratchet the trailing stop
+    peak_price: f64,
+    /// Active trailing-stop level, once `ExitPolicy::trailing_activation_offset` is exceeded
+    trailing_stop: Option<f64>,
+}
+
+/// Replay `candles` for `symbol` through `router` and produce a trade
+/// report, applying `exit_policy`'s trailing stop and minimal-ROI exits on
+/// top of each position's own `stop_loss`/`take_profit`
+pub fn run_backtest(
+    router: &mut StrategyRouter,
+    symbol: &str,
+    candles: &[Candle],
+    exit_policy: &ExitPolicy,
+) -> BacktestReport {
+    let mut trades = Vec::new();
+    let mut position: Option<OpenPosition> = None;
+
+    for (bar, candle) in candles.iter().enumerate() {
+        let Some(routed) = router.update(symbol, candle.high, candle.low, candle.close) else {
+            continue;
+        };
+
+        // Ratchet the trailing stop on new highs before checking any exits
+        if let Some(pos) = &mut position {
+            if candle.high > pos.peak_price {
+                pos.peak_price = candle.high;
+            }
+            let unrealized = (pos.peak_price - pos.entry_price) / pos.entry_price;
+            let activated = exit_policy
+                .trailing_activation_offset
+                .is_some_and(|offset| unrealized >= offset);
+            if activated {
+                let candidate = pos.peak_price * (1.0 - exit_policy.trailing_distance);
+                pos.trailing_stop = Some(pos.trailing_stop.map_or(candidate, |current| current.max(candidate)));
+            }
+        }
+
+        // Check intrabar stop/target before anything else - a trade can't
+        // survive past the level it was designed to exit at.
+        if let Some(pos) = &position {
+            if let Some(stop) = pos.stop_loss {
+                if candle.low <= stop {
+                    trades.push(close_trade(pos, bar, stop, ExitReason::StopLoss));
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            if let Some(target) = pos.take_profit {
+                if candle.high >= target {
+                    trades.push(close_trade(pos, bar, target, ExitReason::TakeProfit));
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            if let Some(trailing) = pos.trailing_stop {
+                if candle.low <= trailing {
+                    trades.push(close_trade(pos, bar, trailing, ExitReason::TrailingStop));
+                    position = None;
+                }
+            }
+        }
+        if let Some(pos) = &position {
+            let duration_bars = bar.saturating_sub(pos.entry_bar);
+            if let Some(threshold) = exit_policy.roi_threshold(duration_bars) {
+                let unrealized = (candle.close - pos.entry_price) / pos.entry_price;
+                if unrealized >= threshold {
+                    trades.push(close_trade(pos, bar, candle.close, ExitReason::MinimalRoi));
+                    position = None;
+                }
+            }
+        }
+
+        // Regime exit: the regime driving the open strategy has moved on
+        if let Some(pos) = &position {
+            if routed.source_strategy != pos.strategy && routed.signal != Signal::Sell {
+                trades.push(close_trade(pos, bar, candle.close, ExitReason::RegimeExit));
+                position = None;
+            }
+        }
+
+        match routed.signal {
+            Signal::Buy if position.is_none() => {
+                position = Some(OpenPosition {
+                    entry_bar: bar,
+                    entry_price: candle.close,
+                    stop_loss: routed.stop_loss,
+                    take_profit: routed.take_profit,
+                    strategy: routed.source_strategy,
+                    regime: routed.regime,
+                    peak_price: candle.close,
+                    trailing_stop: None,
+                });
+            }
+            Signal::Sell if position.is_some() => {
+                if let Some(pos) = &position {
+                    trades.push(close_trade(pos, bar, candle.close, ExitReason::SignalReversal));
+                }
+                position = None;
+            }
+            _ => {}
+        }
+    }
+
+    // Anything left open at the end of the data closes at the last price
+    if let Some(pos) = &position {
+        let last_bar = candles.len().saturating_sub(1);
+        let last_price = candles.last().map(|c| c.close).unwrap_or(pos.entry_price);
+        trades.push(close_trade(pos, last_bar, last_price, ExitReason::EndOfData));
+    }
+
+    build_report(trades)
+}
+
+fn close_trade(pos: &OpenPosition, exit_bar: usize, exit_price: f64, reason: ExitReason) -> Trade {
+    Trade {
+        entry_bar: pos.entry_bar,
+        exit_bar,
+        entry_price: pos.entry_price,
+        exit_price,
+        duration_bars: exit_bar.saturating_sub(pos.entry_bar),
+        return_pct: (exit_price - pos.entry_price) / pos.entry_price,
+        strategy: pos.strategy,
+        regime: pos.regime,
+        exit_reason: reason,
+    }
+}
+
+fn build_report(trades: Vec<Trade>) -> BacktestReport {
+    let mut by_strategy: HashMap<ActiveStrategy, Vec<&Trade>> = HashMap::new();
+    let mut by_regime: HashMap<MarketRegime, Vec<&Trade>> = HashMap::new();
+
+    for trade in &trades {
+        by_strategy.entry(trade.strategy).or_default().push(trade);
+        by_regime.entry(trade.regime).or_default().push(trade);
+    }
+
+    let by_strategy = by_strategy
+        .into_iter()
+        .map(|(k, v)| {
+            let mut stats = GroupStats::default();
+            stats.record(&v);
+            (k, stats)
+        })
+        .collect();
+
+    let by_regime = by_regime
+        .into_iter()
+        .map(|(k, v)| {
+            let mut stats = GroupStats::default();
+            stats.record(&v);
+            (k, stats)
+        })
+        .collect();
+
+    BacktestReport {
+        trades,
+        by_strategy,
+        by_regime,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::router::StrategyRouterConfig;
+
+    fn candle(ts: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: ts,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_backtest_report() {
+        let mut router = StrategyRouter::new(StrategyRouterConfig::default());
+        let report = run_backtest(&mut router, "BTC/USD", &[], &ExitPolicy::default());
+        assert!(report.trades.is_empty());
+    }
+
+    #[test]
+    fn test_backtest_on_trending_data() {
+        let mut router = StrategyRouter::new(StrategyRouterConfig::default());
+        let mut candles = Vec::new();
+        let mut price = 50000.0;
+        for i in 0..300 {
+            price += 15.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+
+        let report = run_backtest(&mut router, "BTC/USD", &candles, &ExitPolicy::default());
+
+        // Every closed trade should have a non-negative duration and a
+        // categorized exit reason.
+        for trade in &report.trades {
+            assert!(trade.exit_bar >= trade.entry_bar);
+        }
+    }
+
+    #[test]
+    fn test_roi_threshold_picks_the_nearest_bucket_at_or_below_duration() {
+        let policy = ExitPolicy {
+            trailing_activation_offset: None,
+            trailing_distance: 0.0,
+            minimal_roi: [(0, 0.05), (20, 0.02), (60, 0.0)].into_iter().collect(),
+        };
+
+        assert_eq!(policy.roi_threshold(0), Some(0.05));
+        assert_eq!(policy.roi_threshold(19), Some(0.05));
+        assert_eq!(policy.roi_threshold(20), Some(0.02));
+        assert_eq!(policy.roi_threshold(100), Some(0.0));
+    }
+
+    #[test]
+    fn test_roi_threshold_is_none_below_every_bucket() {
+        let policy = ExitPolicy {
+            trailing_activation_offset: None,
+            trailing_distance: 0.0,
+            minimal_roi: [(10, 0.05)].into_iter().collect(),
+        };
+
+        assert_eq!(policy.roi_threshold(5), None);
+    }
+
+    #[test]
+    fn test_trailing_stop_exits_once_price_pulls_back_from_the_peak() {
+        let mut router = StrategyRouter::new(StrategyRouterConfig::default());
+        let policy = ExitPolicy {
+            trailing_activation_offset: Some(0.01),
+            trailing_distance: 0.05,
+            minimal_roi: MinimalRoiTable::new(),
+        };
+
+        let mut candles = Vec::new();
+        let mut price = 50000.0;
+        for i in 0..40 {
+            price += 50.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+        // Sharp pullback: low should touch the ratcheted trailing stop
+        for i in 40..50 {
+            price -= 300.0;
+            candles.push(candle(i as i64 * 900, price + 10.0, price - 10.0, price));
+        }
+
+        let report = run_backtest(&mut router, "BTC/USD", &candles, &policy);
+        assert!(report
+            .trades
+            .iter()
+            .any(|t| t.exit_reason == ExitReason::TrailingStop));
+    }
+}
@@ -0,0 +1,376 @@
+//! Parameter Optimization (Hyperopt)
+//!
+//! Searches over `StrategyRouterConfig` (and the `RegimeConfig` nested inside
+//! it) for settings that maximize a chosen objective on historical data,
+//! mirroring freqtrade's hyperopt: declare named parameter ranges, build a
+//! full config from a sampled point via a caller-supplied closure, backtest
+//! it with `run_backtest`, and score the resulting trades. Both grid search
+//! (exhaustive over each range's declared step) and random search (uniform
+//! sampling, seeded for reproducibility) are supported. An optional
+//! walk-forward split scores each candidate in-sample to pick the winner but
+//! reports its out-of-sample score too, so overfit configs are visible in
+//! the ranked trial table rather than hidden behind a single number.
+
+use crate::backtest::{run_backtest, BacktestReport, ExitPolicy};
+use crate::integration::Candle;
+use crate::strategy::router::{StrategyRouter, StrategyRouterConfig};
+use std::collections::HashMap;
+
+mod surrogate;
+pub use surrogate::{Estimator, Optimizer, OptimizerConfig, OptimizerResult};
+
+/// A named parameter and the range of values to search over
+#[derive(Debug, Clone)]
+pub struct ParamRange {
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    /// Step between grid points; ignored by `random_search`
+    pub step: f64,
+}
+
+impl ParamRange {
+    pub fn new(name: &str, min: f64, max: f64, step: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            min,
+            max,
+            step,
+        }
+    }
+
+    fn grid_values(&self) -> Vec<f64> {
+        if self.step <= 0.0 {
+            return vec![self.min];
+        }
+        let mut values = Vec::new();
+        let mut v = self.min;
+        while v <= self.max + f64::EPSILON {
+            values.push(v);
+            v += self.step;
+        }
+        values
+    }
+}
+
+/// A sampled parameter point, keyed by `ParamRange::name`
+pub type ParamPoint = HashMap<String, f64>;
+
+/// Builds a full router config from a sampled parameter point
+pub type ConfigBuilder = dyn Fn(&ParamPoint) -> StrategyRouterConfig;
+
+/// Objective function used to rank trials; higher is always better
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Sum of all trade returns
+    TotalReturn,
+    /// Mean return divided by its standard deviation across trades
+    Sharpe,
+    /// Total return divided by max drawdown (percentage points)
+    ReturnOverMaxDrawdown,
+    /// Gross profit over gross loss. Capped at `PROFIT_FACTOR_CAP` when there
+    /// are no losing trades rather than returning an unusable infinity.
+    ProfitFactor,
+}
+
+/// Sentinel returned by `Objective::ProfitFactor` when a trial has zero
+/// losing trades, so the surrogate regressors in `surrogate` never have to
+/// fit against an actual infinity
+const PROFIT_FACTOR_CAP: f64 = 1.0e6;
+
+impl Objective {
+    pub fn score(&self, report: &BacktestReport) -> f64 {
+        let returns: Vec<f64> = report.trades.iter().map(|t| t.return_pct).collect();
+        self.score_returns(&returns)
+    }
+
+    /// Score a raw sequence of trade returns directly, for callers (such as
+    /// `surrogate::Optimizer`, which backtests through `EnhancedRouter`
+    /// rather than `StrategyRouter`) that don't have a `BacktestReport`
+    pub fn score_returns(&self, returns: &[f64]) -> f64 {
+        match self {
+            Objective::TotalReturn => returns.iter().sum(),
+            Objective::Sharpe => sharpe_ratio(returns),
+            Objective::ReturnOverMaxDrawdown => {
+                let total_return: f64 = returns.iter().sum();
+                let max_dd = max_drawdown_pct(returns);
+                if max_dd.abs() < f64::EPSILON {
+                    0.0
+                } else {
+                    total_return / max_dd
+                }
+            }
+            Objective::ProfitFactor => profit_factor(returns),
+        }
+    }
+}
+
+fn profit_factor(returns: &[f64]) -> f64 {
+    let gross_profit: f64 = returns.iter().filter(|r| **r > 0.0).sum();
+    let gross_loss: f64 = returns.iter().filter(|r| **r < 0.0).map(|r| r.abs()).sum();
+    if gross_loss < f64::EPSILON {
+        if gross_profit > 0.0 {
+            PROFIT_FACTOR_CAP
+        } else {
+            0.0
+        }
+    } else {
+        gross_profit / gross_loss
+    }
+}
+
+fn sharpe_ratio(returns: &[f64]) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+    let std_dev = variance.sqrt();
+    if std_dev.abs() < f64::EPSILON {
+        0.0
+    } else {
+        mean / std_dev
+    }
+}
+
+fn max_drawdown_pct(returns: &[f64]) -> f64 {
+    let mut equity = 1.0;
+    let mut peak = 1.0;
+    let mut max_dd = 0.0;
+    for r in returns {
+        equity *= 1.0 + r;
+        peak = f64::max(peak, equity);
+        let dd = (peak - equity) / peak;
+        max_dd = f64::max(max_dd, dd);
+    }
+    max_dd * 100.0
+}
+
+/// A single scored trial: the sampled parameters and the resulting score(s)
+#[derive(Debug, Clone)]
+pub struct Trial {
+    pub params: ParamPoint,
+    pub in_sample_score: f64,
+    /// Populated only when `walk_forward_split` is used
+    pub out_of_sample_score: Option<f64>,
+}
+
+/// Result of a full hyperopt run
+#[derive(Debug, Clone)]
+pub struct HyperoptResult {
+    pub best_config: StrategyRouterConfig,
+    pub best_trial: Trial,
+    /// Every trial, ranked best-to-worst by `in_sample_score`
+    pub trials: Vec<Trial>,
+}
+
+/// Exhaustively search the cartesian product of each range's grid steps
+pub fn grid_search(
+    ranges: &[ParamRange],
+    build_config: &ConfigBuilder,
+    candles: &[Candle],
+    objective: Objective,
+    walk_forward_split: Option<f64>,
+) -> HyperoptResult {
+    let points = cartesian_product(ranges);
+    run_search(points, build_config, candles, objective, walk_forward_split)
+}
+
+/// Uniformly sample `trials` random points from each range
+///
+/// `seed` makes the sampled points reproducible across runs.
+pub fn random_search(
+    ranges: &[ParamRange],
+    build_config: &ConfigBuilder,
+    candles: &[Candle],
+    objective: Objective,
+    walk_forward_split: Option<f64>,
+    trials: usize,
+    seed: u64,
+) -> HyperoptResult {
+    let mut rng = XorShiftRng::new(seed);
+    let points: Vec<ParamPoint> = (0..trials)
+        .map(|_| {
+            ranges
+                .iter()
+                .map(|r| (r.name.clone(), rng.next_in_range(r.min, r.max)))
+                .collect()
+        })
+        .collect();
+    run_search(points, build_config, candles, objective, walk_forward_split)
+}
+
+fn cartesian_product(ranges: &[ParamRange]) -> Vec<ParamPoint> {
+    let mut points = vec![ParamPoint::new()];
+    for range in ranges {
+        let mut next = Vec::new();
+        for point in &points {
+            for v in range.grid_values() {
+                let mut p = point.clone();
+                p.insert(range.name.clone(), v);
+                next.push(p);
+            }
+        }
+        points = next;
+    }
+    points
+}
+
+fn run_search(
+    points: Vec<ParamPoint>,
+    build_config: &ConfigBuilder,
+    candles: &[Candle],
+    objective: Objective,
+    walk_forward_split: Option<f64>,
+) -> HyperoptResult {
+    let (in_sample, out_of_sample) = match walk_forward_split {
+        Some(frac) => {
+            let split_at = ((candles.len() as f64) * frac).round() as usize;
+            (&candles[..split_at], Some(&candles[split_at..]))
+        }
+        None => (candles, None),
+    };
+
+    let mut trials: Vec<Trial> = points
+        .into_iter()
+        .map(|params| {
+            let config = build_config(&params);
+            let in_sample_score = backtest_score(&config, in_sample, objective);
+            let out_of_sample_score =
+                out_of_sample.map(|oos| backtest_score(&config, oos, objective));
+            Trial {
+                params,
+                in_sample_score,
+                out_of_sample_score,
+            }
+        })
+        .collect();
+
+    trials.sort_by(|a, b| {
+        b.in_sample_score
+            .partial_cmp(&a.in_sample_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let best_trial = trials
+        .first()
+        .cloned()
+        .expect("hyperopt search requires at least one parameter point");
+    let best_config = build_config(&best_trial.params);
+
+    HyperoptResult {
+        best_config,
+        best_trial,
+        trials,
+    }
+}
+
+fn backtest_score(config: &StrategyRouterConfig, candles: &[Candle], objective: Objective) -> f64 {
+    let mut router = StrategyRouter::new(config.clone());
+    let report = run_backtest(&mut router, "HYPEROPT", candles, &ExitPolicy::default());
+    objective.score(&report)
+}
+
+/// Minimal deterministic PRNG so `random_search` trials are reproducible for
+/// a given seed. Not suitable for cryptographic use.
+struct XorShiftRng {
+    state: u64,
+}
+
+impl XorShiftRng {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: if seed == 0 { 0x9E3779B97F4A7C15 } else { seed },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn next_in_range(&mut self, min: f64, max: f64) -> f64 {
+        let frac = (self.next_u64() as f64) / (u64::MAX as f64);
+        min + frac * (max - min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(ts: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            timestamp: ts,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn trending_candles(bars: usize) -> Vec<Candle> {
+        let mut price = 100.0;
+        (0..bars as i64)
+            .map(|i| {
+                price += 1.0;
+                candle(i, price + 0.5, price - 0.5, price)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_grid_search_finds_a_best_config() {
+        let ranges = vec![ParamRange::new("min_regime_confidence", 0.3, 0.7, 0.2)];
+        let build: &ConfigBuilder = &|params| StrategyRouterConfig {
+            min_regime_confidence: params["min_regime_confidence"],
+            ..StrategyRouterConfig::default()
+        };
+        let candles = trending_candles(260);
+
+        let result = grid_search(&ranges, build, &candles, Objective::TotalReturn, None);
+
+        assert!(!result.trials.is_empty());
+        assert!(result.best_trial.out_of_sample_score.is_none());
+        // Trials must be sorted best-first
+        for pair in result.trials.windows(2) {
+            assert!(pair[0].in_sample_score >= pair[1].in_sample_score);
+        }
+    }
+
+    #[test]
+    fn test_random_search_is_reproducible_for_a_seed() {
+        let ranges = vec![ParamRange::new("min_regime_confidence", 0.2, 0.8, 0.0)];
+        let build: &ConfigBuilder = &|params| StrategyRouterConfig {
+            min_regime_confidence: params["min_regime_confidence"],
+            ..StrategyRouterConfig::default()
+        };
+        let candles = trending_candles(260);
+
+        let a = random_search(&ranges, build, &candles, Objective::TotalReturn, None, 5, 42);
+        let b = random_search(&ranges, build, &candles, Objective::TotalReturn, None, 5, 42);
+
+        let params_a: Vec<f64> = a.trials.iter().map(|t| t.params["min_regime_confidence"]).collect();
+        let params_b: Vec<f64> = b.trials.iter().map(|t| t.params["min_regime_confidence"]).collect();
+        assert_eq!(params_a, params_b);
+    }
+
+    #[test]
+    fn test_walk_forward_split_populates_out_of_sample_score() {
+        let ranges = vec![ParamRange::new("min_regime_confidence", 0.5, 0.5, 0.0)];
+        let build: &ConfigBuilder = &|params| StrategyRouterConfig {
+            min_regime_confidence: params["min_regime_confidence"],
+            ..StrategyRouterConfig::default()
+        };
+        let candles = trending_candles(400);
+
+        let result = grid_search(&ranges, build, &candles, Objective::TotalReturn, Some(0.7));
+
+        assert!(result.best_trial.out_of_sample_score.is_some());
+    }
+}
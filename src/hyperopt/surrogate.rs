@@ -0,0 +1,554 @@
+//! Surrogate-model-based autotuning of `EnhancedRouterConfig`
+//!
+//! `grid_search`/`random_search` spend their whole budget sweeping every
+//! declared grid point or a fixed number of uniform random draws. Against an
+//! expensive-to-evaluate objective (a full backtest per trial) that wastes
+//! most of the budget on regions already known to be bad. `Optimizer` instead
+//! runs sequential model-based optimization (SMBO): keep every `(params,
+//! score)` pair evaluated so far, fit a surrogate that predicts a mean and
+//! standard deviation of the objective at any candidate point, and pick the
+//! next trial by maximizing Expected Improvement -
+//! `EI = (μ - best)·Φ(z) + σ·φ(z)`, `z = (μ - best)/σ` - which balances
+//! exploiting high-mean regions against exploring high-uncertainty ones. This
+//! is the same loop tools like scikit-optimize/Optuna run, with `Estimator`
+//! playing the role of their pluggable regressor.
+
+use crate::backtest::run_enhanced_backtest;
+use crate::hyperopt::{Objective, ParamPoint, ParamRange, Trial, XorShiftRng};
+use crate::integration::Candle;
+use crate::strategy::enhanced_router::{EnhancedRouter, EnhancedRouterConfig};
+
+/// Builds a full `EnhancedRouter` config from a sampled parameter point
+pub type EnhancedConfigBuilder = dyn Fn(&ParamPoint) -> EnhancedRouterConfig;
+
+/// Which surrogate regressor predicts mean/std for unevaluated points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Estimator {
+    /// RBF-kernel-weighted average of observed scores - a lightweight
+    /// stand-in for a full Gaussian process, with per-point weight doubling
+    /// as the basis for a local variance estimate
+    GaussianProcess,
+    /// Bagged extremely-randomized regression trees (random feature, random
+    /// split threshold). Mean across trees is μ, variance across trees is σ²
+    RandomForest,
+}
+
+/// Tuning knobs for `Optimizer::optimize`
+#[derive(Debug, Clone, Copy)]
+pub struct OptimizerConfig {
+    pub estimator: Estimator,
+    pub objective: Objective,
+    /// Random points evaluated before the surrogate starts driving candidate
+    /// selection
+    pub n_initial_points: usize,
+    /// Maximum surrogate-guided trials after the initial random seed
+    pub n_iterations: usize,
+    /// Candidate points drawn and scored against the surrogate each
+    /// iteration; the one with the highest Expected Improvement is evaluated
+    pub candidates_per_iteration: usize,
+    /// Trees in the `RandomForest` estimator's ensemble
+    pub n_trees: usize,
+    /// Stop once Expected Improvement stays below this threshold for
+    /// `convergence_patience` consecutive iterations
+    pub convergence_tolerance: f64,
+    pub convergence_patience: usize,
+    /// Seeds both the initial random points and the surrogate-guided search,
+    /// so a run is reproducible for a given seed
+    pub seed: u64,
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            estimator: Estimator::GaussianProcess,
+            objective: Objective::Sharpe,
+            n_initial_points: 5,
+            n_iterations: 25,
+            candidates_per_iteration: 200,
+            n_trees: 20,
+            convergence_tolerance: 1e-4,
+            convergence_patience: 5,
+            seed: 42,
+        }
+    }
+}
+
+/// Result of a full `Optimizer::optimize` run
+#[derive(Debug, Clone)]
+pub struct OptimizerResult {
+    pub best_config: EnhancedRouterConfig,
+    pub best_trial: Trial,
+    /// Every trial evaluated, ranked best-to-worst by `in_sample_score`
+    pub trials: Vec<Trial>,
+}
+
+/// Tunes `EnhancedRouterConfig` against a historical candle series via
+/// sequential model-based optimization
+#[derive(Debug, Clone, Copy)]
+pub struct Optimizer {
+    config: OptimizerConfig,
+}
+
+impl Optimizer {
+    pub fn new(config: OptimizerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(OptimizerConfig::default())
+    }
+
+    /// Run the search and return the best config found
+    pub fn optimize(
+        &self,
+        ranges: &[ParamRange],
+        build_config: &EnhancedConfigBuilder,
+        candles: &[Candle],
+    ) -> OptimizerResult {
+        let mut rng = XorShiftRng::new(self.config.seed);
+        let mut trials: Vec<Trial> = Vec::new();
+        let mut xs: Vec<Vec<f64>> = Vec::new();
+        let mut ys: Vec<f64> = Vec::new();
+
+        let evaluate = |point: ParamPoint| -> Trial {
+            let cfg = build_config(&point);
+            let score = backtest_score(&cfg, candles, self.config.objective);
+            Trial {
+                params: point,
+                in_sample_score: score,
+                out_of_sample_score: None,
+            }
+        };
+
+        for _ in 0..self.config.n_initial_points.max(1) {
+            let point = sample_point(ranges, &mut rng);
+            let trial = evaluate(point);
+            xs.push(to_vec(ranges, &trial.params));
+            ys.push(trial.in_sample_score);
+            trials.push(trial);
+        }
+
+        let mut surrogate = self
+            .config
+            .estimator
+            .build(ranges, self.config.n_trees, self.config.seed);
+        let mut stale_iterations = 0;
+
+        for _ in 0..self.config.n_iterations {
+            surrogate.fit(&xs, &ys);
+            let best_score = ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+            let mut best: Option<(ParamPoint, Vec<f64>, f64)> = None;
+            for _ in 0..self.config.candidates_per_iteration.max(1) {
+                let point = sample_point(ranges, &mut rng);
+                let x = to_vec(ranges, &point);
+                let (mu, sigma) = surrogate.predict(&x);
+                let ei = expected_improvement(mu, sigma, best_score);
+                if best.as_ref().map_or(true, |(_, _, best_ei)| ei > *best_ei) {
+                    best = Some((point, x, ei));
+                }
+            }
+            let Some((point, x, ei)) = best else {
+                break;
+            };
+
+            if ei < self.config.convergence_tolerance {
+                stale_iterations += 1;
+                if stale_iterations >= self.config.convergence_patience {
+                    break;
+                }
+            } else {
+                stale_iterations = 0;
+            }
+
+            let trial = evaluate(point);
+            xs.push(x);
+            ys.push(trial.in_sample_score);
+            trials.push(trial);
+        }
+
+        trials.sort_by(|a, b| {
+            b.in_sample_score
+                .partial_cmp(&a.in_sample_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let best_trial = trials
+            .first()
+            .cloned()
+            .expect("optimize requires at least one evaluated point");
+        let best_config = build_config(&best_trial.params);
+
+        OptimizerResult {
+            best_config,
+            best_trial,
+            trials,
+        }
+    }
+}
+
+fn sample_point(ranges: &[ParamRange], rng: &mut XorShiftRng) -> ParamPoint {
+    ranges
+        .iter()
+        .map(|r| (r.name.clone(), rng.next_in_range(r.min, r.max)))
+        .collect()
+}
+
+/// Flattens a `ParamPoint` into a vector ordered by `ranges`, the layout
+/// every `Surrogate` operates on
+fn to_vec(ranges: &[ParamRange], point: &ParamPoint) -> Vec<f64> {
+    ranges.iter().map(|r| point[&r.name]).collect()
+}
+
+fn backtest_score(config: &EnhancedRouterConfig, candles: &[Candle], objective: Objective) -> f64 {
+    let mut router = EnhancedRouter::new(config.clone());
+    let report = run_enhanced_backtest(&mut router, "AUTOTUNE", candles);
+    let returns: Vec<f64> = report.trades.iter().map(|t| t.return_pct).collect();
+    objective.score_returns(&returns)
+}
+
+/// Expected Improvement of a candidate with predicted mean `mu` and standard
+/// deviation `sigma` over the best score seen so far
+fn expected_improvement(mu: f64, sigma: f64, best: f64) -> f64 {
+    if sigma < f64::EPSILON {
+        return (mu - best).max(0.0);
+    }
+    let z = (mu - best) / sigma;
+    ((mu - best) * normal_cdf(z) + sigma * normal_pdf(z)).max(0.0)
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz-Stegun approximation of the error function (max error ~1.5e-7),
+/// accurate enough for ranking Expected Improvement candidates
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    let a1 = 0.254829592;
+    let a2 = -0.284496736;
+    let a3 = 1.421413741;
+    let a4 = -1.453152027;
+    let a5 = 1.061405429;
+    let p = 0.3275911;
+
+    let t = 1.0 / (1.0 + p * x);
+    let poly = ((((a5 * t + a4) * t + a3) * t + a2) * t + a1) * t;
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+/// Predicts a mean and standard deviation of the objective at an
+/// unevaluated point, fit from every `(params, score)` pair seen so far
+trait Surrogate {
+    fn fit(&mut self, xs: &[Vec<f64>], ys: &[f64]);
+    fn predict(&self, x: &[f64]) -> (f64, f64);
+}
+
+impl Estimator {
+    fn build(&self, ranges: &[ParamRange], n_trees: usize, seed: u64) -> Box<dyn Surrogate> {
+        match self {
+            Estimator::GaussianProcess => Box::new(KernelRegressor::new(ranges)),
+            Estimator::RandomForest => Box::new(ExtraTreesRegressor::new(n_trees, seed)),
+        }
+    }
+}
+
+/// RBF-kernel-weighted (Nadaraya-Watson) regressor. Distances are normalized
+/// per-dimension by each `ParamRange`'s width so a run_confidence-scale knob
+/// and an HMM `n_states`-scale knob contribute comparably to the kernel.
+struct KernelRegressor {
+    widths: Vec<f64>,
+    length_scale: f64,
+    xs: Vec<Vec<f64>>,
+    ys: Vec<f64>,
+}
+
+impl KernelRegressor {
+    fn new(ranges: &[ParamRange]) -> Self {
+        let widths = ranges
+            .iter()
+            .map(|r| (r.max - r.min).abs().max(1e-9))
+            .collect();
+        Self {
+            widths,
+            length_scale: 0.3,
+            xs: Vec::new(),
+            ys: Vec::new(),
+        }
+    }
+
+    fn kernel(&self, a: &[f64], b: &[f64]) -> f64 {
+        let sq_dist: f64 = a
+            .iter()
+            .zip(b)
+            .zip(&self.widths)
+            .map(|((ai, bi), w)| ((ai - bi) / w / self.length_scale).powi(2))
+            .sum();
+        (-0.5 * sq_dist).exp()
+    }
+}
+
+impl Surrogate for KernelRegressor {
+    fn fit(&mut self, xs: &[Vec<f64>], ys: &[f64]) {
+        self.xs = xs.to_vec();
+        self.ys = ys.to_vec();
+    }
+
+    fn predict(&self, x: &[f64]) -> (f64, f64) {
+        if self.xs.is_empty() {
+            return (0.0, 1.0);
+        }
+        let weights: Vec<f64> = self.xs.iter().map(|xi| self.kernel(x, xi)).collect();
+        let total: f64 = weights.iter().sum();
+        if total < f64::EPSILON {
+            let mean = self.ys.iter().sum::<f64>() / self.ys.len() as f64;
+            return (mean, sample_std(&self.ys, mean));
+        }
+
+        let mean: f64 = weights.iter().zip(&self.ys).map(|(w, y)| w * y).sum::<f64>() / total;
+        let variance: f64 = weights
+            .iter()
+            .zip(&self.ys)
+            .map(|(w, y)| w * (y - mean).powi(2))
+            .sum::<f64>()
+            / total;
+        (mean, variance.sqrt().max(1e-6))
+    }
+}
+
+fn sample_std(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 1.0;
+    }
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt().max(1e-6)
+}
+
+/// One node of an extremely-randomized regression tree: a leaf holding the
+/// mean training target, or a split on a random feature/threshold
+enum TreeNode {
+    Leaf(f64),
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: Box<TreeNode>,
+        right: Box<TreeNode>,
+    },
+}
+
+impl TreeNode {
+    fn predict(&self, x: &[f64]) -> f64 {
+        match self {
+            TreeNode::Leaf(value) => *value,
+            TreeNode::Split { feature, threshold, left, right } => {
+                if x[*feature] < *threshold {
+                    left.predict(x)
+                } else {
+                    right.predict(x)
+                }
+            }
+        }
+    }
+}
+
+const MAX_TREE_DEPTH: usize = 6;
+
+fn mean_of(ys: &[f64], indices: &[usize]) -> f64 {
+    indices.iter().map(|&i| ys[i]).sum::<f64>() / indices.len().max(1) as f64
+}
+
+fn build_tree(
+    xs: &[Vec<f64>],
+    ys: &[f64],
+    indices: &[usize],
+    depth: usize,
+    rng: &mut XorShiftRng,
+) -> TreeNode {
+    if depth >= MAX_TREE_DEPTH || indices.len() <= 1 {
+        return TreeNode::Leaf(mean_of(ys, indices));
+    }
+
+    let n_features = xs[0].len();
+    let feature = (rng.next_u64() as usize) % n_features;
+    let (min, max) = indices.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(mn, mx), &i| {
+        (mn.min(xs[i][feature]), mx.max(xs[i][feature]))
+    });
+    if (max - min).abs() < f64::EPSILON {
+        return TreeNode::Leaf(mean_of(ys, indices));
+    }
+
+    let threshold = min + rng.next_in_range(0.0, 1.0) * (max - min);
+    let (left, right): (Vec<usize>, Vec<usize>) = indices
+        .iter()
+        .copied()
+        .partition(|&i| xs[i][feature] < threshold);
+    if left.is_empty() || right.is_empty() {
+        return TreeNode::Leaf(mean_of(ys, indices));
+    }
+
+    TreeNode::Split {
+        feature,
+        threshold,
+        left: Box::new(build_tree(xs, ys, &left, depth + 1, rng)),
+        right: Box::new(build_tree(xs, ys, &right, depth + 1, rng)),
+    }
+}
+
+/// Bagged extremely-randomized trees: each tree is fit on a bootstrap sample
+/// with random feature/threshold splits (no split-quality search), so
+/// building the ensemble stays cheap per autotuner iteration. Mean across
+/// trees is μ, standard deviation across trees is σ.
+struct ExtraTreesRegressor {
+    n_trees: usize,
+    rng: XorShiftRng,
+    trees: Vec<TreeNode>,
+}
+
+impl ExtraTreesRegressor {
+    fn new(n_trees: usize, seed: u64) -> Self {
+        Self {
+            n_trees: n_trees.max(1),
+            rng: XorShiftRng::new(seed),
+            trees: Vec::new(),
+        }
+    }
+}
+
+impl Surrogate for ExtraTreesRegressor {
+    fn fit(&mut self, xs: &[Vec<f64>], ys: &[f64]) {
+        self.trees.clear();
+        if xs.is_empty() {
+            return;
+        }
+
+        for _ in 0..self.n_trees {
+            let mut bootstrap = Vec::with_capacity(xs.len());
+            for _ in 0..xs.len() {
+                bootstrap.push((self.rng.next_u64() as usize) % xs.len());
+            }
+            self.trees.push(build_tree(xs, ys, &bootstrap, 0, &mut self.rng));
+        }
+    }
+
+    fn predict(&self, x: &[f64]) -> (f64, f64) {
+        if self.trees.is_empty() {
+            return (0.0, 1.0);
+        }
+        let predictions: Vec<f64> = self.trees.iter().map(|t| t.predict(x)).collect();
+        let mean = predictions.iter().sum::<f64>() / predictions.len() as f64;
+        (mean, sample_std(&predictions, mean))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trending_candles(bars: usize) -> Vec<Candle> {
+        let mut price = 100.0;
+        (0..bars as i64)
+            .map(|i| {
+                price += 1.0;
+                Candle {
+                    timestamp: i,
+                    open: price,
+                    high: price + 0.5,
+                    low: price - 0.5,
+                    close: price,
+                    volume: 1.0,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_expected_improvement_prefers_higher_mean_at_equal_uncertainty() {
+        let low = expected_improvement(0.1, 0.2, 0.0);
+        let high = expected_improvement(0.5, 0.2, 0.0);
+        assert!(high > low);
+    }
+
+    #[test]
+    fn test_expected_improvement_is_nonnegative() {
+        assert!(expected_improvement(-1.0, 0.1, 5.0) >= 0.0);
+    }
+
+    #[test]
+    fn test_gaussian_process_optimizer_finds_trials() {
+        let ranges = vec![ParamRange::new("min_confidence", 0.2, 0.8, 0.0)];
+        let candles = trending_candles(300);
+        let optimizer = Optimizer::new(OptimizerConfig {
+            estimator: Estimator::GaussianProcess,
+            n_initial_points: 3,
+            n_iterations: 4,
+            candidates_per_iteration: 10,
+            ..OptimizerConfig::default()
+        });
+
+        let build: &EnhancedConfigBuilder = &|params: &ParamPoint| EnhancedRouterConfig {
+            min_confidence: params["min_confidence"],
+            ..EnhancedRouterConfig::default()
+        };
+        let result = optimizer.optimize(&ranges, build, &candles);
+
+        assert!(!result.trials.is_empty());
+        for pair in result.trials.windows(2) {
+            assert!(pair[0].in_sample_score >= pair[1].in_sample_score);
+        }
+    }
+
+    #[test]
+    fn test_random_forest_optimizer_finds_trials() {
+        let ranges = vec![ParamRange::new("min_confidence", 0.2, 0.8, 0.0)];
+        let candles = trending_candles(300);
+        let optimizer = Optimizer::new(OptimizerConfig {
+            estimator: Estimator::RandomForest,
+            n_initial_points: 3,
+            n_iterations: 4,
+            candidates_per_iteration: 10,
+            n_trees: 5,
+            ..OptimizerConfig::default()
+        });
+
+        let build: &EnhancedConfigBuilder = &|params: &ParamPoint| EnhancedRouterConfig {
+            min_confidence: params["min_confidence"],
+            ..EnhancedRouterConfig::default()
+        };
+        let result = optimizer.optimize(&ranges, build, &candles);
+
+        assert!(!result.trials.is_empty());
+        assert!(result.trials.len() >= 3);
+    }
+
+    #[test]
+    fn test_optimizer_is_reproducible_for_a_seed() {
+        let ranges = vec![ParamRange::new("min_confidence", 0.2, 0.8, 0.0)];
+        let candles = trending_candles(300);
+        let config = OptimizerConfig {
+            n_initial_points: 3,
+            n_iterations: 3,
+            candidates_per_iteration: 10,
+            seed: 7,
+            ..OptimizerConfig::default()
+        };
+
+        let build: &EnhancedConfigBuilder = &|params: &ParamPoint| EnhancedRouterConfig {
+            min_confidence: params["min_confidence"],
+            ..EnhancedRouterConfig::default()
+        };
+        let a = Optimizer::new(config).optimize(&ranges, build, &candles);
+        let b = Optimizer::new(config).optimize(&ranges, build, &candles);
+
+        let scores_a: Vec<f64> = a.trials.iter().map(|t| t.in_sample_score).collect();
+        let scores_b: Vec<f64> = b.trials.iter().map(|t| t.in_sample_score).collect();
+        assert_eq!(scores_a, scores_b);
+    }
+}
@@ -0,0 +1,198 @@
+//! A reconnecting Kraken WebSocket client.
+//!
+//! `websocket_integration::handle_ws_message` is the building block for
+//! wiring a socket loop by hand (see `examples/live_trading.rs`); this
+//! module is the hardened version of that loop as a type a binary can just
+//! own: `KrakenWsClient` subscribes every configured pair on connect,
+//! tracks which subscriptions Kraken has acknowledged, treats a missing
+//! heartbeat as a dead connection, and reconnects with exponential
+//! backoff, re-subscribing and replaying a `warmup_with_history` backfill
+//! each time so a dropped connection doesn't leave a gap in the router's
+//! candle history.
+//!
+//! Targets Kraken's v1 protocol only, since that's what `KrakenEvent`
+//! models; a v2 connection should still use the hand-rolled loop shown in
+//! `examples/live_trading.rs`.
+
+use super::kraken::websocket_integration::{self, KrakenWsMessage};
+use super::kraken_ws::{ControlMessage, KrakenEvent, KrakenWsFrame};
+use super::{Candle, KrakenIntegrationConfig, KrakenRegimeTrader};
+use backoff::{backoff::Backoff, ExponentialBackoff};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use std::collections::HashSet;
+use std::future::Future;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// How long to wait with no frames (data or heartbeat) before treating the
+/// connection as half-open and reconnecting.
+pub const DEFAULT_HEARTBEAT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// A reconnecting Kraken v1 WebSocket client wrapping a `KrakenRegimeTrader`.
+///
+/// `fetch_history` is called once per pair on every (re)connect to fetch
+/// the candles to backfill with; plug in whatever REST client is already
+/// in use (see `rest_integration` for Kraken's own OHLC endpoint).
+pub struct KrakenWsClient<F> {
+    config: KrakenIntegrationConfig,
+    trader: KrakenRegimeTrader,
+    fetch_history: F,
+    heartbeat_timeout: std::time::Duration,
+    acknowledged: HashSet<String>,
+}
+
+impl<F, Fut> KrakenWsClient<F>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<Candle>, String>>,
+{
+    pub fn new(config: KrakenIntegrationConfig, trader: KrakenRegimeTrader, fetch_history: F) -> Self {
+        Self {
+            config,
+            trader,
+            fetch_history,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
+            acknowledged: HashSet::new(),
+        }
+    }
+
+    /// Override how long to wait with no frames before reconnecting
+    /// (default: `DEFAULT_HEARTBEAT_TIMEOUT`).
+    pub fn with_heartbeat_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// `"{channelName}:{pair}"` keys Kraken has acknowledged with
+    /// `subscriptionStatus: "subscribed"` on the current connection.
+    /// Cleared on every reconnect until fresh acks arrive.
+    pub fn acknowledged_subscriptions(&self) -> &HashSet<String> {
+        &self.acknowledged
+    }
+
+    pub fn trader(&self) -> &KrakenRegimeTrader {
+        &self.trader
+    }
+
+    /// Run the connect/subscribe/read loop forever, reconnecting on any
+    /// connection-level failure with exponential backoff. Only returns if
+    /// the caller drops the future; a bad URL or a down server just
+    /// extends the backoff instead of ending the loop.
+    pub async fn run(&mut self) -> ! {
+        let mut backoff = ExponentialBackoff {
+            max_elapsed_time: None,
+            ..ExponentialBackoff::default()
+        };
+
+        loop {
+            self.backfill_history().await;
+
+            let (mut write, mut read) = match self.connect_and_subscribe().await {
+                Ok(streams) => streams,
+                Err(_) => {
+                    let wait = backoff.next_backoff().unwrap_or(backoff.max_interval);
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+            };
+            backoff.reset();
+
+            let mut last_frame_at = tokio::time::Instant::now();
+
+            loop {
+                tokio::select! {
+                    Some(msg) = read.next() => {
+                        last_frame_at = tokio::time::Instant::now();
+                        match msg {
+                            Ok(Message::Text(text)) => {
+                                backoff.reset();
+                                self.handle_frame(&text).await;
+                            }
+                            Ok(Message::Ping(data)) => {
+                                backoff.reset();
+                                let _ = write.send(Message::Pong(data)).await;
+                            }
+                            Ok(Message::Close(_)) | Err(_) => break,
+                            _ => {}
+                        }
+                    }
+                    _ = tokio::time::sleep_until(last_frame_at + self.heartbeat_timeout) => {
+                        break;
+                    }
+                }
+            }
+
+            self.acknowledged.clear();
+            let wait = backoff.next_backoff().unwrap_or(backoff.max_interval);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Fetch and replay recent history for every configured pair, so a
+    /// fresh (or reconnected) connection starts from a router that already
+    /// knows about whatever candles the gap missed.
+    async fn backfill_history(&mut self) {
+        for pair in self.config.pairs.clone() {
+            if let Ok(candles) = (self.fetch_history)(pair.clone()).await {
+                self.trader.warmup_with_history(&pair, &candles);
+            }
+        }
+    }
+
+    async fn connect_and_subscribe(&self) -> Result<(WsWriter, WsReader), String> {
+        let (ws_stream, _) = connect_async(self.config.ws_url.as_str())
+            .await
+            .map_err(|e| e.to_string())?;
+        let (mut write, read) = ws_stream.split();
+
+        for pair in &self.config.pairs {
+            let sub_msg = json!({
+                "event": "subscribe",
+                "pair": [pair],
+                "subscription": {
+                    "name": "ohlc",
+                    "interval": self.config.timeframe_minutes
+                }
+            });
+            write
+                .send(Message::Text(sub_msg.to_string()))
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok((write, read))
+    }
+
+    /// Parse one text frame: control frames (status/heartbeat/subscription
+    /// acks) are consumed internally and update `acknowledged`; data frames
+    /// flow into `websocket_integration::handle_ws_message`.
+    async fn handle_frame(&mut self, text: &str) {
+        let Ok(frame) = serde_json::from_str::<KrakenWsFrame>(text) else {
+            return;
+        };
+
+        match frame {
+            KrakenWsFrame::Control(control) => self.handle_control(&control),
+            KrakenWsFrame::Data(_) => {
+                if let Ok(msg) = serde_json::from_str::<KrakenWsMessage>(text) {
+                    websocket_integration::handle_ws_message(&mut self.trader, msg).await;
+                }
+            }
+        }
+    }
+
+    fn handle_control(&mut self, control: &ControlMessage) {
+        if let KrakenEvent::SubscriptionStatus(status) = control.event_kind() {
+            if status.status == "subscribed" {
+                if let (Some(pair), Some(channel)) = (status.pair, status.channel_name) {
+                    self.acknowledged.insert(format!("{}:{}", channel, pair));
+                }
+            }
+        }
+    }
+}
@@ -3,15 +3,21 @@
 //! Integrates the strategy router with Kraken's WebSocket (live data) and REST API (historical data).
 //! This module bridges your existing kraken codebase with the new regime detection system.
 
-use crate::regime::MarketRegime;
+use crate::regime::{MarketRegime, RegimeConfig, RegimeDetector};
 use crate::strategy::mean_reversion::Signal;
 use crate::strategy::router::{ActiveStrategy, RoutedSignal, StrategyRouter, StrategyRouterConfig};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
 use tokio::sync::mpsc;
 
+/// Kraken's default public WebSocket v1 endpoint.
+pub const KRAKEN_WS_URL_V1: &str = "wss://ws.kraken.com";
+/// Kraken's public WebSocket v2 endpoint.
+pub const KRAKEN_WS_URL_V2: &str = "wss://ws.kraken.com/v2";
+
 /// OHLCV candle data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Candle {
     pub timestamp: i64,
     pub open: f64,
@@ -19,6 +25,11 @@ pub struct Candle {
     pub low: f64,
     pub close: f64,
     pub volume: f64,
+    /// Volume-weighted average price over the candle, `sum(price*vol) /
+    /// sum(vol)`. `0.0` for candles built without per-tick volume (older
+    /// call sites, test fixtures).
+    #[serde(default)]
+    pub vwap: f64,
 }
 
 /// Trade action to execute
@@ -26,14 +37,40 @@ pub struct Candle {
 pub struct TradeAction {
     pub symbol: String,
     pub action: TradeType,
+    /// Estimated fill price: `price` adjusted by `ExecutionModel`'s
+    /// directional half-spread, not the raw candle close.
     pub price: f64,
     pub size_factor: f64, // 0.0 - 1.0, multiply by max position
+    /// Actionable order size in USD: `risk_per_trade_pct` of
+    /// `max_trade_usd` sized against the `stop_loss` distance, scaled by
+    /// `size_factor` and clamped to `[min_trade_usd, max_trade_usd]`. `0.0`
+    /// when `action` is `Hold`.
+    pub order_size_usd: f64,
     pub stop_loss: Option<f64>,
     pub take_profit: Option<f64>,
     pub source_strategy: String,
     pub regime: String,
     pub confidence: f64,
     pub reason: String,
+    /// Per-confirmation-timeframe regime breakdown, one entry per
+    /// `KrakenIntegrationConfig::confirmation_timeframes` multiple, in
+    /// configured order. Empty when no confirmation timeframes are
+    /// configured.
+    pub timeframe_regimes: Vec<TimeframeRegime>,
+}
+
+/// One confirmation timeframe's own regime call, folded from
+/// `candles_per_bar` consecutive base candles - see
+/// [`KrakenIntegrationConfig::confirmation_timeframes`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeframeRegime {
+    pub candles_per_bar: usize,
+    pub regime: MarketRegime,
+    pub confidence: f64,
+    /// Consecutive bullish (`close > open`) closes at this timeframe
+    pub consecutive_up: u32,
+    /// Consecutive bearish (`close < open`) closes at this timeframe
+    pub consecutive_down: u32,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -53,6 +90,160 @@ impl From<Signal> for TradeType {
     }
 }
 
+/// A single order book price level: `(price, volume)`
+pub type BookLevel = (f64, f64);
+
+/// Sorted, depth-limited snapshot of one pair's order book, built by
+/// applying Kraken's incremental L2 deltas: a level is replaced on update
+/// and removed once its volume drops to zero. Bids are kept sorted
+/// descending by price, asks ascending, so `best_bid`/`best_ask` are always
+/// the first element.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    bids: Vec<BookLevel>,
+    asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a batch of incremental deltas to both sides of the book.
+    pub fn apply_updates(&mut self, bids: &[BookLevel], asks: &[BookLevel]) {
+        for &(price, volume) in bids {
+            Self::apply_level(&mut self.bids, price, volume, true);
+        }
+        for &(price, volume) in asks {
+            Self::apply_level(&mut self.asks, price, volume, false);
+        }
+    }
+
+    fn apply_level(levels: &mut Vec<BookLevel>, price: f64, volume: f64, descending: bool) {
+        let existing = levels.iter().position(|&(p, _)| p == price);
+
+        if volume <= 0.0 {
+            if let Some(idx) = existing {
+                levels.remove(idx);
+            }
+            return;
+        }
+
+        match existing {
+            Some(idx) => levels[idx].1 = volume,
+            None => {
+                let insert_at = levels
+                    .iter()
+                    .position(|&(p, _)| if descending { p < price } else { p > price })
+                    .unwrap_or(levels.len());
+                levels.insert(insert_at, (price, volume));
+            }
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<BookLevel> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<BookLevel> {
+        self.asks.first().copied()
+    }
+
+    /// Top-of-book spread, `ask - bid`
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.0 - self.best_bid()?.0)
+    }
+
+    /// Mid-price weighted by the opposite side's top-of-book volume, so the
+    /// mid leans toward whichever side is thinner
+    pub fn weighted_mid_price(&self) -> Option<f64> {
+        let (bid_price, bid_volume) = self.best_bid()?;
+        let (ask_price, ask_volume) = self.best_ask()?;
+        if bid_volume + ask_volume <= 0.0 {
+            return None;
+        }
+        Some((bid_price * ask_volume + ask_price * bid_volume) / (bid_volume + ask_volume))
+    }
+
+    /// Order-book imbalance over the top `depth` levels of each side:
+    /// `(bid_vol - ask_vol) / (bid_vol + ask_vol)`, in `[-1, 1]`. Positive
+    /// values mean bid-side liquidity dominates.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let bid_volume: f64 = self.bids.iter().take(depth).map(|&(_, v)| v).sum();
+        let ask_volume: f64 = self.asks.iter().take(depth).map(|&(_, v)| v).sum();
+        if bid_volume + ask_volume <= 0.0 {
+            return None;
+        }
+        Some((bid_volume - ask_volume) / (bid_volume + ask_volume))
+    }
+
+    /// Snapshot every microstructure feature derived from this book at once
+    pub fn features(&self, depth: usize) -> Option<MicrostructureFeatures> {
+        Some(MicrostructureFeatures {
+            spread: self.spread()?,
+            weighted_mid_price: self.weighted_mid_price()?,
+            imbalance: self.imbalance(depth)?,
+        })
+    }
+}
+
+/// Liquidity-derived features read off an `OrderBook` snapshot, so regimes
+/// and sizing can react to liquidity conditions alongside price action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MicrostructureFeatures {
+    pub spread: f64,
+    pub weighted_mid_price: f64,
+    pub imbalance: f64,
+}
+
+/// Order book depth used when deriving `MicrostructureFeatures::imbalance`
+/// unless the caller asks for a different depth
+pub const DEFAULT_BOOK_DEPTH: usize = 10;
+
+/// Models the execution-time cost of crossing the spread, so `TradeAction`
+/// reports a realistic fill instead of the raw candle close: a buy fills
+/// above the close, a sell below it, and any stop-loss/take-profit level
+/// computed off that close shifts by the same offset so the risk math
+/// stays consistent with the simulated fill.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExecutionModel {
+    /// Round-trip spread as a fraction of price (e.g. `0.0015` = 0.15%).
+    /// Half of this is applied directionally to the fill price.
+    pub spread_pct: f64,
+}
+
+impl Default for ExecutionModel {
+    fn default() -> Self {
+        Self { spread_pct: 0.0015 } // 0.15% round-trip spread
+    }
+}
+
+impl ExecutionModel {
+    /// Multiplicative offset applied to a raw price for `action`: above 1.0
+    /// for a buy (fills above the close), below 1.0 for a sell (fills
+    /// below it), and 1.0 - a no-op - for `Hold`.
+    fn offset(&self, action: TradeType) -> f64 {
+        let half_spread = self.spread_pct / 2.0;
+        match action {
+            TradeType::Buy => 1.0 + half_spread,
+            TradeType::Sell => 1.0 - half_spread,
+            TradeType::Hold => 1.0,
+        }
+    }
+
+    /// The price `action` would actually fill at, after crossing half the spread.
+    pub fn fill_price(&self, price: f64, action: TradeType) -> f64 {
+        price * self.offset(action)
+    }
+
+    /// Shift a stop-loss/take-profit level computed off the raw close by
+    /// the same offset as `fill_price`, so its distance from the fill price
+    /// matches the distance it was computed at.
+    pub fn adjust_level(&self, level: f64, action: TradeType) -> f64 {
+        level * self.offset(action)
+    }
+}
+
 /// Configuration for Kraken integration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KrakenIntegrationConfig {
@@ -76,6 +267,64 @@ pub struct KrakenIntegrationConfig {
 
     /// Risk per trade as percentage of account
     pub risk_per_trade_pct: f64,
+
+    /// Target per-bar realized volatility (a fraction, e.g. `0.01` for 1%)
+    /// that `order_size_usd` scales size to hit: bigger when realized vol
+    /// is below target, smaller when it spikes above it. Combined
+    /// multiplicatively with the regime-based `position_size_factor`
+    /// already applied there.
+    pub target_volatility: f64,
+
+    /// Cap on the volatility-targeting multiplier, so a quiet market
+    /// doesn't scale size up without bound.
+    pub max_leverage: f64,
+
+    /// Number of completed candles of realized-vol history `order_size_usd`
+    /// estimates against.
+    pub vol_lookback: usize,
+
+    /// Candle multiples the multi-timeframe confirmation gate folds the
+    /// base candle stream into - e.g. `[1, 5, 15]` to confirm the primary
+    /// router's regime against the base timeframe itself plus 5x and 15x
+    /// coarser bars (1m/5m/15m, if the base stream is 1-minute candles).
+    /// Each entry runs its own `RegimeDetector` against its own folded
+    /// bars. `[1]` (the default) is a single, always-agreeing timeframe -
+    /// the quorum gate is a no-op until more are added.
+    pub confirmation_timeframes: Vec<usize>,
+
+    /// Minimum number of `confirmation_timeframes` whose own regime call
+    /// must exactly match the primary router's regime before a non-Hold
+    /// signal is allowed through; otherwise the action is downgraded to
+    /// Hold. Single-timeframe detection alone whipsaws on noise a coarser
+    /// timeframe would reject as still in the prior regime.
+    pub confirmation_quorum: usize,
+
+    /// Minimum consecutive bullish (for a Buy) or bearish (for a Sell)
+    /// closes a confirming timeframe must show, counted toward the same
+    /// `confirmation_quorum`, before an entry is let through. `0` disables
+    /// this gate.
+    pub consecutive_run_threshold: u32,
+
+    /// WebSocket endpoint to connect to. Defaults to Kraken's public v1
+    /// endpoint; override to point at the beta server, a local mock for
+    /// tests, or Kraken's v2 endpoint (pair with `use_v2 = true`). Stored
+    /// as a plain `String` rather than a parsed `url::Url` - this crate has
+    /// no other use for the `url` crate, and a malformed override is a
+    /// connect-time error either way, so there's nothing a parsed type buys
+    /// that's worth carrying the extra dependency for.
+    pub ws_url: String,
+
+    /// Whether `ws_url` speaks the v2 protocol (object-keyed `{"method":
+    /// "subscribe", ...}` messages and data frames) instead of v1's
+    /// positional-array frames.
+    pub use_v2: bool,
+
+    /// How often (in seconds) to log/publish a status snapshot.
+    pub status_interval_secs: u64,
+
+    /// Spread/slippage model applied to `TradeAction`'s fill price and
+    /// stop-loss/take-profit levels.
+    pub execution: ExecutionModel,
 }
 
 impl Default for KrakenIntegrationConfig {
@@ -92,6 +341,16 @@ impl Default for KrakenIntegrationConfig {
             min_trade_usd: 10.0,
             max_trade_usd: 250.0,
             risk_per_trade_pct: 1.0,
+            target_volatility: 0.01, // 1% per-bar realized vol target
+            max_leverage: 2.0,
+            vol_lookback: 20,
+            confirmation_timeframes: vec![1],
+            confirmation_quorum: 1,
+            consecutive_run_threshold: 0,
+            ws_url: KRAKEN_WS_URL_V1.to_string(),
+            use_v2: false,
+            status_interval_secs: 60,
+            execution: ExecutionModel::default(),
         }
     }
 }
@@ -107,6 +366,17 @@ pub struct KrakenRegimeTrader {
     // Last processed candle timestamp per pair
     last_candle_time: HashMap<String, i64>,
 
+    // Level-2 order book snapshot per pair, fed by process_l2_update
+    order_books: HashMap<String, OrderBook>,
+
+    // Rolling realized-vol estimate per pair, feeding the volatility-target
+    // scale in order_size_usd
+    vol_estimators: HashMap<String, RealizedVol>,
+
+    // Per-pair multi-timeframe confirmation tracks, one per entry in
+    // config.confirmation_timeframes
+    timeframe_tracks: HashMap<String, Vec<TimeframeTrack>>,
+
     // Signal channel
     signal_tx: Option<mpsc::Sender<TradeAction>>,
 }
@@ -125,6 +395,9 @@ impl KrakenRegimeTrader {
             router,
             candle_builders: HashMap::new(),
             last_candle_time: HashMap::new(),
+            order_books: HashMap::new(),
+            vol_estimators: HashMap::new(),
+            timeframe_tracks: HashMap::new(),
             signal_tx: None,
         }
     }
@@ -150,8 +423,13 @@ impl KrakenRegimeTrader {
             .router
             .update(pair, candle.high, candle.low, candle.close)?;
 
+        let realized_vol = self.vol_estimator_mut(pair).update(candle.close);
+        let timeframe_regimes = self.update_timeframe_tracks(pair, candle);
+
         // Convert to trade action
-        let action = self.routed_to_action(pair, candle.close, &routed);
+        let mut action = self.routed_to_action(pair, candle.close, &routed, realized_vol);
+        action.timeframe_regimes = timeframe_regimes;
+        self.apply_confirmation_gate(routed.regime, &mut action);
 
         // Send to channel if set and not Hold
         if action.action != TradeType::Hold {
@@ -168,7 +446,13 @@ impl KrakenRegimeTrader {
     }
 
     /// Process real-time tick data (aggregates into candles)
-    pub fn process_tick(&mut self, pair: &str, price: f64, timestamp: i64) -> Option<TradeAction> {
+    pub fn process_tick(
+        &mut self,
+        pair: &str,
+        price: f64,
+        volume: f64,
+        timestamp: i64,
+    ) -> Option<TradeAction> {
         let timeframe_secs = self.config.timeframe_minutes as i64 * 60;
 
         // Get or create candle builder
@@ -178,25 +462,193 @@ impl KrakenRegimeTrader {
             .or_insert_with(|| CandleBuilder::new(timeframe_secs));
 
         // Add tick to builder
-        if let Some(completed_candle) = builder.add_tick(price, timestamp) {
+        if let Some(completed_candle) = builder.add_tick(price, volume, timestamp) {
             return self.process_candle(pair, &completed_candle);
         }
 
         None
     }
 
-    fn routed_to_action(&self, pair: &str, price: f64, routed: &RoutedSignal) -> TradeAction {
+    /// Apply a level-2 order book update (snapshot or incremental delta)
+    /// for `pair`. Each level is replaced on update and removed once its
+    /// volume drops to zero, per Kraken's book-update semantics.
+    pub fn process_l2_update(&mut self, pair: &str, bids: &[BookLevel], asks: &[BookLevel]) {
+        let book = self
+            .order_books
+            .entry(pair.to_string())
+            .or_insert_with(OrderBook::new);
+        book.apply_updates(bids, asks);
+    }
+
+    /// Latest microstructure features derived from `pair`'s order book,
+    /// over the top `depth` levels of each side. `None` if no book update
+    /// has been seen yet for `pair`, or either side is empty.
+    pub fn microstructure(&self, pair: &str, depth: usize) -> Option<MicrostructureFeatures> {
+        self.order_books.get(pair)?.features(depth)
+    }
+
+    /// Mutable accessor for `pair`'s `RealizedVol` estimator, lazily
+    /// registering one seeded from `config.vol_lookback` on first use
+    fn vol_estimator_mut(&mut self, pair: &str) -> &mut RealizedVol {
+        self.vol_estimators
+            .entry(pair.to_string())
+            .or_insert_with(|| RealizedVol::new(self.config.vol_lookback))
+    }
+
+    fn routed_to_action(
+        &self,
+        pair: &str,
+        price: f64,
+        routed: &RoutedSignal,
+        realized_vol: Option<f64>,
+    ) -> TradeAction {
+        let action: TradeType = routed.signal.into();
+        let execution = &self.config.execution;
+
+        let fill_price = execution.fill_price(price, action);
+        let stop_loss = routed.stop_loss.map(|l| execution.adjust_level(l, action));
+        let take_profit = routed.take_profit.map(|l| execution.adjust_level(l, action));
+        let order_size_usd = if action == TradeType::Hold {
+            0.0
+        } else {
+            self.order_size_usd(
+                fill_price,
+                stop_loss,
+                routed.position_size_factor,
+                realized_vol,
+            )
+        };
+
         TradeAction {
             symbol: pair.to_string(),
-            action: routed.signal.into(),
-            price,
+            action,
+            price: fill_price,
             size_factor: routed.position_size_factor,
-            stop_loss: routed.stop_loss,
-            take_profit: routed.take_profit,
+            order_size_usd,
+            stop_loss,
+            take_profit,
             source_strategy: routed.source_strategy.to_string(),
             regime: routed.regime.to_string(),
             confidence: routed.confidence,
             reason: routed.reason.clone(),
+            timeframe_regimes: Vec::new(),
+        }
+    }
+
+    /// Fold `candle` into every configured confirmation timeframe and
+    /// return each one's current regime breakdown, in configured order.
+    fn update_timeframe_tracks(&mut self, pair: &str, candle: &Candle) -> Vec<TimeframeRegime> {
+        let confirmation_timeframes = self.config.confirmation_timeframes.clone();
+        let regime_config = self.config.router_config.regime_config.clone();
+
+        let tracks = self.timeframe_tracks.entry(pair.to_string()).or_insert_with(|| {
+            confirmation_timeframes
+                .iter()
+                .map(|&n| TimeframeTrack::new(n, regime_config.clone()))
+                .collect()
+        });
+
+        for track in tracks.iter_mut() {
+            track.update(candle);
+        }
+
+        tracks.iter().map(TimeframeTrack::snapshot).collect()
+    }
+
+    /// Suppress `action` to `Hold` unless a `confirmation_quorum` of
+    /// `action.timeframe_regimes` both (a) agree with `primary_regime` and
+    /// (b) - for a Buy/Sell - show at least `consecutive_run_threshold`
+    /// consecutive bars in the signal's direction. A no-op once `action`
+    /// is already `Hold`, or when `confirmation_timeframes` is the default
+    /// single, always-agreeing timeframe.
+    fn apply_confirmation_gate(&self, primary_regime: MarketRegime, action: &mut TradeAction) {
+        if action.action == TradeType::Hold {
+            return;
+        }
+
+        let quorum = self.config.confirmation_quorum;
+        let run_threshold = self.config.consecutive_run_threshold;
+        let trade_type = action.action;
+
+        let regime_agree = action
+            .timeframe_regimes
+            .iter()
+            .filter(|t| t.regime == primary_regime)
+            .count();
+        let run_confirmed = if run_threshold == 0 {
+            action.timeframe_regimes.len()
+        } else {
+            action
+                .timeframe_regimes
+                .iter()
+                .filter(|t| match trade_type {
+                    TradeType::Buy => t.consecutive_up >= run_threshold,
+                    TradeType::Sell => t.consecutive_down >= run_threshold,
+                    TradeType::Hold => true,
+                })
+                .count()
+        };
+
+        if regime_agree < quorum || run_confirmed < quorum {
+            action.action = TradeType::Hold;
+            action.size_factor = 0.0;
+            action.order_size_usd = 0.0;
+            action.reason = format!(
+                "{} (suppressed: {}/{} timeframes confirm regime, {}/{} confirm run)",
+                action.reason, regime_agree, quorum, run_confirmed, quorum
+            );
+        }
+    }
+
+    /// Dollar order size for a trade: risk `risk_per_trade_pct` of
+    /// `max_trade_usd` against the `stop_loss` distance from `fill_price`,
+    /// scale by the regime's `position_size_factor` and the volatility-target
+    /// multiplier (see `volatility_target_scale`), then clamp to
+    /// `[min_trade_usd, max_trade_usd]`. Falls back to a flat
+    /// `max_trade_usd` basis when there's no stop-loss to size against.
+    /// `0.0` whenever `position_size_factor` is zero (e.g. `Hold`).
+    fn order_size_usd(
+        &self,
+        fill_price: f64,
+        stop_loss: Option<f64>,
+        position_size_factor: f64,
+        realized_vol: Option<f64>,
+    ) -> f64 {
+        if position_size_factor <= 0.0 {
+            return 0.0;
+        }
+
+        let risk_usd = self.config.max_trade_usd * (self.config.risk_per_trade_pct / 100.0);
+
+        let raw_size_usd = match stop_loss {
+            Some(stop) if fill_price > 0.0 => {
+                let stop_distance_pct = ((fill_price - stop) / fill_price).abs();
+                if stop_distance_pct > 0.0 {
+                    risk_usd / stop_distance_pct
+                } else {
+                    self.config.max_trade_usd
+                }
+            }
+            _ => self.config.max_trade_usd,
+        };
+
+        let vol_scale = self.volatility_target_scale(realized_vol);
+
+        (raw_size_usd * position_size_factor * vol_scale)
+            .clamp(self.config.min_trade_usd, self.config.max_trade_usd)
+    }
+
+    /// Multiplier that scales position size inversely with realized
+    /// volatility, so that quantity × realized vol holds roughly constant
+    /// at `target_volatility`: `target_volatility / realized_vol`, capped at
+    /// `max_leverage` so a quiet market doesn't scale size up without bound.
+    /// `1.0` (no-op) until `realized_vol` has warmed up.
+    fn volatility_target_scale(&self, realized_vol: Option<f64>) -> f64 {
+        match realized_vol {
+            Some(vol) if vol > 0.0 => {
+                (self.config.target_volatility / vol).min(self.config.max_leverage)
+            }
+            _ => 1.0,
         }
     }
 
@@ -211,6 +663,8 @@ impl KrakenRegimeTrader {
         for candle in candles {
             self.router
                 .update(pair, candle.high, candle.low, candle.close);
+            self.vol_estimator_mut(pair).update(candle.close);
+            self.update_timeframe_tracks(pair, candle);
         }
 
         if self.router.is_ready(pair) {
@@ -268,6 +722,142 @@ pub struct PairStatus {
     pub regime_changes: u32,
 }
 
+/// Rolling realized-vol estimate over a trailing window of closes, feeding
+/// `KrakenRegimeTrader::volatility_target_scale`. Tracks log returns rather
+/// than raw price deltas so the estimate is comparable across symbols at
+/// different price levels.
+#[derive(Debug)]
+struct RealizedVol {
+    window: usize,
+    last_close: Option<f64>,
+    returns: VecDeque<f64>,
+}
+
+impl RealizedVol {
+    fn new(window: usize) -> Self {
+        Self {
+            window: window.max(2),
+            last_close: None,
+            returns: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Feed a new close and return the sample standard deviation of
+    /// log returns over the trailing window, or `None` until at least two
+    /// returns have accumulated.
+    fn update(&mut self, close: f64) -> Option<f64> {
+        if let Some(prev) = self.last_close {
+            if prev > 0.0 && close > 0.0 {
+                self.returns.push_back((close / prev).ln());
+                if self.returns.len() > self.window {
+                    self.returns.pop_front();
+                }
+            }
+        }
+        self.last_close = Some(close);
+
+        if self.returns.len() < 2 {
+            return None;
+        }
+        let n = self.returns.len() as f64;
+        let mean = self.returns.iter().sum::<f64>() / n;
+        let variance = self
+            .returns
+            .iter()
+            .map(|r| (r - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0);
+        Some(variance.sqrt())
+    }
+}
+
+/// One confirmation timeframe's state: folds `candles_per_bar` consecutive
+/// base candles (a tumbling window over the pair's own candle stream, not a
+/// wall-clock-aligned bucket) into one coarser bar, runs its own
+/// `RegimeDetector` against those bars, and tracks how many consecutive
+/// bullish/bearish closes it has seen - see
+/// `KrakenIntegrationConfig::confirmation_timeframes`.
+#[derive(Debug)]
+struct TimeframeTrack {
+    candles_per_bar: usize,
+    candles_in_bar: usize,
+    partial: Option<PartialCandle>,
+    detector: RegimeDetector,
+    consecutive_up: u32,
+    consecutive_down: u32,
+    last_regime: MarketRegime,
+    last_confidence: f64,
+}
+
+impl TimeframeTrack {
+    fn new(candles_per_bar: usize, regime_config: RegimeConfig) -> Self {
+        Self {
+            candles_per_bar: candles_per_bar.max(1),
+            candles_in_bar: 0,
+            partial: None,
+            detector: RegimeDetector::new(regime_config),
+            consecutive_up: 0,
+            consecutive_down: 0,
+            last_regime: MarketRegime::Uncertain,
+            last_confidence: 0.0,
+        }
+    }
+
+    /// Fold one base candle in. Runs the timeframe's own `RegimeDetector`
+    /// and updates the consecutive-run counters once `candles_per_bar`
+    /// base candles have completed a bar; a no-op while the bar is still
+    /// forming.
+    fn update(&mut self, candle: &Candle) {
+        match &mut self.partial {
+            Some(partial) => {
+                partial.high = partial.high.max(candle.high);
+                partial.low = partial.low.min(candle.low);
+                partial.close = candle.close;
+                partial.volume += candle.volume;
+                partial.price_vol_sum += candle.vwap * candle.volume;
+            }
+            None => {
+                self.partial = Some(PartialCandle::from_child(candle.timestamp, candle));
+            }
+        }
+        self.candles_in_bar += 1;
+
+        if self.candles_in_bar < self.candles_per_bar {
+            return;
+        }
+        let bar = self.partial.take().unwrap().to_candle();
+        self.candles_in_bar = 0;
+
+        if bar.close > bar.open {
+            self.consecutive_up += 1;
+            self.consecutive_down = 0;
+        } else if bar.close < bar.open {
+            self.consecutive_down += 1;
+            self.consecutive_up = 0;
+        }
+
+        // Use the volume-less `update()`, matching the primary `StrategyRouter`
+        // path (which never receives volume) - otherwise a single default
+        // `confirmation_timeframes: [1]` timeframe could score a breakout
+        // differently than the primary detector on the very same bar, and
+        // the "always agrees" no-op guarantee documented on `apply_confirmation_gate`
+        // would silently break.
+        let confidence = self.detector.update(bar.high, bar.low, bar.close);
+        self.last_regime = confidence.regime;
+        self.last_confidence = confidence.confidence;
+    }
+
+    fn snapshot(&self) -> TimeframeRegime {
+        TimeframeRegime {
+            candles_per_bar: self.candles_per_bar,
+            regime: self.last_regime,
+            confidence: self.last_confidence,
+            consecutive_up: self.consecutive_up,
+            consecutive_down: self.consecutive_down,
+        }
+    }
+}
+
 /// Builds candles from tick data
 #[derive(Debug)]
 struct CandleBuilder {
@@ -283,6 +873,57 @@ struct PartialCandle {
     low: f64,
     close: f64,
     volume: f64,
+    /// Running `sum(price * volume)`, divided by `volume` on completion to
+    /// get the candle's VWAP.
+    price_vol_sum: f64,
+}
+
+impl PartialCandle {
+    fn start(start_time: i64, price: f64, volume: f64) -> Self {
+        Self {
+            start_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            price_vol_sum: price * volume,
+        }
+    }
+
+    /// Seed a partial from a just-completed child candle being folded into
+    /// a coarser resolution (see `MultiResolutionBuilder::fold`).
+    fn from_child(start_time: i64, child: &Candle) -> Self {
+        Self {
+            start_time,
+            open: child.open,
+            high: child.high,
+            low: child.low,
+            close: child.close,
+            volume: child.volume,
+            price_vol_sum: child.vwap * child.volume,
+        }
+    }
+
+    fn vwap(&self) -> f64 {
+        if self.volume > 0.0 {
+            self.price_vol_sum / self.volume
+        } else {
+            self.close
+        }
+    }
+
+    fn to_candle(&self) -> Candle {
+        Candle {
+            timestamp: self.start_time,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            vwap: self.vwap(),
+        }
+    }
 }
 
 impl CandleBuilder {
@@ -293,7 +934,7 @@ impl CandleBuilder {
         }
     }
 
-    fn add_tick(&mut self, price: f64, timestamp: i64) -> Option<Candle> {
+    fn add_tick(&mut self, price: f64, volume: f64, timestamp: i64) -> Option<Candle> {
         let candle_start = (timestamp / self.timeframe_secs) * self.timeframe_secs;
 
         match &mut self.current_candle {
@@ -302,48 +943,205 @@ impl CandleBuilder {
                 candle.high = candle.high.max(price);
                 candle.low = candle.low.min(price);
                 candle.close = price;
-                candle.volume += 1.0; // Simplified - real impl would use actual volume
+                candle.volume += volume;
+                candle.price_vol_sum += price * volume;
                 None
             }
             Some(candle) => {
                 // New candle period - complete the old one
-                let completed = Candle {
-                    timestamp: candle.start_time,
-                    open: candle.open,
-                    high: candle.high,
-                    low: candle.low,
-                    close: candle.close,
-                    volume: candle.volume,
-                };
+                let completed = candle.to_candle();
 
                 // Start new candle
-                self.current_candle = Some(PartialCandle {
-                    start_time: candle_start,
-                    open: price,
-                    high: price,
-                    low: price,
-                    close: price,
-                    volume: 1.0,
-                });
+                self.current_candle = Some(PartialCandle::start(candle_start, price, volume));
 
                 Some(completed)
             }
             None => {
                 // First tick
-                self.current_candle = Some(PartialCandle {
-                    start_time: candle_start,
-                    open: price,
-                    high: price,
-                    low: price,
-                    close: price,
-                    volume: 1.0,
-                });
+                self.current_candle = Some(PartialCandle::start(candle_start, price, volume));
                 None
             }
         }
     }
 }
 
+/// Timeframe a `MultiResolutionBuilder` aggregates the base tick stream into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+impl Resolution {
+    /// Every resolution `MultiResolutionBuilder` tracks, smallest to
+    /// largest - the order completions must cascade in, since a single
+    /// tick can roll several buckets at once (e.g. 1m, 5m and 15m all
+    /// close at `:00`)
+    pub const ALL: [Resolution; 6] = [
+        Resolution::OneMinute,
+        Resolution::FiveMinutes,
+        Resolution::FifteenMinutes,
+        Resolution::OneHour,
+        Resolution::FourHours,
+        Resolution::OneDay,
+    ];
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Resolution::OneMinute => 60,
+            Resolution::FiveMinutes => 5 * 60,
+            Resolution::FifteenMinutes => 15 * 60,
+            Resolution::OneHour => 60 * 60,
+            Resolution::FourHours => 4 * 60 * 60,
+            Resolution::OneDay => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Aggregates one tick stream into every `Resolution` at once, so the
+/// router can consume several timeframes simultaneously (e.g. regime
+/// detection on `OneHour` while signals fire on `FifteenMinutes`).
+///
+/// Maintains the base 1-minute candle via `CandleBuilder`, then folds each
+/// completed base candle into every coarser resolution's open partial
+/// (`open` = first child's open, `high`/`low` = running max/min, `close` =
+/// last child's close, `volume` = sum of children). A higher-timeframe
+/// candle completes once the incoming tick's bucket boundary
+/// (`(ts / res_secs) * res_secs`) moves past the partial's own.
+#[derive(Debug)]
+pub struct MultiResolutionBuilder {
+    base: CandleBuilder,
+    partials: HashMap<Resolution, PartialCandle>,
+}
+
+impl MultiResolutionBuilder {
+    pub fn new() -> Self {
+        Self {
+            base: CandleBuilder::new(Resolution::OneMinute.seconds()),
+            partials: HashMap::new(),
+        }
+    }
+
+    /// Feed one tick in. Returns whichever resolutions closed on this
+    /// tick - possibly several at once - cascading from `OneMinute` up
+    /// through `OneDay`.
+    pub fn process_tick(&mut self, price: f64, volume: f64, timestamp: i64) -> HashMap<Resolution, Candle> {
+        let mut closed = HashMap::new();
+
+        let Some(base_candle) = self.base.add_tick(price, volume, timestamp) else {
+            return closed;
+        };
+        closed.insert(Resolution::OneMinute, base_candle.clone());
+
+        for resolution in Resolution::ALL.into_iter().skip(1) {
+            if let Some(completed) = Self::fold(&mut self.partials, resolution, &base_candle, timestamp) {
+                closed.insert(resolution, completed);
+            }
+        }
+
+        closed
+    }
+
+    /// Fold a just-completed base candle into `resolution`'s open partial,
+    /// emitting the partial once the bucket of `tick_ts` (the tick that
+    /// closed `child`) has moved past the partial's own
+    fn fold(
+        partials: &mut HashMap<Resolution, PartialCandle>,
+        resolution: Resolution,
+        child: &Candle,
+        tick_ts: i64,
+    ) -> Option<Candle> {
+        let bucket_secs = resolution.seconds();
+        let bucket_start = (child.timestamp / bucket_secs) * bucket_secs;
+
+        let mut completed = None;
+        match partials.entry(resolution) {
+            Entry::Occupied(mut occupied) if occupied.get().start_time == bucket_start => {
+                let partial = occupied.get_mut();
+                partial.high = partial.high.max(child.high);
+                partial.low = partial.low.min(child.low);
+                partial.close = child.close;
+                partial.volume += child.volume;
+                partial.price_vol_sum += child.vwap * child.volume;
+            }
+            Entry::Occupied(mut occupied) => {
+                completed = Some(occupied.get().to_candle());
+                occupied.insert(PartialCandle::from_child(bucket_start, child));
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(PartialCandle::from_child(bucket_start, child));
+            }
+        }
+
+        if completed.is_none() && (tick_ts / bucket_secs) * bucket_secs != bucket_start {
+            completed = partials.remove(&resolution).map(|p| p.to_candle());
+        }
+        completed
+    }
+}
+
+impl Default for MultiResolutionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod multi_resolution_tests {
+    use super::*;
+
+    #[test]
+    fn test_single_minute_ticks_emit_only_one_minute_candles() {
+        let mut builder = MultiResolutionBuilder::new();
+        let mut last_closed: HashMap<Resolution, Candle> = HashMap::new();
+
+        for i in 0..5 {
+            last_closed = builder.process_tick(100.0 + i as f64, 1.0, i * 60);
+        }
+
+        assert!(last_closed.contains_key(&Resolution::OneMinute));
+        assert!(!last_closed.contains_key(&Resolution::FiveMinutes));
+    }
+
+    #[test]
+    fn test_five_minute_boundary_cascades_one_and_five_minute_candles() {
+        let mut builder = MultiResolutionBuilder::new();
+        let mut closed_at_boundary: HashMap<Resolution, Candle> = HashMap::new();
+
+        // Six 1-minute ticks: the sixth rolls over both the 1m and 5m buckets
+        for i in 0..6 {
+            closed_at_boundary = builder.process_tick(100.0 + i as f64, 1.0, i * 60);
+        }
+
+        assert!(closed_at_boundary.contains_key(&Resolution::OneMinute));
+        assert!(closed_at_boundary.contains_key(&Resolution::FiveMinutes));
+
+        let five_min = &closed_at_boundary[&Resolution::FiveMinutes];
+        assert_eq!(five_min.open, 100.0);
+        assert_eq!(five_min.high, 104.0);
+        assert_eq!(five_min.low, 100.0);
+        assert_eq!(five_min.close, 104.0);
+    }
+
+    #[test]
+    fn test_fifteen_minutes_of_ticks_also_closes_the_fifteen_minute_bucket() {
+        let mut builder = MultiResolutionBuilder::new();
+        let mut last_closed: HashMap<Resolution, Candle> = HashMap::new();
+
+        for i in 0..16 {
+            last_closed = builder.process_tick(100.0, 1.0, i * 60);
+        }
+
+        assert!(last_closed.contains_key(&Resolution::OneMinute));
+        assert!(last_closed.contains_key(&Resolution::FiveMinutes));
+        assert!(last_closed.contains_key(&Resolution::FifteenMinutes));
+    }
+}
+
 // ============================================================================
 // Example integration with your existing Kraken WebSocket
 // ============================================================================
@@ -359,6 +1157,7 @@ pub mod websocket_integration {
     pub enum KrakenWsMessage {
         Trade(TradeMessage),
         Ohlc(OhlcMessage),
+        Book(BookMessage),
         // Add other message types as needed
     }
 
@@ -366,6 +1165,7 @@ pub mod websocket_integration {
     pub struct TradeMessage {
         pub pair: String,
         pub price: String,
+        pub volume: String,
         pub timestamp: String,
     }
 
@@ -377,6 +1177,7 @@ pub mod websocket_integration {
         pub high: String,
         pub low: String,
         pub close: String,
+        pub vwap: String,
         pub volume: String,
     }
 
@@ -389,10 +1190,47 @@ pub mod websocket_integration {
                 low: self.low.parse().ok()?,
                 close: self.close.parse().ok()?,
                 volume: self.volume.parse().ok()?,
+                vwap: self.vwap.parse().ok()?,
             })
         }
     }
 
+    /// One price level in a `BookMessage`'s snapshot/update payload
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct BookLevelMessage {
+        pub price: String,
+        pub volume: String,
+    }
+
+    /// Level-2 order book snapshot or incremental update from Kraken's
+    /// book feed. Snapshots populate both `bids` and `asks` in full;
+    /// incremental updates carry only the levels that changed.
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct BookMessage {
+        pub pair: String,
+        #[serde(default)]
+        pub bids: Vec<BookLevelMessage>,
+        #[serde(default)]
+        pub asks: Vec<BookLevelMessage>,
+    }
+
+    impl BookMessage {
+        fn parse_levels(levels: &[BookLevelMessage]) -> Vec<BookLevel> {
+            levels
+                .iter()
+                .filter_map(|level| Some((level.price.parse().ok()?, level.volume.parse().ok()?)))
+                .collect()
+        }
+
+        pub fn bid_levels(&self) -> Vec<BookLevel> {
+            Self::parse_levels(&self.bids)
+        }
+
+        pub fn ask_levels(&self) -> Vec<BookLevel> {
+            Self::parse_levels(&self.asks)
+        }
+    }
+
     /// Example handler - integrate with your WebSocket loop
     pub async fn handle_ws_message(
         trader: &mut KrakenRegimeTrader,
@@ -401,13 +1239,18 @@ pub mod websocket_integration {
         match msg {
             KrakenWsMessage::Trade(trade) => {
                 let price: f64 = trade.price.parse().ok()?;
+                let volume: f64 = trade.volume.parse().ok()?;
                 let timestamp: i64 = trade.timestamp.parse::<f64>().ok()? as i64;
-                trader.process_tick(&trade.pair, price, timestamp)
+                trader.process_tick(&trade.pair, price, volume, timestamp)
             }
             KrakenWsMessage::Ohlc(ohlc) => {
                 let candle = ohlc.to_candle()?;
                 trader.process_candle(&ohlc.pair, &candle)
             }
+            KrakenWsMessage::Book(book) => {
+                trader.process_l2_update(&book.pair, &book.bid_levels(), &book.ask_levels());
+                None
+            }
         }
     }
 }
@@ -448,6 +1291,7 @@ pub mod rest_integration {
                                     .as_str()
                                     .and_then(|s| s.parse().ok())
                                     .unwrap_or(0.0),
+                                vwap: ohlc[5].as_str().and_then(|s| s.parse().ok()).unwrap_or(0.0),
                             };
                             candles.push(candle);
                         }
@@ -459,6 +1303,76 @@ pub mod rest_integration {
         candles
     }
 
+    /// `result.last` from a Kraken OHLC response: the `since` cursor to pass
+    /// on the next request to continue right after this page.
+    fn next_since(response: &KrakenOhlcResponse) -> Option<i64> {
+        response.result.get("last").and_then(|v| {
+            v.as_i64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        })
+    }
+
+    /// Minimum gap between successive requests in [`fetch_history_paginated`],
+    /// to stay well under Kraken's public-endpoint rate limit.
+    pub const PAGINATION_THROTTLE: std::time::Duration = std::time::Duration::from_millis(1_000);
+
+    /// Page through `OHLC?pair=...&since=...` from `from` up to `to`,
+    /// following the `last` cursor each response returns, so a warmup that
+    /// needs more than the ~720-candle single-request cap still completes
+    /// in one call.
+    ///
+    /// Stops once a page returns no candle past the previous page's cursor
+    /// (the feed is caught up) or a candle at/after `to` is reached.
+    /// Candles are de-duplicated on `timestamp`, since Kraken always
+    /// re-returns the most recent, possibly still-forming candle as the
+    /// first row of the next page.
+    pub async fn fetch_history_paginated(
+        pair: &str,
+        interval: u32, // minutes
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<Candle>, Box<dyn std::error::Error + Send + Sync>> {
+        let kraken_pair = convert_pair_format(pair);
+        let mut candles: Vec<Candle> = Vec::new();
+        let mut seen_timestamps = std::collections::HashSet::new();
+        let mut since = from;
+
+        loop {
+            let url = format!(
+                "https://api.kraken.com/0/public/OHLC?pair={}&interval={}&since={}",
+                kraken_pair, interval, since
+            );
+            let response: KrakenOhlcResponse = reqwest::get(&url).await?.json().await?;
+            if !response.error.is_empty() {
+                return Err(format!("Kraken API error: {:?}", response.error).into());
+            }
+
+            let page = parse_ohlc_response(&response, &kraken_pair);
+            let new_candles = page
+                .into_iter()
+                .filter(|c| seen_timestamps.insert(c.timestamp))
+                .collect::<Vec<_>>();
+
+            let Some(cursor) = next_since(&response) else {
+                break;
+            };
+            let made_progress = cursor > since && !new_candles.is_empty();
+
+            candles.extend(new_candles);
+            candles.retain(|c| c.timestamp <= to);
+
+            if !made_progress || cursor >= to {
+                break;
+            }
+            since = cursor;
+
+            tokio::time::sleep(PAGINATION_THROTTLE).await;
+        }
+
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+
     /// Example: Fetch historical OHLC for warmup
     /// Integrate with your existing API client
     pub async fn fetch_historical_ohlc(
@@ -498,20 +1412,21 @@ pub mod rest_integration {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::regime::TrendDirection;
 
     #[test]
     fn test_candle_builder() {
         let mut builder = CandleBuilder::new(60); // 1 minute candles
 
         // First tick
-        assert!(builder.add_tick(100.0, 0).is_none());
+        assert!(builder.add_tick(100.0, 1.0, 0).is_none());
 
         // More ticks in same period
-        assert!(builder.add_tick(101.0, 30).is_none());
-        assert!(builder.add_tick(99.0, 45).is_none());
+        assert!(builder.add_tick(101.0, 1.0, 30).is_none());
+        assert!(builder.add_tick(99.0, 1.0, 45).is_none());
 
         // New period - should complete candle
-        let candle = builder.add_tick(102.0, 60);
+        let candle = builder.add_tick(102.0, 1.0, 60);
         assert!(candle.is_some());
 
         let c = candle.unwrap();
@@ -519,6 +1434,19 @@ mod tests {
         assert_eq!(c.high, 101.0);
         assert_eq!(c.low, 99.0);
         assert_eq!(c.close, 99.0);
+        assert_eq!(c.vwap, 100.0); // (100 + 101 + 99) / 3, all equal volume
+    }
+
+    #[test]
+    fn test_candle_builder_weights_vwap_by_volume() {
+        let mut builder = CandleBuilder::new(60);
+
+        builder.add_tick(100.0, 1.0, 0);
+        builder.add_tick(200.0, 3.0, 30);
+        let candle = builder.add_tick(100.0, 1.0, 60).unwrap();
+
+        // (100*1 + 200*3) / (1 + 3) = 175
+        assert_eq!(candle.vwap, 175.0);
     }
 
     #[tokio::test]
@@ -528,4 +1456,218 @@ mod tests {
 
         assert!(!trader.is_ready("BTC/USD")); // Not warmed up yet
     }
+
+    #[test]
+    fn test_execution_model_fills_buys_above_and_sells_below_raw_price() {
+        let model = ExecutionModel { spread_pct: 0.002 };
+
+        assert_eq!(model.fill_price(100.0, TradeType::Buy), 100.1);
+        assert_eq!(model.fill_price(100.0, TradeType::Sell), 99.9);
+        assert_eq!(model.fill_price(100.0, TradeType::Hold), 100.0);
+    }
+
+    #[test]
+    fn test_execution_model_adjusts_stop_levels_by_the_same_offset() {
+        let model = ExecutionModel { spread_pct: 0.002 };
+
+        // A stop-loss computed off a 100.0 close shifts the same way the
+        // fill price does, so its distance from the fill stays correct.
+        assert!((model.adjust_level(95.0, TradeType::Buy) - 95.095).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_order_size_scales_with_stop_distance_and_clamps_to_config() {
+        let mut config = KrakenIntegrationConfig::default();
+        config.min_trade_usd = 10.0;
+        config.max_trade_usd = 1000.0;
+        config.risk_per_trade_pct = 1.0; // risk $10 (1% of max_trade_usd) per trade
+        let trader = KrakenRegimeTrader::new(config);
+
+        // 2% stop distance: $10 risk / 2% = $500 notional
+        let size = trader.order_size_usd(100.0, Some(98.0), 1.0, None);
+        assert!((size - 500.0).abs() < 1e-6);
+
+        // Halved by a half-confidence position size factor
+        let half_size = trader.order_size_usd(100.0, Some(98.0), 0.5, None);
+        assert!((half_size - 250.0).abs() < 1e-6);
+
+        // Zero factor (e.g. Hold) sizes to nothing
+        assert_eq!(trader.order_size_usd(100.0, Some(98.0), 0.0, None), 0.0);
+
+        // Clamped to max_trade_usd when the stop is very tight
+        let clamped = trader.order_size_usd(100.0, Some(99.9), 1.0, None);
+        assert_eq!(clamped, 1000.0);
+    }
+
+    #[test]
+    fn test_order_size_scales_inversely_with_realized_volatility() {
+        let mut config = KrakenIntegrationConfig::default();
+        config.min_trade_usd = 10.0;
+        config.max_trade_usd = 1000.0;
+        config.risk_per_trade_pct = 1.0;
+        config.target_volatility = 0.01;
+        config.max_leverage = 2.0;
+        let trader = KrakenRegimeTrader::new(config);
+
+        // Realized vol double the target halves the size
+        let base = trader.order_size_usd(100.0, Some(98.0), 1.0, None);
+        let scaled_down = trader.order_size_usd(100.0, Some(98.0), 1.0, Some(0.02));
+        assert!((scaled_down - base / 2.0).abs() < 1e-6);
+
+        // A quiet market (vol well under target) is capped at max_leverage
+        let scaled_up = trader.order_size_usd(100.0, Some(98.0), 1.0, Some(0.001));
+        assert!((scaled_up - (base * 2.0).min(1000.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_realized_vol_estimator_warms_up_then_tracks_return_dispersion() {
+        let mut estimator = RealizedVol::new(20);
+        assert_eq!(estimator.update(100.0), None);
+        assert_eq!(estimator.update(101.0), None, "only one return so far");
+
+        let mut last = None;
+        for price in [99.0, 102.0, 98.0, 103.0, 97.0] {
+            last = estimator.update(price);
+        }
+        assert!(last.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_order_book_replaces_and_removes_levels() {
+        let mut book = OrderBook::new();
+        book.apply_updates(&[(100.0, 1.0), (99.0, 2.0)], &[(101.0, 1.5)]);
+
+        assert_eq!(book.best_bid(), Some((100.0, 1.0)));
+        assert_eq!(book.best_ask(), Some((101.0, 1.5)));
+
+        // Zero volume removes the level
+        book.apply_updates(&[(100.0, 0.0)], &[]);
+        assert_eq!(book.best_bid(), Some((99.0, 2.0)));
+    }
+
+    #[test]
+    fn test_order_book_imbalance_favors_the_heavier_side() {
+        let mut book = OrderBook::new();
+        book.apply_updates(&[(100.0, 3.0)], &[(101.0, 1.0)]);
+
+        let imbalance = book.imbalance(10).unwrap();
+        assert!(imbalance > 0.0, "bid-heavy book should have positive imbalance");
+    }
+
+    #[test]
+    fn test_trader_exposes_microstructure_after_l2_update() {
+        let config = KrakenIntegrationConfig::default();
+        let mut trader = KrakenRegimeTrader::new(config);
+
+        assert!(trader.microstructure("BTC/USD", DEFAULT_BOOK_DEPTH).is_none());
+
+        trader.process_l2_update("BTC/USD", &[(100.0, 2.0)], &[(101.0, 1.0)]);
+
+        let features = trader
+            .microstructure("BTC/USD", DEFAULT_BOOK_DEPTH)
+            .unwrap();
+        assert_eq!(features.spread, 1.0);
+    }
+
+    fn candle(open: f64, high: f64, low: f64, close: f64, timestamp: i64) -> Candle {
+        Candle { timestamp, open, high, low, close, volume: 1.0, vwap: close }
+    }
+
+    #[test]
+    fn test_timeframe_track_folds_n_candles_and_tracks_consecutive_runs() {
+        let mut track = TimeframeTrack::new(3, RegimeConfig::default());
+
+        // First two candles of the 3-candle bar don't complete it yet
+        track.update(&candle(100.0, 101.0, 99.0, 100.5, 0));
+        track.update(&candle(100.5, 101.5, 100.0, 101.0, 60));
+        assert_eq!(track.consecutive_up, 0);
+
+        // Third candle completes a bullish bar (close 101.5 > open 100.0)
+        track.update(&candle(101.0, 101.5, 100.5, 101.5, 120));
+        assert_eq!(track.consecutive_up, 1);
+        assert_eq!(track.consecutive_down, 0);
+
+        // A bearish 3-candle bar resets the up streak and starts a down one
+        for ts in [180, 240, 300] {
+            track.update(&candle(101.0, 101.0, 95.0, 95.5, ts));
+        }
+        assert_eq!(track.consecutive_up, 0);
+        assert_eq!(track.consecutive_down, 1);
+    }
+
+    #[test]
+    fn test_confirmation_gate_suppresses_entry_when_quorum_not_met() {
+        let mut config = KrakenIntegrationConfig::default();
+        config.confirmation_quorum = 2;
+        let trader = KrakenRegimeTrader::new(config);
+
+        let mut action = TradeAction {
+            symbol: "BTC/USD".to_string(),
+            action: TradeType::Buy,
+            price: 100.0,
+            size_factor: 1.0,
+            order_size_usd: 500.0,
+            stop_loss: None,
+            take_profit: None,
+            source_strategy: "Trend".to_string(),
+            regime: "Trending".to_string(),
+            confidence: 0.8,
+            reason: "breakout".to_string(),
+            timeframe_regimes: vec![
+                TimeframeRegime {
+                    candles_per_bar: 1,
+                    regime: MarketRegime::Trending(TrendDirection::Bullish),
+                    confidence: 0.8,
+                    consecutive_up: 0,
+                    consecutive_down: 0,
+                },
+                TimeframeRegime {
+                    candles_per_bar: 5,
+                    regime: MarketRegime::MeanReverting,
+                    confidence: 0.5,
+                    consecutive_up: 0,
+                    consecutive_down: 0,
+                },
+            ],
+        };
+
+        // Only one of two confirmation timeframes agrees with the primary
+        // regime, below the quorum of 2 - the entry is suppressed
+        trader.apply_confirmation_gate(MarketRegime::Trending(TrendDirection::Bullish), &mut action);
+        assert_eq!(action.action, TradeType::Hold);
+        assert_eq!(action.order_size_usd, 0.0);
+    }
+
+    #[test]
+    fn test_confirmation_gate_passes_entry_when_run_threshold_met() {
+        let mut config = KrakenIntegrationConfig::default();
+        config.confirmation_quorum = 1;
+        config.consecutive_run_threshold = 2;
+        let trader = KrakenRegimeTrader::new(config);
+
+        let mut action = TradeAction {
+            symbol: "BTC/USD".to_string(),
+            action: TradeType::Buy,
+            price: 100.0,
+            size_factor: 1.0,
+            order_size_usd: 500.0,
+            stop_loss: None,
+            take_profit: None,
+            source_strategy: "Trend".to_string(),
+            regime: "Trending".to_string(),
+            confidence: 0.8,
+            reason: "breakout".to_string(),
+            timeframe_regimes: vec![TimeframeRegime {
+                candles_per_bar: 1,
+                regime: MarketRegime::Trending(TrendDirection::Bullish),
+                confidence: 0.8,
+                consecutive_up: 2,
+                consecutive_down: 0,
+            }],
+        };
+
+        trader.apply_confirmation_gate(MarketRegime::Trending(TrendDirection::Bullish), &mut action);
+        assert_eq!(action.action, TradeType::Buy);
+        assert_eq!(action.order_size_usd, 500.0);
+    }
 }
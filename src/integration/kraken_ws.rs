@@ -0,0 +1,560 @@
+//! Typed deserialization for Kraken's public WebSocket v1 frames.
+//!
+//! The feed multiplexes two message shapes on one socket: JSON objects
+//! carrying an `"event"` field (subscription acks, heartbeats, errors) and
+//! JSON arrays `[channelId, payload, channelName, pair]` carrying market
+//! data, where `payload`'s own shape depends on `channelName`. Modeling
+//! this as an `#[serde(untagged)]` enum plus hand-written positional
+//! `Deserialize` impls turns a malformed or unexpected frame into a typed
+//! parse error instead of a silently-zeroed candle.
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+
+/// Any message that can arrive on the Kraken public WebSocket.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KrakenWsFrame {
+    /// `{"event": ..., ...}` — subscription status, heartbeats, errors.
+    Control(ControlMessage),
+    /// `[channelId, payload, channelName, pair]` — market data.
+    Data(DataFrame),
+}
+
+/// A control/event message. Only `event` is modeled explicitly; the rest
+/// (status, channel name, error text, ...) varies by event type and is kept
+/// as raw JSON for callers that care.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ControlMessage {
+    pub event: String,
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ControlMessage {
+    /// Interpret `event` and the flattened payload as a typed `KrakenEvent`.
+    /// Falls back to `KrakenEvent::Other` for an event kind we don't model,
+    /// or whose payload doesn't match the shape we expect.
+    pub fn event_kind(&self) -> KrakenEvent {
+        let payload = serde_json::Value::Object(self.fields.clone());
+        match self.event.as_str() {
+            "systemStatus" => serde_json::from_value(payload)
+                .map(KrakenEvent::SystemStatus)
+                .unwrap_or_else(|_| KrakenEvent::Other(self.event.clone())),
+            "subscriptionStatus" => serde_json::from_value(payload)
+                .map(KrakenEvent::SubscriptionStatus)
+                .unwrap_or_else(|_| KrakenEvent::Other(self.event.clone())),
+            "heartbeat" => KrakenEvent::Heartbeat,
+            "pong" => KrakenEvent::Pong,
+            other => KrakenEvent::Other(other.to_string()),
+        }
+    }
+}
+
+/// `systemStatus` event payload: Kraken's online/maintenance/cancel-only
+/// status for the whole feed, sent once right after connecting.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemStatus {
+    pub status: String,
+    pub version: Option<String>,
+    #[serde(rename = "connectionID")]
+    pub connection_id: Option<u64>,
+}
+
+/// `subscriptionStatus` event payload: an acknowledgement (or rejection) of
+/// one `{"event": "subscribe", ...}` request sent earlier on the same
+/// connection.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubscriptionStatus {
+    /// `"subscribed"`, `"unsubscribed"`, or `"error"`.
+    pub status: String,
+    pub pair: Option<String>,
+    #[serde(rename = "channelName")]
+    pub channel_name: Option<String>,
+    #[serde(rename = "channelID")]
+    pub channel_id: Option<i64>,
+    #[serde(rename = "errorMessage")]
+    pub error_message: Option<String>,
+}
+
+/// A typed view of a `ControlMessage`'s `event` field, covering the event
+/// kinds a live connection actually needs to act on: system status,
+/// subscription acks, and the heartbeat/pong keepalive frames that signal
+/// the connection is still open.
+#[derive(Debug, Clone)]
+pub enum KrakenEvent {
+    SystemStatus(SystemStatus),
+    SubscriptionStatus(SubscriptionStatus),
+    Heartbeat,
+    Pong,
+    /// An event kind we don't model explicitly (e.g. `"error"`), carrying
+    /// its `event` string.
+    Other(String),
+}
+
+/// `[channelId, payload, channelName, pair]`. `payload`'s shape depends on
+/// `channelName`'s prefix (`"ohlc-*"` vs `"ticker"`), so this can't be
+/// `#[derive(Deserialize)]`d directly — the array is read positionally and
+/// dispatched on the channel name.
+#[derive(Debug, Clone)]
+pub enum DataFrame {
+    Ohlc {
+        channel_id: i64,
+        payload: OhlcPayload,
+        pair: String,
+    },
+    Ticker {
+        channel_id: i64,
+        payload: TickerPayload,
+        pair: String,
+    },
+    /// A channel we don't model yet; kept so an otherwise well-formed frame
+    /// doesn't become a parse error just because it's unfamiliar.
+    Other {
+        channel_id: i64,
+        channel_name: String,
+        pair: String,
+    },
+}
+
+impl<'de> Deserialize<'de> for DataFrame {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DataFrameVisitor;
+
+        impl<'de> Visitor<'de> for DataFrameVisitor {
+            type Value = DataFrame;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a [channelId, payload, channelName, pair] array")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let channel_id: i64 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let payload: serde_json::Value = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let channel_name: String = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                let pair: String = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+
+                if channel_name.starts_with("ohlc") {
+                    let payload = serde_json::from_value(payload).map_err(de::Error::custom)?;
+                    Ok(DataFrame::Ohlc {
+                        channel_id,
+                        payload,
+                        pair,
+                    })
+                } else if channel_name == "ticker" {
+                    let payload = serde_json::from_value(payload).map_err(de::Error::custom)?;
+                    Ok(DataFrame::Ticker {
+                        channel_id,
+                        payload,
+                        pair,
+                    })
+                } else {
+                    Ok(DataFrame::Other {
+                        channel_id,
+                        channel_name,
+                        pair,
+                    })
+                }
+            }
+        }
+
+        deserializer.deserialize_seq(DataFrameVisitor)
+    }
+}
+
+/// Kraken sends OHLC/ticker numeric fields as JSON strings (to preserve
+/// precision) but a stray server could in principle send a bare number;
+/// accept either and parse down to `f64`.
+struct NumericField(f64);
+
+impl<'de> Deserialize<'de> for NumericField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NumericFieldVisitor;
+
+        impl<'de> Visitor<'de> for NumericFieldVisitor {
+            type Value = NumericField;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a number or a string containing a number")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.parse().map(NumericField).map_err(de::Error::custom)
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(NumericField(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(NumericField(v as f64))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(NumericField(v as f64))
+            }
+        }
+
+        deserializer.deserialize_any(NumericFieldVisitor)
+    }
+}
+
+/// OHLC candle payload: `[time, etime, open, high, low, close, vwap, volume, count]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OhlcPayload {
+    pub time: f64,
+    pub end_time: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub vwap: f64,
+    pub volume: f64,
+    pub count: u64,
+}
+
+impl<'de> Deserialize<'de> for OhlcPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OhlcVisitor;
+
+        impl<'de> Visitor<'de> for OhlcVisitor {
+            type Value = OhlcPayload;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an OHLC array [time, etime, open, high, low, close, vwap, volume, count]")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut next = |idx: usize| -> Result<f64, A::Error> {
+                    let field: NumericField = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(idx, &self))?;
+                    Ok(field.0)
+                };
+
+                let time = next(0)?;
+                let end_time = next(1)?;
+                let open = next(2)?;
+                let high = next(3)?;
+                let low = next(4)?;
+                let close = next(5)?;
+                let vwap = next(6)?;
+                let volume = next(7)?;
+                let count = next(8)? as u64;
+
+                Ok(OhlcPayload {
+                    time,
+                    end_time,
+                    open,
+                    high,
+                    low,
+                    close,
+                    vwap,
+                    volume,
+                    count,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(OhlcVisitor)
+    }
+}
+
+/// Ticker payload: an object keyed by Kraken's single-letter field codes,
+/// each value an array (strings for prices/volumes, integers for trade
+/// counts). `a`/`b`/`c` lead with the price followed by lot volumes; the
+/// other fields are `[today, last24h]` pairs. Only index 0 is kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickerPayload {
+    pub ask: f64,
+    pub bid: f64,
+    pub last_trade_price: f64,
+    pub volume_today: f64,
+    pub vwap_today: f64,
+    pub trades_today: u64,
+    pub low_today: f64,
+    pub high_today: f64,
+    pub open_today: f64,
+}
+
+impl<'de> Deserialize<'de> for TickerPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RawTicker {
+            a: Vec<NumericField>,
+            b: Vec<NumericField>,
+            c: Vec<NumericField>,
+            v: Vec<NumericField>,
+            p: Vec<NumericField>,
+            t: Vec<NumericField>,
+            l: Vec<NumericField>,
+            h: Vec<NumericField>,
+            o: Vec<NumericField>,
+        }
+
+        fn today(field: &str, values: &[NumericField]) -> Result<f64, String> {
+            values
+                .first()
+                .map(|v| v.0)
+                .ok_or_else(|| format!("ticker field \"{}\" has no elements", field))
+        }
+
+        let raw = RawTicker::deserialize(deserializer)?;
+
+        Ok(TickerPayload {
+            ask: today("a", &raw.a).map_err(de::Error::custom)?,
+            bid: today("b", &raw.b).map_err(de::Error::custom)?,
+            last_trade_price: today("c", &raw.c).map_err(de::Error::custom)?,
+            volume_today: today("v", &raw.v).map_err(de::Error::custom)?,
+            vwap_today: today("p", &raw.p).map_err(de::Error::custom)?,
+            trades_today: today("t", &raw.t).map_err(de::Error::custom)? as u64,
+            low_today: today("l", &raw.l).map_err(de::Error::custom)?,
+            high_today: today("h", &raw.h).map_err(de::Error::custom)?,
+            open_today: today("o", &raw.o).map_err(de::Error::custom)?,
+        })
+    }
+}
+
+// ============================================================================
+// Kraken WebSocket v2
+// ============================================================================
+
+/// Any message that can arrive on Kraken's public WebSocket **v2** endpoint.
+///
+/// Unlike v1, every v2 frame is a plain JSON object — there's no
+/// `[channelId, payload, channelName, pair]` array to decode positionally,
+/// so `#[derive(Deserialize)]` is enough; no hand-written `Deserialize`
+/// impl needed here.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KrakenWsFrameV2 {
+    /// `{"channel": "ohlc", "type": "update"|"snapshot", "data": [...]}`.
+    Ohlc(OhlcFrameV2),
+    /// Everything else: `method`-based acks, `"channel": "heartbeat"`,
+    /// `"channel": "status"`, errors, etc. Kept as raw JSON since callers
+    /// of this example only care about OHLC data.
+    Other(serde_json::Value),
+}
+
+/// An OHLC update/snapshot frame on the v2 feed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OhlcFrameV2 {
+    pub channel: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub data: Vec<OhlcDataV2>,
+}
+
+/// A single candle inside a v2 OHLC frame.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OhlcDataV2 {
+    pub symbol: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub trades: u64,
+    pub volume: f64,
+    pub vwap: f64,
+    pub interval_begin: String,
+    pub interval: u32,
+    pub timestamp: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ohlc_data_frame() {
+        let raw = r#"[
+            42,
+            [1609459200.0, 1609459260.0, "29000.1", "29050.5", "28990.0", "29010.3", "29005.2", "12.345", 9],
+            "ohlc-15",
+            "XBT/USD"
+        ]"#;
+
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        match frame {
+            KrakenWsFrame::Data(DataFrame::Ohlc {
+                channel_id,
+                payload,
+                pair,
+            }) => {
+                assert_eq!(channel_id, 42);
+                assert_eq!(pair, "XBT/USD");
+                assert_eq!(payload.open, 29000.1);
+                assert_eq!(payload.close, 29010.3);
+                assert_eq!(payload.count, 9);
+            }
+            other => panic!("expected an OHLC data frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_ticker_data_frame() {
+        let raw = r#"[
+            340,
+            {
+                "a": ["5525.40000", 1, "1.000"],
+                "b": ["5525.10000", 1, "1.000"],
+                "c": ["5525.10000", "0.00398963"],
+                "v": ["2634.11501626", "4720.08751280"],
+                "p": ["5631.44067", "5510.00508"],
+                "t": [25974, 44947],
+                "l": ["5505.00000", "5505.00000"],
+                "h": ["5783.00000", "5783.00000"],
+                "o": ["5760.70000", "5763.40000"]
+            },
+            "ticker",
+            "XBT/USD"
+        ]"#;
+
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        match frame {
+            KrakenWsFrame::Data(DataFrame::Ticker {
+                channel_id, payload, pair,
+            }) => {
+                assert_eq!(channel_id, 340);
+                assert_eq!(pair, "XBT/USD");
+                assert_eq!(payload.ask, 5525.4);
+                assert_eq!(payload.bid, 5525.1);
+                assert_eq!(payload.trades_today, 25974);
+            }
+            other => panic!("expected a ticker data frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_control_message() {
+        let raw = r#"{"event": "heartbeat"}"#;
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        match frame {
+            KrakenWsFrame::Control(msg) => assert_eq!(msg.event, "heartbeat"),
+            other => panic!("expected a control message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn interprets_heartbeat_as_a_typed_event() {
+        let raw = r#"{"event": "heartbeat"}"#;
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        let KrakenWsFrame::Control(msg) = frame else {
+            panic!("expected a control message");
+        };
+        assert!(matches!(msg.event_kind(), KrakenEvent::Heartbeat));
+    }
+
+    #[test]
+    fn interprets_subscription_status_as_a_typed_event() {
+        let raw = r#"{
+            "event": "subscriptionStatus",
+            "status": "subscribed",
+            "pair": "XBT/USD",
+            "channelName": "ohlc-15",
+            "channelID": 42
+        }"#;
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        let KrakenWsFrame::Control(msg) = frame else {
+            panic!("expected a control message");
+        };
+        match msg.event_kind() {
+            KrakenEvent::SubscriptionStatus(status) => {
+                assert_eq!(status.status, "subscribed");
+                assert_eq!(status.pair.as_deref(), Some("XBT/USD"));
+                assert_eq!(status.channel_name.as_deref(), Some("ohlc-15"));
+            }
+            other => panic!("expected a subscription status event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unmodeled_event_falls_back_to_other() {
+        let raw = r#"{"event": "error", "errorMessage": "Unknown pair"}"#;
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        let KrakenWsFrame::Control(msg) = frame else {
+            panic!("expected a control message");
+        };
+        match msg.event_kind() {
+            KrakenEvent::Other(event) => assert_eq!(event, "error"),
+            other => panic!("expected Other(\"error\"), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_channel_falls_back_to_other() {
+        let raw = r#"[7, {"foo": "bar"}, "spread", "XBT/USD"]"#;
+        let frame: KrakenWsFrame = serde_json::from_str(raw).unwrap();
+        match frame {
+            KrakenWsFrame::Data(DataFrame::Other {
+                channel_name, pair, ..
+            }) => {
+                assert_eq!(channel_name, "spread");
+                assert_eq!(pair, "XBT/USD");
+            }
+            other => panic!("expected an Other data frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_v2_ohlc_frame() {
+        let raw = r#"{
+            "channel": "ohlc",
+            "type": "update",
+            "data": [{
+                "symbol": "BTC/USD",
+                "open": 29000.1,
+                "high": 29050.5,
+                "low": 28990.0,
+                "close": 29010.3,
+                "trades": 9,
+                "volume": 12.345,
+                "vwap": 29005.2,
+                "interval_begin": "2021-01-01T00:00:00Z",
+                "interval": 15,
+                "timestamp": "2021-01-01T00:15:00Z"
+            }]
+        }"#;
+
+        let frame: KrakenWsFrameV2 = serde_json::from_str(raw).unwrap();
+        match frame {
+            KrakenWsFrameV2::Ohlc(ohlc) => {
+                assert_eq!(ohlc.kind, "update");
+                assert_eq!(ohlc.data.len(), 1);
+                assert_eq!(ohlc.data[0].symbol, "BTC/USD");
+                assert_eq!(ohlc.data[0].close, 29010.3);
+            }
+            other => panic!("expected a v2 OHLC frame, got {:?}", other),
+        }
+    }
+}
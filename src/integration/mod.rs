@@ -2,15 +2,32 @@
 //!
 //! Provides integration with Kraken's WebSocket and REST APIs for live trading.
 
+mod client;
 mod kraken;
+mod kraken_ws;
 
 pub use kraken::{
     KrakenRegimeTrader,
     KrakenIntegrationConfig,
+    ExecutionModel,
     Candle,
     TradeAction,
     TradeType,
     PairStatus,
+    Resolution,
+    MultiResolutionBuilder,
+    BookLevel,
+    OrderBook,
+    MicrostructureFeatures,
     websocket_integration,
     rest_integration,
+    KRAKEN_WS_URL_V1,
+    KRAKEN_WS_URL_V2,
 };
+
+pub use kraken_ws::{
+    ControlMessage, DataFrame, KrakenEvent, KrakenWsFrame, OhlcPayload, TickerPayload,
+    SubscriptionStatus, SystemStatus, OhlcDataV2, OhlcFrameV2, KrakenWsFrameV2,
+};
+
+pub use client::{KrakenWsClient, DEFAULT_HEARTBEAT_TIMEOUT};
@@ -51,15 +51,24 @@
 pub mod regime;
 pub mod strategy;
 pub mod integration;
+pub mod backtest;
+pub mod portfolio;
+pub mod hyperopt;
+pub mod regime_allocation;
+pub mod sizing;
 
 // Re-exports for convenience
 pub use regime::{
     MarketRegime,
     TrendDirection,
+    PositionDirection,
     RegimeConfig,
+    AdaptiveRegimeConfig,
     RegimeConfidence,
     RegimeDetector,
     RecommendedStrategy,
+    RiskParameters,
+    StopTakeProfitLevels,
 };
 
 pub use strategy::{
@@ -70,9 +79,24 @@ pub use strategy::{
 pub use integration::{
     KrakenRegimeTrader,
     KrakenIntegrationConfig,
+    ExecutionModel,
     Candle,
     TradeAction,
     TradeType,
+    PairStatus,
+    KrakenWsFrame,
+    ControlMessage,
+    DataFrame,
+    OhlcPayload,
+    TickerPayload,
+    KrakenWsFrameV2,
+    OhlcFrameV2,
+    OhlcDataV2,
+    KrakenEvent,
+    SystemStatus,
+    SubscriptionStatus,
+    KrakenWsClient,
+    DEFAULT_HEARTBEAT_TIMEOUT,
 };
 
 /// Version information
@@ -0,0 +1,253 @@
+//! Portfolio-Level Capital Allocator
+//!
+//! `StrategyRouter` treats every asset independently with no notion of
+//! shared capital. `PortfolioAllocator` turns the per-asset `RoutedSignal`s
+//! into target weights under a total-equity constraint, following the
+//! top-down rebalancing approach common in portfolio-management tools:
+//! score each asset, normalize to a capital budget, clamp to per-asset
+//! limits, and redistribute any clipped excess among the assets that still
+//! have room.
+
+use crate::strategy::mean_reversion::Signal;
+use crate::strategy::router::RoutedSignal;
+use std::collections::HashMap;
+
+/// Configuration for the portfolio allocator
+#[derive(Debug, Clone)]
+pub struct AllocatorConfig {
+    /// Minimum weight any single asset may hold (usually 0.0)
+    pub min_weight: f64,
+    /// Maximum weight any single asset may hold
+    pub max_weight: f64,
+    /// Fraction of equity to keep invested; the rest is held as cash
+    pub capital_budget: f64,
+    /// Target changes smaller than this fraction are suppressed to avoid churn
+    pub min_trade_fraction: f64,
+    /// Maximum clamp/redistribute passes before giving up and accepting
+    /// whatever is left clamped
+    pub max_iterations: usize,
+}
+
+impl Default for AllocatorConfig {
+    fn default() -> Self {
+        Self {
+            min_weight: 0.0,
+            max_weight: 0.3,
+            capital_budget: 0.95,
+            min_trade_fraction: 0.01,
+            max_iterations: 10,
+        }
+    }
+}
+
+/// A rebalancing instruction for one asset
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetPosition {
+    pub symbol: String,
+    pub target_weight: f64,
+    pub delta_from_current: f64,
+}
+
+/// Turns per-asset `RoutedSignal`s into portfolio target weights
+#[derive(Debug, Clone)]
+pub struct PortfolioAllocator {
+    config: AllocatorConfig,
+    current_weights: HashMap<String, f64>,
+}
+
+impl PortfolioAllocator {
+    pub fn new(config: AllocatorConfig) -> Self {
+        Self {
+            config,
+            current_weights: HashMap::new(),
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(AllocatorConfig::default())
+    }
+
+    /// Record the portfolio's current weight for an asset, used to compute
+    /// `delta_from_current` and to suppress sub-threshold rebalances
+    pub fn set_current_weight(&mut self, symbol: &str, weight: f64) {
+        self.current_weights.insert(symbol.to_string(), weight);
+    }
+
+    /// Compute target weights for the given per-asset signals.
+    ///
+    /// Returns the target positions plus the resulting cash buffer fraction
+    /// (1.0 minus the sum of target weights).
+    pub fn allocate(&self, signals: &HashMap<String, RoutedSignal>) -> (Vec<TargetPosition>, f64) {
+        let mut weights = self.raw_weights(signals);
+        self.clamp_and_redistribute(&mut weights);
+
+        let mut targets: Vec<TargetPosition> = Vec::with_capacity(weights.len());
+        for (symbol, target_weight) in weights {
+            let current = self.current_weights.get(&symbol).copied().unwrap_or(0.0);
+            let delta = target_weight - current;
+
+            let target_weight = if delta.abs() < self.config.min_trade_fraction {
+                current
+            } else {
+                target_weight
+            };
+
+            targets.push(TargetPosition {
+                symbol,
+                target_weight,
+                delta_from_current: target_weight - current,
+            });
+        }
+
+        let invested: f64 = targets.iter().map(|t| t.target_weight).sum();
+        let cash_buffer = (1.0 - invested).max(0.0);
+
+        (targets, cash_buffer)
+    }
+
+    /// Score each asset and normalize positive scores to weights summing to
+    /// the capital budget. Negative/flat scores (Sell/Hold/NoTrade) get no
+    /// weight - the allocator is long-only until short support lands.
+    fn raw_weights(&self, signals: &HashMap<String, RoutedSignal>) -> HashMap<String, f64> {
+        let scores: HashMap<String, f64> = signals
+            .iter()
+            .map(|(symbol, signal)| (symbol.clone(), Self::raw_score(signal)))
+            .collect();
+
+        let positive_sum: f64 = scores.values().filter(|&&s| s > 0.0).sum();
+        if positive_sum <= 0.0 {
+            return scores.keys().map(|s| (s.clone(), 0.0)).collect();
+        }
+
+        scores
+            .into_iter()
+            .map(|(symbol, score)| {
+                let weight = if score > 0.0 {
+                    (score / positive_sum) * self.config.capital_budget
+                } else {
+                    0.0
+                };
+                (symbol, weight)
+            })
+            .collect()
+    }
+
+    fn raw_score(signal: &RoutedSignal) -> f64 {
+        let direction = match signal.signal {
+            Signal::Buy => 1.0,
+            Signal::Sell => -1.0,
+            Signal::Hold => 0.0,
+        };
+        direction * signal.confidence * signal.position_size_factor
+    }
+
+    /// Clamp weights to [min_weight, max_weight] and redistribute any
+    /// clipped excess proportionally among the still-unclamped assets,
+    /// iterating until the allocation stabilizes.
+    fn clamp_and_redistribute(&self, weights: &mut HashMap<String, f64>) {
+        for _ in 0..self.config.max_iterations {
+            let mut clamped: HashMap<String, f64> = HashMap::new();
+            let mut excess = 0.0;
+            let mut unclamped_total = 0.0;
+
+            for (symbol, &weight) in weights.iter() {
+                if weight > self.config.max_weight {
+                    excess += weight - self.config.max_weight;
+                    clamped.insert(symbol.clone(), self.config.max_weight);
+                } else if weight < self.config.min_weight {
+                    excess -= self.config.min_weight - weight;
+                    clamped.insert(symbol.clone(), self.config.min_weight);
+                } else {
+                    unclamped_total += weight;
+                }
+            }
+
+            if clamped.is_empty() {
+                break;
+            }
+
+            for (symbol, weight) in weights.iter_mut() {
+                if let Some(&c) = clamped.get(symbol) {
+                    *weight = c;
+                } else if unclamped_total > 0.0 {
+                    *weight += excess * (*weight / unclamped_total);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::{MarketRegime, PositionDirection};
+    use crate::strategy::router::ActiveStrategy;
+
+    fn signal(symbol: &str, sig: Signal, confidence: f64, size_factor: f64) -> (String, RoutedSignal) {
+        (
+            symbol.to_string(),
+            RoutedSignal {
+                signal: sig,
+                source_strategy: ActiveStrategy::TrendFollowing,
+                regime: MarketRegime::Uncertain,
+                confidence,
+                position_size_factor: size_factor,
+                reason: String::new(),
+                stop_loss: None,
+                take_profit: None,
+                direction: PositionDirection::Long,
+                leverage: 1.0,
+                risk_halted: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_all_hold_allocates_all_cash() {
+        let allocator = PortfolioAllocator::default_config();
+        let signals: HashMap<String, RoutedSignal> =
+            [signal("BTC/USD", Signal::Hold, 0.0, 0.0)].into_iter().collect();
+
+        let (targets, cash) = allocator.allocate(&signals);
+        assert_eq!(targets[0].target_weight, 0.0);
+        assert_eq!(cash, 1.0);
+    }
+
+    #[test]
+    fn test_weights_respect_max_weight() {
+        let config = AllocatorConfig {
+            max_weight: 0.4,
+            ..AllocatorConfig::default()
+        };
+        let allocator = PortfolioAllocator::new(config);
+
+        let signals: HashMap<String, RoutedSignal> = [
+            signal("BTC/USD", Signal::Buy, 0.9, 1.0),
+            signal("ETH/USD", Signal::Buy, 0.1, 1.0),
+        ]
+        .into_iter()
+        .collect();
+
+        let (targets, _) = allocator.allocate(&signals);
+        for target in &targets {
+            assert!(target.target_weight <= 0.4 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_small_changes_are_suppressed() {
+        let mut allocator = PortfolioAllocator::default_config();
+        allocator.set_current_weight("BTC/USD", 0.295);
+
+        let signals: HashMap<String, RoutedSignal> =
+            [signal("BTC/USD", Signal::Buy, 0.5, 1.0)].into_iter().collect();
+
+        let (targets, _) = allocator.allocate(&signals);
+        // A single asset's 0.95 budget share is clamped to the default
+        // max_weight of 0.3, only 0.005 away from the 0.295 current weight -
+        // below min_trade_fraction, so the rebalance is suppressed.
+        assert_eq!(targets[0].target_weight, 0.295);
+        assert_eq!(targets[0].delta_from_current, 0.0);
+    }
+}
@@ -4,15 +4,15 @@
 
 use std::collections::VecDeque;
 use super::{
-    indicators::{ADX, ATR, BollingerBands, EMA},
-    types::{MarketRegime, RegimeConfig, RegimeConfidence, TrendDirection, RecommendedStrategy},
+    indicators::{ADX, ATR, BollingerBands, EMA, HeikinAshi, RSI, SqueezeDetector, StochasticK, SuperTrend, VolumeOscillator},
+    types::{AdaptiveRegimeConfig, MarketRegime, PositionDirection, RegimeConfig, RegimeConfidence, RecommendedStrategy, StopTakeProfitLevels, TrendDirection},
 };
 
 /// Main regime detection engine
 #[derive(Debug)]
 pub struct RegimeDetector {
     config: RegimeConfig,
-    
+
     // Indicators
     adx: ADX,
     atr: ATR,
@@ -20,14 +20,28 @@ pub struct RegimeDetector {
     bb: BollingerBands,
     ema_short: EMA,
     ema_long: EMA,
-    
+    squeeze: SqueezeDetector,
+    super_trend: SuperTrend,
+    rsi: RSI,
+    stochastic: StochasticK,
+    heikin_ashi: HeikinAshi,
+    volume_osc: VolumeOscillator,
+
+    // Macro-trend gate for `AdaptiveRegimeConfig` - `None` unless the
+    // detector was built with `with_adaptive_config`
+    macro_ema: Option<EMA>,
+    adaptive: Option<AdaptiveRegimeConfig>,
+
     // State
     current_regime: MarketRegime,
     regime_history: VecDeque<MarketRegime>,
     bars_in_regime: usize,
-    
+    bars_in_squeeze: usize,
+
     // For trend direction
     last_close: Option<f64>,
+    // For RSI slope - was it rising or falling last bar
+    prev_rsi: Option<f64>,
 }
 
 impl RegimeDetector {
@@ -39,10 +53,25 @@ impl RegimeDetector {
             bb: BollingerBands::new(config.bb_period, config.bb_std_dev),
             ema_short: EMA::new(config.ema_short_period),
             ema_long: EMA::new(config.ema_long_period),
+            squeeze: SqueezeDetector::new(
+                config.bb_period,
+                config.bb_std_dev,
+                config.atr_period,
+                config.keltner_mult,
+            ),
+            super_trend: SuperTrend::new(config.atr_period, config.super_trend_mult),
+            rsi: RSI::new(config.rsi_period),
+            stochastic: StochasticK::new(config.stochastic_period),
+            heikin_ashi: HeikinAshi::new(),
+            volume_osc: VolumeOscillator::new(config.volume_osc_fast_period, config.volume_osc_slow_period),
+            macro_ema: None,
+            adaptive: None,
             current_regime: MarketRegime::Uncertain,
             regime_history: VecDeque::with_capacity(20),
             bars_in_regime: 0,
+            bars_in_squeeze: 0,
             last_close: None,
+            prev_rsi: None,
             config,
         }
     }
@@ -56,28 +85,110 @@ impl RegimeDetector {
     pub fn crypto_optimized() -> Self {
         Self::new(RegimeConfig::crypto_optimized())
     }
-    
-    /// Update with new OHLC bar
+
+    /// Create a detector that swaps `adaptive.aligned`/`adaptive.neutral`
+    /// in as its active `RegimeConfig` each bar, based on where close sits
+    /// relative to a long EMA of close. Indicators are built once from
+    /// `adaptive.neutral` (a reasonable starting assumption before any
+    /// macro bias is established) - only the threshold fields of `config`
+    /// change afterward, so warmed-up indicator state is never reset by a
+    /// preset switch.
+    pub fn with_adaptive_config(adaptive: AdaptiveRegimeConfig) -> Self {
+        let mut detector = Self::new(adaptive.neutral.clone());
+        detector.macro_ema = Some(EMA::new(adaptive.macro_ema_period));
+        detector.adaptive = Some(adaptive);
+        detector
+    }
+
+    /// Update with new OHLC bar. Volume-less fallback for callers without
+    /// a volume feed - `classify_regime` simply skips the volume
+    /// confirmation gate rather than treating missing data as zero
+    /// participation, so behavior here is unchanged from before
+    /// `update_with_volume` existed.
     pub fn update(&mut self, high: f64, low: f64, close: f64) -> RegimeConfidence {
+        self.update_internal(high, low, close, None)
+    }
+
+    /// Update with a new OHLCV bar. Requires a positive volume oscillator
+    /// (rising participation) to award full confidence to a band-expansion
+    /// or EMA-spread breakout signal; a negative reading during a would-be
+    /// breakout is treated as a probable false move on thin volume and
+    /// dampens confidence instead, letting the stability filter hold the
+    /// prior regime.
+    pub fn update_with_volume(&mut self, high: f64, low: f64, close: f64, volume: f64) -> RegimeConfidence {
+        let volume_oscillator = self.volume_osc.update(volume);
+        self.update_internal(high, low, close, volume_oscillator)
+    }
+
+    fn update_internal(&mut self, high: f64, low: f64, close: f64, volume_oscillator: Option<f64>) -> RegimeConfidence {
+        // The detector's public API has never carried a true `open`, so
+        // Heikin-Ashi's HA_close formula anchors off the prior bar's raw
+        // close instead - a standard continuity approximation for series
+        // where gaps are rare.
+        let effective_open = self.last_close.unwrap_or(close);
+        self.last_close = Some(close);
+
+        // ADX/ATR/Bollinger/EMA optionally consume Heikin-Ashi smoothed
+        // candles instead of raw OHLC, trading a bar of lag for noise
+        // tolerance. The squeeze/SuperTrend/oscillator indicators stay on
+        // raw price - their breakout and overbought/oversold reads need to
+        // react immediately, not a bar late.
+        let (trend_high, trend_low, trend_close) = if self.config.use_heikin_ashi {
+            let ha = self.heikin_ashi.update(effective_open, high, low, close);
+            (ha.high, ha.low, ha.close)
+        } else {
+            (high, low, close)
+        };
+
         // Update all indicators
-        let adx_value = self.adx.update(high, low, close);
-        let atr_value = self.atr.update(high, low, close);
-        let bb_values = self.bb.update(close);
-        let ema_short = self.ema_short.update(close);
-        let ema_long = self.ema_long.update(close);
-        
+        let adx_value = self.adx.update(trend_high, trend_low, trend_close);
+        let atr_value = self.atr.update(trend_high, trend_low, trend_close);
+        let bb_values = self.bb.update(trend_close);
+        let ema_short = self.ema_short.update(trend_close);
+        let ema_long = self.ema_long.update(trend_close);
+        let squeeze_status = self.squeeze.update(high, low, close);
+        let super_trend_direction = self.super_trend.update(high, low, close).map(|s| s.direction);
+        let rsi_value = self.rsi.update(close);
+        let stochastic_k = self.stochastic.update(high, low, close);
+
         // Update ATR average for expansion detection
         if let Some(atr) = atr_value {
             self.atr_avg.update(atr);
         }
-        
-        self.last_close = Some(close);
-        
+
+        // Track how long the squeeze has been coiling, and - on the bar it
+        // releases - the momentum direction over the squeeze window so the
+        // next classification can be biased toward it instead of waiting a
+        // bar for the EMA slope to catch up.
+        self.bars_in_squeeze = if squeeze_status.in_squeeze { self.bars_in_squeeze + 1 } else { 0 };
+        let squeeze_release_direction = squeeze_status.squeeze_fired.then(|| {
+            squeeze_status.bollinger.map(|bb| if close >= bb.middle {
+                TrendDirection::Bullish
+            } else {
+                TrendDirection::Bearish
+            })
+        }).flatten();
+
+        // Adaptive macro-trend gate: swap the active threshold preset in
+        // based on where raw close sits relative to a long EMA of close.
+        // Only `self.config` changes here - every indicator above already
+        // updated against its own state, so nothing gets reset.
+        if let Some(macro_value) = self.macro_ema.as_mut().and_then(|ema| ema.update(close)) {
+            if let Some(adaptive) = &self.adaptive {
+                let band = macro_value * adaptive.macro_band_pct;
+                self.config = if (close - macro_value).abs() > band {
+                    adaptive.aligned.clone()
+                } else {
+                    adaptive.neutral.clone()
+                };
+            }
+        }
+
         // Check if we have enough data
         if !self.is_ready() {
             return RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
         }
-        
+
         // Detect regime
         let (new_regime, confidence) = self.classify_regime(
             adx_value.unwrap(),
@@ -85,12 +196,16 @@ impl RegimeDetector {
             bb_values.as_ref().unwrap(),
             ema_short.unwrap(),
             ema_long.unwrap(),
-            close,
+            trend_close,
+            squeeze_status.in_squeeze,
+            squeeze_release_direction,
+            super_trend_direction,
+            volume_oscillator,
         );
-        
+
         // Apply stability filter - avoid whipsawing
         let stable_regime = self.apply_stability_filter(new_regime, confidence);
-        
+
         // Update state
         if stable_regime != self.current_regime {
             self.regime_history.push_back(self.current_regime);
@@ -102,14 +217,63 @@ impl RegimeDetector {
         } else {
             self.bars_in_regime += 1;
         }
-        
+
+        // Momentum-oscillator confirmation: RSI/Stochastic either back up
+        // the called regime or flag it as exhausted, nudging confidence
+        // within a +/-15% band rather than overriding the regime itself.
+        let rsi = rsi_value.unwrap();
+        let stochastic = stochastic_k.unwrap();
+        let rsi_rising = self.prev_rsi.map(|prev| rsi > prev).unwrap_or(false);
+        self.prev_rsi = Some(rsi);
+
+        let confirmation_score = Self::oscillator_confirmation(stable_regime, rsi, rsi_rising, stochastic);
+        let confirmed_confidence = (confidence * (0.7 + 0.3 * (confirmation_score + 1.0) / 2.0)).min(1.0);
+
         RegimeConfidence::with_metrics(
             stable_regime,
-            confidence,
+            confirmed_confidence,
             adx_value.unwrap(),
             bb_values.as_ref().map(|b| b.width_percentile).unwrap_or(50.0),
-            self.calculate_trend_strength(ema_short.unwrap(), ema_long.unwrap(), close),
+            self.calculate_trend_strength(ema_short.unwrap(), ema_long.unwrap(), trend_close),
         )
+        .with_oscillators(rsi, stochastic)
+        .with_squeeze(squeeze_status.in_squeeze, self.bars_in_squeeze)
+    }
+
+    /// Score in `[-1, 1]` for how well RSI/Stochastic back up `regime`:
+    /// `1.0` confirms it, `-1.0` flags exhaustion, `0.0` is neutral. Folded
+    /// into `confidence` as a +/-15% adjustment rather than a veto, since a
+    /// single bar of overbought/oversold readings shouldn't override what
+    /// ADX/BB/ATR already agreed on.
+    fn oscillator_confirmation(regime: MarketRegime, rsi: f64, rsi_rising: bool, stochastic_k: f64) -> f64 {
+        match regime {
+            MarketRegime::Trending(TrendDirection::Bullish) => {
+                if rsi >= 80.0 && stochastic_k >= 80.0 {
+                    -1.0  // Overbought on both oscillators - trend looks exhausted
+                } else if rsi_rising && (50.0..=70.0).contains(&rsi) {
+                    1.0  // Rising RSI with room left before overbought confirms the push
+                } else {
+                    0.0
+                }
+            }
+            MarketRegime::Trending(TrendDirection::Bearish) => {
+                if rsi <= 20.0 && stochastic_k <= 20.0 {
+                    -1.0  // Oversold on both oscillators - trend looks exhausted
+                } else if !rsi_rising && (30.0..=50.0).contains(&rsi) {
+                    1.0  // Falling RSI with room left before oversold confirms the push
+                } else {
+                    0.0
+                }
+            }
+            MarketRegime::MeanReverting => {
+                if !(30.0..=70.0).contains(&rsi) || !(20.0..=80.0).contains(&stochastic_k) {
+                    1.0  // Extreme reading is exactly what a reversion entry wants
+                } else {
+                    0.0
+                }
+            }
+            MarketRegime::Volatile | MarketRegime::Squeeze | MarketRegime::Uncertain => 0.0,
+        }
     }
     
     /// Classify regime based on indicator values
@@ -121,7 +285,22 @@ impl RegimeDetector {
         ema_short: f64,
         ema_long: f64,
         close: f64,
+        in_squeeze: bool,
+        squeeze_release_direction: Option<TrendDirection>,
+        super_trend_direction: Option<TrendDirection>,
+        volume_oscillator: Option<f64>,
     ) -> (MarketRegime, f64) {
+        // Bollinger Bands inside the Keltner Channel: coiling, low-volatility
+        // market. Classify it outright rather than letting the trend/range
+        // scores below fight over a regime they weren't designed to see -
+        // once the squeeze releases (`in_squeeze` goes false) the normal
+        // scoring resumes and picks up the breakout direction from the EMA
+        // slope like any other trend call.
+        if in_squeeze {
+            let confidence = (1.0 - bb.width_percentile / 100.0).clamp(0.5, 1.0);
+            return (MarketRegime::Squeeze, confidence);
+        }
+
         // Calculate ATR expansion
         let atr_expansion = if let Some(avg_atr) = self.atr_avg.value() {
             atr / avg_atr
@@ -130,39 +309,55 @@ impl RegimeDetector {
         };
         
         // Score each regime possibility
-        let mut trending_score = 0.0;
-        let mut ranging_score = 0.0;
-        let mut volatile_score = 0.0;
-        
+        let mut trending_score: f64 = 0.0;
+        let mut ranging_score: f64 = 0.0;
+        let mut volatile_score: f64 = 0.0;
+
+        // Volume confirmation: when a volume feed is available, a
+        // band-expansion or EMA-spread breakout only earns its full score
+        // with rising participation behind it (VO > 0). No feed (`None`,
+        // the plain `update` path) is a no-op here rather than a penalty.
+        let volume_confirms = volume_oscillator.map(|vo| vo > 0.0).unwrap_or(true);
+
         // ADX analysis
         if adx >= self.config.adx_trending_threshold {
             trending_score += 0.4;
         } else if adx <= self.config.adx_ranging_threshold {
             ranging_score += 0.3;
         }
-        
+
         // Bollinger Band width analysis
-        if bb.is_high_volatility(self.config.bb_width_volatility_threshold) {
+        let bb_breakout = bb.is_high_volatility(self.config.bb_width_volatility_threshold);
+        if bb_breakout && volume_confirms {
             volatile_score += 0.3;
         }
         if bb.is_squeeze(25.0) {
             ranging_score += 0.2;  // Tight bands suggest range-bound
         }
-        
+
         // ATR expansion
-        if atr_expansion >= self.config.atr_expansion_threshold {
+        let atr_breakout = atr_expansion >= self.config.atr_expansion_threshold;
+        if atr_breakout && volume_confirms {
             volatile_score += 0.3;
         } else if atr_expansion < 0.8 {
             ranging_score += 0.2;  // Low volatility suggests ranging
         }
-        
+
         // EMA alignment for trend
         let ema_diff_pct = ((ema_short - ema_long) / ema_long).abs() * 100.0;
-        if ema_diff_pct > 2.0 {
+        let ema_breakout = ema_diff_pct > self.config.ema_spread_breakout_pct;
+        if ema_breakout && volume_confirms {
             trending_score += 0.3;
-        } else if ema_diff_pct < 1.0 {
+        } else if ema_diff_pct < self.config.ema_spread_ranging_pct {
             ranging_score += 0.2;
         }
+
+        // A squeeze that just released is the clearest breakout signal this
+        // function sees - tip the call toward Trending even if the EMAs
+        // haven't caught up yet.
+        if squeeze_release_direction.is_some() {
+            trending_score += 0.35;
+        }
         
         // Price position relative to EMAs
         let price_above_both = close > ema_short && close > ema_long;
@@ -175,7 +370,16 @@ impl RegimeDetector {
         
         // Determine regime and direction
         let max_score = trending_score.max(ranging_score).max(volatile_score);
-        let confidence = max_score / 1.2;  // Normalize to 0-1 range
+        let mut confidence = max_score / 1.2;  // Normalize to 0-1 range
+
+        // A breakout signal (band expansion or EMA spread) that fired on
+        // thinning volume looks like the real thing by every other metric,
+        // but is the textbook setup for a false move - knock confidence
+        // down so the stability filter is more likely to hold the prior
+        // regime instead of whipsawing into this one.
+        if !volume_confirms && (bb_breakout || atr_breakout || ema_breakout) {
+            confidence *= 0.5;
+        }
         
         let regime = if volatile_score >= 0.5 && volatile_score >= trending_score {
             MarketRegime::Volatile
@@ -185,12 +389,22 @@ impl RegimeDetector {
                 TrendDirection::Bullish
             } else if ema_short < ema_long && close < ema_long {
                 TrendDirection::Bearish
+            } else if let Some(dir) = squeeze_release_direction {
+                dir  // Squeeze just fired - trust the momentum over the coil rather than the slower EMA slope
             } else if let Some(dir) = self.adx.trend_direction() {
                 dir
             } else {
                 TrendDirection::Bullish  // Default
             };
-            MarketRegime::Trending(direction)
+
+            // SuperTrend is a second, less noisy vote on direction - only
+            // commit to Trending when it agrees with the EMA-ordering call;
+            // a disagreement means we're likely right at a crossover and
+            // the regime is genuinely unclear rather than confidently wrong.
+            match super_trend_direction {
+                Some(st_dir) if st_dir != direction => MarketRegime::Uncertain,
+                _ => MarketRegime::Trending(direction),
+            }
         } else if ranging_score > 0.3 {
             MarketRegime::MeanReverting
         } else {
@@ -226,7 +440,8 @@ impl RegimeDetector {
                 (&r, &new_regime),
                 (MarketRegime::Trending(_), MarketRegime::Trending(_)) |
                 (MarketRegime::MeanReverting, MarketRegime::MeanReverting) |
-                (MarketRegime::Volatile, MarketRegime::Volatile)
+                (MarketRegime::Volatile, MarketRegime::Volatile) |
+                (MarketRegime::Squeeze, MarketRegime::Squeeze)
             ))
             .count();
         
@@ -253,9 +468,11 @@ impl RegimeDetector {
     
     /// Check if detector has enough data to classify regime
     pub fn is_ready(&self) -> bool {
-        self.adx.is_ready() && self.atr.is_ready() && 
-        self.bb.is_ready() && self.ema_short.is_ready() && 
-        self.ema_long.is_ready()
+        self.adx.is_ready() && self.atr.is_ready() &&
+        self.bb.is_ready() && self.ema_short.is_ready() &&
+        self.ema_long.is_ready() && self.squeeze.is_ready() &&
+        self.super_trend.is_ready() &&
+        self.rsi.is_ready() && self.stochastic.is_ready()
     }
     
     /// Get current detected regime
@@ -267,26 +484,64 @@ impl RegimeDetector {
     pub fn recommended_strategy(&self) -> RecommendedStrategy {
         RecommendedStrategy::from(&self.current_regime)
     }
-    
+
+    /// Turn `recommended_strategy()`'s risk parameters into concrete prices
+    /// for a position opened at `close` in `direction`, using the live ATR.
+    /// `None` when the current regime has no risk parameters (`StayCash`)
+    /// or ATR hasn't warmed up yet.
+    pub fn risk_levels(&self, close: f64, direction: PositionDirection) -> Option<StopTakeProfitLevels> {
+        let atr = self.atr_value()?;
+        let risk = self.recommended_strategy().risk_parameters()?;
+        let stop_distance = risk.stop_atr_multiple * atr;
+        let take_profit_distance = stop_distance * risk.reward_risk_ratio;
+
+        let (stop_loss, take_profit) = match direction {
+            PositionDirection::Long => (close - stop_distance, close + take_profit_distance),
+            PositionDirection::Short => (close + stop_distance, close - take_profit_distance),
+        };
+
+        Some(StopTakeProfitLevels { stop_loss, take_profit })
+    }
+
     /// Get number of bars in current regime
     pub fn bars_in_current_regime(&self) -> usize {
         self.bars_in_regime
     }
-    
-    /// Get ADX value
+
+    /// Get number of consecutive bars the Keltner squeeze has been active -
+    /// `0` when not currently squeezing
+    pub fn bars_in_squeeze(&self) -> usize {
+        self.bars_in_squeeze
+    }
+
+    /// Get the SuperTrend's current direction vote - `None` until it warms up
+    pub fn super_trend_direction(&self) -> Option<TrendDirection> {
+        self.super_trend.direction()
+    }
+
+    /// Get ADX value - reflects Heikin-Ashi smoothed candles, not raw
+    /// price action, when `config.use_heikin_ashi` is on
     pub fn adx_value(&self) -> Option<f64> {
         self.adx.value()
     }
-    
-    /// Get ATR value
+
+    /// Get ATR value - reflects Heikin-Ashi smoothed candles, not raw
+    /// price action, when `config.use_heikin_ashi` is on
     pub fn atr_value(&self) -> Option<f64> {
         self.atr.value()
     }
     
-    /// Get current config
+    /// Get current config - the active `aligned`/`neutral` preset when
+    /// built with `with_adaptive_config`
     pub fn config(&self) -> &RegimeConfig {
         &self.config
     }
+
+    /// Current reading of the macro-trend gating EMA - `None` unless built
+    /// with `with_adaptive_config`, or before it warms up
+    pub fn macro_trend_ema(&self) -> Option<f64> {
+        self.macro_ema.as_ref()?.value()
+    }
     
     /// Update config (resets internal state)
     pub fn set_config(&mut self, config: RegimeConfig) {
@@ -370,4 +625,349 @@ mod tests {
         println!("Final regime: {:?}", last_regime);
         // Ranging should either be MeanReverting or at least not strongly Trending
     }
+
+    #[test]
+    fn test_squeeze_detection_on_a_tight_coiling_range() {
+        let mut detector = RegimeDetector::default_config();
+
+        // Very tight, low-volatility chop: Bollinger should pull in tighter
+        // than the ATR-driven Keltner Channel, same setup as
+        // `SqueezeDetector`'s own unit test.
+        let mut last_result = RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
+        for i in 0..60 {
+            let price = 100.0 + (i as f64 % 2.0) * 0.1;
+            let result = detector.update(price + 0.2, price - 0.2, price);
+            if detector.is_ready() {
+                last_result = result;
+            }
+        }
+
+        assert_eq!(last_result.regime, MarketRegime::Squeeze);
+        assert!(last_result.squeeze_active);
+        assert!(detector.bars_in_squeeze() > 1);
+    }
+
+    #[test]
+    fn test_squeeze_release_biases_the_next_call_toward_trending() {
+        let mut detector = RegimeDetector::default_config();
+
+        // Coil tightly, then break out hard to the upside.
+        let mut last_regime = MarketRegime::Uncertain;
+        let mut saw_squeeze = false;
+        for i in 0..60 {
+            let price = 100.0 + (i as f64 % 2.0) * 0.1;
+            let result = detector.update(price + 0.2, price - 0.2, price);
+            if detector.is_ready() && result.regime == MarketRegime::Squeeze {
+                saw_squeeze = true;
+            }
+        }
+        assert!(saw_squeeze, "setup should have coiled into a squeeze first");
+
+        let mut price = 100.0;
+        for _ in 0..5 {
+            price += 3.0;
+            let result = detector.update(price + 0.2, price - 0.2, price);
+            last_regime = result.regime;
+        }
+
+        assert_eq!(last_regime, MarketRegime::Trending(TrendDirection::Bullish));
+    }
+
+    #[test]
+    fn test_super_trend_disagreement_demotes_trending_to_uncertain() {
+        let detector = RegimeDetector::default_config();
+
+        let bb = super::super::indicators::BollingerBandsValues {
+            upper: 110.0,
+            middle: 100.0,
+            lower: 90.0,
+            width: 20.0,
+            width_percentile: 50.0,
+            percent_b: 0.8,
+            std_dev: 5.0,
+        };
+
+        // Everything else (ADX, EMA ordering, price position) says Bullish,
+        // but the SuperTrend vote disagrees - the call should back off to
+        // Uncertain rather than trust the weaker EMA-only signal.
+        let (regime, _confidence) = detector.classify_regime(
+            30.0,
+            5.0,
+            &bb,
+            105.0,
+            95.0,
+            106.0,
+            false,
+            None,
+            Some(TrendDirection::Bearish),
+            None,
+        );
+
+        assert_eq!(regime, MarketRegime::Uncertain);
+    }
+
+    #[test]
+    fn test_super_trend_agreement_confirms_trending() {
+        let detector = RegimeDetector::default_config();
+
+        let bb = super::super::indicators::BollingerBandsValues {
+            upper: 110.0,
+            middle: 100.0,
+            lower: 90.0,
+            width: 20.0,
+            width_percentile: 50.0,
+            percent_b: 0.8,
+            std_dev: 5.0,
+        };
+
+        let (regime, _confidence) = detector.classify_regime(
+            30.0,
+            5.0,
+            &bb,
+            105.0,
+            95.0,
+            106.0,
+            false,
+            None,
+            Some(TrendDirection::Bullish),
+            None,
+        );
+
+        assert_eq!(regime, MarketRegime::Trending(TrendDirection::Bullish));
+    }
+
+    #[test]
+    fn test_oscillator_confirmation_boosts_a_healthy_uptrend() {
+        let score = RegimeDetector::oscillator_confirmation(
+            MarketRegime::Trending(TrendDirection::Bullish),
+            60.0,
+            true,
+            55.0,
+        );
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_oscillator_confirmation_penalizes_an_overbought_uptrend() {
+        let score = RegimeDetector::oscillator_confirmation(
+            MarketRegime::Trending(TrendDirection::Bullish),
+            85.0,
+            true,
+            90.0,
+        );
+        assert_eq!(score, -1.0);
+    }
+
+    #[test]
+    fn test_oscillator_confirmation_boosts_mean_reversion_at_an_extreme() {
+        let score = RegimeDetector::oscillator_confirmation(MarketRegime::MeanReverting, 22.0, false, 15.0);
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_trending_detection_reports_oscillator_readings() {
+        let mut detector = RegimeDetector::default_config();
+        let data = generate_trending_data(300, 100.0, 0.5);
+
+        let mut last_result = RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
+        for (high, low, close) in data {
+            let result = detector.update(high, low, close);
+            if detector.is_ready() {
+                last_result = result;
+            }
+        }
+
+        assert!(last_result.rsi_value > 0.0);
+        assert!(last_result.stochastic_k > 0.0);
+    }
+
+    #[test]
+    fn test_heikin_ashi_mode_still_detects_a_sustained_uptrend() {
+        let mut config = RegimeConfig::default();
+        config.use_heikin_ashi = true;
+        let mut detector = RegimeDetector::new(config);
+
+        let data = generate_trending_data(300, 100.0, 0.5);
+        let mut last_regime = MarketRegime::Uncertain;
+        for (high, low, close) in data {
+            let result = detector.update(high, low, close);
+            if detector.is_ready() {
+                last_regime = result.regime;
+            }
+        }
+
+        assert!(matches!(last_regime, MarketRegime::Trending(_)));
+    }
+
+    #[test]
+    fn test_heikin_ashi_mode_stays_off_by_default() {
+        assert!(!RegimeConfig::default().use_heikin_ashi);
+    }
+
+    #[test]
+    fn test_volume_confirmation_dampens_confidence_on_fading_volume() {
+        let detector = RegimeDetector::default_config();
+
+        let bb = super::super::indicators::BollingerBandsValues {
+            upper: 106.0,
+            middle: 100.0,
+            lower: 94.0,
+            width: 12.0,
+            width_percentile: 50.0,
+            percent_b: 0.8,
+            std_dev: 3.0,
+        };
+
+        // ADX/EMA-spread/price-position all say Bullish, just like the
+        // SuperTrend-agreement test - the only thing that differs here is
+        // the volume oscillator reading.
+        let (_, confirmed) = detector.classify_regime(
+            30.0, 5.0, &bb, 105.0, 95.0, 106.0, false, None, Some(TrendDirection::Bullish), Some(2.0),
+        );
+        let (regime, dampened) = detector.classify_regime(
+            30.0, 5.0, &bb, 105.0, 95.0, 106.0, false, None, Some(TrendDirection::Bullish), Some(-2.0),
+        );
+
+        assert_eq!(regime, MarketRegime::Trending(TrendDirection::Bullish));
+        assert!(dampened < confirmed, "negative volume oscillator should dampen confidence relative to a confirmed breakout");
+    }
+
+    #[test]
+    fn test_update_without_volume_does_not_apply_the_gate() {
+        let detector = RegimeDetector::default_config();
+
+        let bb = super::super::indicators::BollingerBandsValues {
+            upper: 106.0,
+            middle: 100.0,
+            lower: 94.0,
+            width: 12.0,
+            width_percentile: 50.0,
+            percent_b: 0.8,
+            std_dev: 3.0,
+        };
+
+        let (_, no_volume_feed) = detector.classify_regime(
+            30.0, 5.0, &bb, 105.0, 95.0, 106.0, false, None, Some(TrendDirection::Bullish), None,
+        );
+        let (_, confirmed) = detector.classify_regime(
+            30.0, 5.0, &bb, 105.0, 95.0, 106.0, false, None, Some(TrendDirection::Bullish), Some(2.0),
+        );
+
+        assert_eq!(no_volume_feed, confirmed, "missing volume data should behave like confirmed volume, not like a penalty");
+    }
+
+    #[test]
+    fn test_update_with_volume_still_detects_a_sustained_uptrend() {
+        let mut detector = RegimeDetector::default_config();
+
+        let data = generate_trending_data(300, 100.0, 0.5);
+        let mut last_regime = MarketRegime::Uncertain;
+        for (i, (high, low, close)) in data.into_iter().enumerate() {
+            // Rising participation alongside the rally so the volume gate
+            // confirms rather than dampens the breakout.
+            let volume = 1000.0 + i as f64 * 5.0;
+            let result = detector.update_with_volume(high, low, close, volume);
+            if detector.is_ready() {
+                last_regime = result.regime;
+            }
+        }
+
+        assert!(matches!(last_regime, MarketRegime::Trending(_)));
+    }
+
+    #[test]
+    fn test_risk_levels_none_before_atr_warms_up() {
+        let detector = RegimeDetector::default_config();
+        assert!(detector.risk_levels(100.0, PositionDirection::Long).is_none());
+    }
+
+    #[test]
+    fn test_risk_levels_trending_long_uses_trend_following_multiples() {
+        let mut detector = RegimeDetector::default_config();
+        let data = generate_trending_data(300, 100.0, 0.5);
+        let mut close = 0.0;
+        for (high, low, c) in data {
+            detector.update(high, low, c);
+            close = c;
+        }
+
+        assert!(matches!(detector.current_regime(), MarketRegime::Trending(_)));
+        let atr = detector.atr_value().unwrap();
+        let risk = detector.recommended_strategy().risk_parameters().unwrap();
+        let levels = detector.risk_levels(close, PositionDirection::Long).unwrap();
+
+        assert_eq!(levels.stop_loss, close - risk.stop_atr_multiple * atr);
+        assert_eq!(
+            levels.take_profit,
+            close + risk.stop_atr_multiple * atr * risk.reward_risk_ratio
+        );
+    }
+
+    #[test]
+    fn test_risk_levels_short_flips_stop_and_target_sides() {
+        let mut detector = RegimeDetector::default_config();
+        let data = generate_trending_data(300, 100.0, 0.5);
+        let mut close = 0.0;
+        for (high, low, c) in data {
+            detector.update(high, low, c);
+            close = c;
+        }
+
+        let long = detector.risk_levels(close, PositionDirection::Long).unwrap();
+        let short = detector.risk_levels(close, PositionDirection::Short).unwrap();
+
+        assert!(short.stop_loss > close);
+        assert!(short.take_profit < close);
+        assert_eq!(long.stop_loss - close, close - short.stop_loss);
+        assert_eq!(close - long.take_profit, short.take_profit - close);
+    }
+
+    #[test]
+    fn test_stay_cash_has_no_risk_parameters() {
+        assert!(RecommendedStrategy::StayCash.risk_parameters().is_none());
+    }
+
+    #[test]
+    fn test_macro_trend_ema_is_none_without_adaptive_config() {
+        let detector = RegimeDetector::default_config();
+        assert!(detector.macro_trend_ema().is_none());
+    }
+
+    #[test]
+    fn test_adaptive_config_starts_on_the_neutral_preset() {
+        let adaptive = AdaptiveRegimeConfig::default();
+        let detector = RegimeDetector::with_adaptive_config(adaptive.clone());
+        assert_eq!(detector.config().adx_trending_threshold, adaptive.neutral.adx_trending_threshold);
+    }
+
+    #[test]
+    fn test_adaptive_config_switches_to_aligned_preset_on_a_sustained_rally() {
+        let adaptive = AdaptiveRegimeConfig::default();
+        let aligned_threshold = adaptive.aligned.adx_trending_threshold;
+        let mut detector = RegimeDetector::with_adaptive_config(adaptive);
+
+        // A long, steady rally should eventually carry close decisively
+        // above the macro EMA, past the band, and flip the active preset.
+        let data = generate_trending_data(400, 100.0, 0.6);
+        for (high, low, close) in data {
+            detector.update(high, low, close);
+        }
+
+        assert!(detector.macro_trend_ema().is_some());
+        assert_eq!(detector.config().adx_trending_threshold, aligned_threshold);
+    }
+
+    #[test]
+    fn test_adaptive_config_does_not_reset_warmed_up_indicators_on_preset_switch() {
+        let mut detector = RegimeDetector::with_adaptive_config(AdaptiveRegimeConfig::default());
+
+        let data = generate_trending_data(350, 100.0, 0.6);
+        for (high, low, close) in data {
+            detector.update(high, low, close);
+        }
+
+        // If a preset switch had rebuilt the detector (like `set_config`
+        // does), ATR would still be warming up this far into the series.
+        assert!(detector.atr_value().is_some());
+    }
 }
@@ -1,38 +1,163 @@
 //! Ensemble Regime Detector
 //!
-//! Combines multiple regime detection methods:
+//! Combines any number of weighted `RegimeSource`s - indicators, an HMM, or
+//! any other regime classifier - into a single weighted-vote call, so a
+//! richer detector panel (e.g. extra confirmation detectors alongside the
+//! built-in ones) can be assembled without touching the combination logic.
+//!
+//! The default panel still pairs:
 //! 1. Technical Indicators (ADX, Bollinger Bands, ATR) - Rule-based
 //! 2. Hidden Markov Model - Statistical/probabilistic
 //!
-//! The ensemble approach provides more robust regime detection by:
-//! - Reducing false positives when methods disagree
-//! - Increasing confidence when methods agree
+//! Weighted voting provides more robust regime detection by:
+//! - Reducing false positives when sources disagree
+//! - Increasing confidence when sources agree
 //! - Leveraging different strengths of each approach
 
+use std::collections::{HashMap, VecDeque};
+
 use super::{
     detector::RegimeDetector,
     hmm::HMMRegimeDetector,
-    types::{MarketRegime, RegimeConfidence, RegimeConfig},
+    types::{MarketRegime, RegimeConfidence, RegimeConfig, TrendDirection},
 };
 
-#[cfg(test)]
-use super::types::TrendDirection;
+/// A pluggable regime classifier that can sit in an `EnsembleRegimeDetector`
+/// panel alongside any number of others.
+///
+/// `atr_value`/`state_probabilities`/`expected_duration` are optional
+/// capabilities a source may expose for `EnhancedRouter`'s risk sizing and
+/// monitoring; sources that don't track them keep the `None` defaults.
+pub trait RegimeSource: std::fmt::Debug {
+    /// Update with a new OHLC bar and return this source's regime call
+    fn update(&mut self, high: f64, low: f64, close: f64) -> RegimeConfidence;
+
+    /// Whether this source has enough history to be trusted
+    fn is_ready(&self) -> bool;
+
+    /// Volatility estimate (e.g. ATR) this source tracks, if any
+    fn atr_value(&self) -> Option<f64> {
+        None
+    }
+
+    /// Hidden-state probability distribution, for sources backed by an HMM
+    fn state_probabilities(&self) -> Option<Vec<f64>> {
+        None
+    }
 
-/// Configuration for ensemble detector
+    /// Expected bars remaining in the current regime, for sources that track one
+    fn expected_duration(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl RegimeSource for RegimeDetector {
+    fn update(&mut self, high: f64, low: f64, close: f64) -> RegimeConfidence {
+        RegimeDetector::update(self, high, low, close)
+    }
+
+    fn is_ready(&self) -> bool {
+        RegimeDetector::is_ready(self)
+    }
+
+    fn atr_value(&self) -> Option<f64> {
+        RegimeDetector::atr_value(self)
+    }
+}
+
+impl RegimeSource for HMMRegimeDetector {
+    fn update(&mut self, high: f64, low: f64, close: f64) -> RegimeConfidence {
+        self.update_ohlc(high, low, close)
+    }
+
+    fn is_ready(&self) -> bool {
+        HMMRegimeDetector::is_ready(self)
+    }
+
+    fn state_probabilities(&self) -> Option<Vec<f64>> {
+        Some(HMMRegimeDetector::state_probabilities(self).to_vec())
+    }
+
+    fn expected_duration(&self) -> Option<f64> {
+        Some(self.expected_regime_duration(self.current_state_index()))
+    }
+}
+
+/// `MarketRegime` with `Trending`'s direction stripped out, so votes can be
+/// grouped by category before a direction is picked within the winner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RegimeCategory {
+    Trending,
+    MeanReverting,
+    Volatile,
+    Squeeze,
+    Uncertain,
+}
+
+impl From<MarketRegime> for RegimeCategory {
+    fn from(regime: MarketRegime) -> Self {
+        match regime {
+            MarketRegime::Trending(_) => RegimeCategory::Trending,
+            MarketRegime::MeanReverting => RegimeCategory::MeanReverting,
+            MarketRegime::Volatile => RegimeCategory::Volatile,
+            MarketRegime::Squeeze => RegimeCategory::Squeeze,
+            MarketRegime::Uncertain => RegimeCategory::Uncertain,
+        }
+    }
+}
+
+/// Configuration for the default two-source (indicator + HMM) ensemble
+/// panel. Panels assembled with [`EnsembleRegimeDetector::with_sources`]
+/// carry their weights on each source instead and ignore
+/// `indicator_weight`/`hmm_weight`; every other field still applies.
 #[derive(Debug, Clone)]
 pub struct EnsembleConfig {
-    /// Weight for technical indicator detector (0.0 - 1.0)
+    /// Weight for the technical indicator source (0.0 - 1.0)
     pub indicator_weight: f64,
-    /// Weight for HMM detector (0.0 - 1.0)
+    /// Weight for the HMM source (0.0 - 1.0)
     pub hmm_weight: f64,
     /// Minimum agreement threshold to declare a regime
     pub agreement_threshold: f64,
-    /// Use HMM only after warmup (more conservative)
-    pub require_hmm_warmup: bool,
-    /// Boost confidence when both methods agree
+    /// Wait until every source in the panel is ready before voting; until
+    /// then, use the first source's raw read
+    pub require_full_warmup: bool,
+    /// Boost confidence when the panel agrees
     pub agreement_confidence_boost: f64,
-    /// Reduce confidence when methods disagree
+    /// Reduce confidence when the panel disagrees
     pub disagreement_confidence_penalty: f64,
+
+    /// Learn per-source weights online from a lagged realized label instead
+    /// of using the static weights on each source forever
+    pub adaptive_weighting: bool,
+    /// Multiplicative-weights learning rate (`η`); higher decays a
+    /// systematically-wrong source's weight faster
+    pub adaptive_learning_rate: f64,
+    /// Bars of lag before a prediction's realized label is known
+    pub realized_label_lag: usize,
+    /// Minimum `|forward return|` over `realized_label_lag` bars to call the
+    /// realized label `Trending` rather than `MeanReverting`
+    pub realized_trend_threshold: f64,
+    /// Percentile (within this detector's own forward-move history) above
+    /// which the realized label is called `Volatile` instead of
+    /// `Trending`/`MeanReverting`
+    pub realized_volatile_percentile: f64,
+
+    /// Consecutive bars a disagreeing candidate regime must win the vote
+    /// before `current_regime` actually switches to it - the debounce half
+    /// of the hysteresis band. `1` (the default) switches on the first
+    /// disagreeing bar, i.e. debouncing is off.
+    pub hysteresis_confirm_bars: usize,
+    /// A candidate can instead switch immediately once its confidence
+    /// accumulated across consecutive disagreeing bars reaches this total,
+    /// bypassing `hysteresis_confirm_bars` for a single very strong signal.
+    /// Defaults to `0.0`, i.e. always satisfied.
+    pub hysteresis_enter_threshold: f64,
+    /// `current_regime` is held even through a confirmed candidate unless
+    /// the *current* regime's own weighted vote share has fallen below this
+    /// fraction - the lower edge of the enter/exit band that keeps a
+    /// regime from flip-flopping right at `agreement_threshold`. Defaults
+    /// to `1.0`, i.e. always satisfied (debouncing off).
+    pub hysteresis_exit_threshold: f64,
 }
 
 impl Default for EnsembleConfig {
@@ -41,9 +166,17 @@ impl Default for EnsembleConfig {
             indicator_weight: 0.6, // Slightly favor indicators (faster response)
             hmm_weight: 0.4,
             agreement_threshold: 0.5,
-            require_hmm_warmup: true,
+            require_full_warmup: true,
             agreement_confidence_boost: 0.15,
             disagreement_confidence_penalty: 0.2,
+            adaptive_weighting: false,
+            adaptive_learning_rate: 0.1,
+            realized_label_lag: 10,
+            realized_trend_threshold: 0.005,
+            realized_volatile_percentile: 80.0,
+            hysteresis_confirm_bars: 1,
+            hysteresis_enter_threshold: 0.0,
+            hysteresis_exit_threshold: 1.0,
         }
     }
 }
@@ -77,24 +210,33 @@ impl EnsembleConfig {
             ..Default::default()
         }
     }
+
+    /// Enable Schmitt-trigger-style hysteresis on top of whichever preset
+    /// this is chained from, so a disagreeing candidate needs
+    /// `confirm_bars` consecutive votes (or `enter_threshold` of
+    /// accumulated confidence) AND the current regime's own support below
+    /// `exit_threshold` before `EnsembleRegimeDetector::update` switches
+    /// regimes - this is what suppresses flip-flopping right at
+    /// `agreement_threshold`.
+    pub fn with_hysteresis(mut self, confirm_bars: usize, enter_threshold: f64, exit_threshold: f64) -> Self {
+        self.hysteresis_confirm_bars = confirm_bars;
+        self.hysteresis_enter_threshold = enter_threshold;
+        self.hysteresis_exit_threshold = exit_threshold;
+        self
+    }
 }
 
-/// Result from ensemble detection
-#[derive(Debug, Clone)]
+/// Result from a panel vote
+#[derive(Debug)]
 pub struct EnsembleResult {
     /// Final regime determination
     pub regime: MarketRegime,
     /// Combined confidence
     pub confidence: f64,
-    /// Whether methods agree
+    /// Whether the panel reached majority agreement on a regime category
     pub methods_agree: bool,
-    /// Indicator-based result
-    pub indicator_result: RegimeConfidence,
-    /// HMM-based result
-    pub hmm_result: RegimeConfidence,
-    /// Individual method regimes for debugging
-    pub indicator_regime: MarketRegime,
-    pub hmm_regime: MarketRegime,
+    /// Every source's raw call this bar, in panel order
+    pub source_results: Vec<RegimeConfidence>,
 }
 
 impl EnsembleResult {
@@ -104,32 +246,100 @@ impl EnsembleResult {
     }
 }
 
-/// Ensemble regime detector combining multiple methods
-#[derive(Debug)]
+/// A per-source regime category awaiting its lagged realized label, kept
+/// only when `config.adaptive_weighting` is on
+struct PendingPrediction {
+    close_at_prediction: f64,
+    source_categories: Vec<RegimeCategory>,
+}
+
+/// Weighted panel of `RegimeSource`s combined into a single regime call
 pub struct EnsembleRegimeDetector {
     config: EnsembleConfig,
 
-    /// Technical indicator-based detector
-    indicator_detector: RegimeDetector,
-
-    /// Hidden Markov Model detector
-    hmm_detector: HMMRegimeDetector,
+    /// Weighted panel members, in registration order
+    sources: Vec<(Box<dyn RegimeSource>, f64)>,
 
     /// Current ensemble regime
     current_regime: MarketRegime,
 
     /// Track agreement history
     agreement_history: Vec<bool>,
+
+    /// Online per-source weights from the multiplicative-weights update,
+    /// `None` until the first lagged label resolves (falls back to each
+    /// source's static weight until then)
+    adaptive_weights: Option<Vec<f64>>,
+    /// Predictions awaiting a `realized_label_lag`-bar-old close to score against
+    pending: VecDeque<PendingPrediction>,
+    /// Rolling history of `|forward return|` magnitudes, ranked to turn the
+    /// current forward move into the realized-volatility percentile
+    move_history: VecDeque<f64>,
+
+    /// Disagreeing regime currently accumulating votes toward confirmation,
+    /// and how many consecutive bars/how much confidence it has
+    /// accumulated so far. Reset whenever the vote agrees with
+    /// `current_regime` or the leading candidate changes.
+    pending_candidate: Option<MarketRegime>,
+    pending_streak: usize,
+    pending_score: f64,
+
+    /// Consecutive bars `current_regime` has held, reset on every switch
+    regime_dwell: usize,
+}
+
+impl std::fmt::Debug for EnsembleRegimeDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnsembleRegimeDetector")
+            .field("config", &self.config)
+            .field("source_count", &self.sources.len())
+            .field("current_regime", &self.current_regime)
+            .field("adaptive_weights", &self.adaptive_weights)
+            .field("regime_dwell", &self.regime_dwell)
+            .finish()
+    }
 }
 
 impl EnsembleRegimeDetector {
+    /// Build the default two-source panel: an indicator detector weighted
+    /// by `ensemble_config.indicator_weight` and an HMM detector weighted
+    /// by `ensemble_config.hmm_weight`
     pub fn new(ensemble_config: EnsembleConfig, indicator_config: RegimeConfig) -> Self {
+        let indicator_weight = ensemble_config.indicator_weight;
+        let hmm_weight = ensemble_config.hmm_weight;
+        Self::with_sources(
+            ensemble_config,
+            vec![
+                (
+                    Box::new(RegimeDetector::new(indicator_config)) as Box<dyn RegimeSource>,
+                    indicator_weight,
+                ),
+                (
+                    Box::new(HMMRegimeDetector::crypto_optimized()) as Box<dyn RegimeSource>,
+                    hmm_weight,
+                ),
+            ],
+        )
+    }
+
+    /// Build a panel from an arbitrary, caller-supplied list of weighted
+    /// sources - three or more confirmation detectors, custom `RegimeSource`
+    /// implementations, etc. The first source is used as the fallback read
+    /// while `require_full_warmup` is true and the panel isn't ready yet.
+    pub fn with_sources(config: EnsembleConfig, sources: Vec<(Box<dyn RegimeSource>, f64)>) -> Self {
+        assert!(!sources.is_empty(), "EnsembleRegimeDetector needs at least one source");
         Self {
-            config: ensemble_config,
-            indicator_detector: RegimeDetector::new(indicator_config),
-            hmm_detector: HMMRegimeDetector::crypto_optimized(),
+            config,
+            sources,
             current_regime: MarketRegime::Uncertain,
             agreement_history: Vec::with_capacity(100),
+            adaptive_weights: None,
+            pending: VecDeque::new(),
+            move_history: VecDeque::with_capacity(100),
+            pending_candidate: None,
+            pending_streak: 0,
+            pending_score: 0.0,
+            regime_dwell: 0,
         }
     }
 
@@ -143,128 +353,302 @@ impl EnsembleRegimeDetector {
         Self::new(EnsembleConfig::balanced(), RegimeConfig::crypto_optimized())
     }
 
+    /// Register an additional weighted source on an already-built panel
+    pub fn add_source(&mut self, source: Box<dyn RegimeSource>, weight: f64) {
+        self.sources.push((source, weight));
+    }
+
     /// Update with new OHLC data
     pub fn update(&mut self, high: f64, low: f64, close: f64) -> EnsembleResult {
-        // Update both detectors
-        let indicator_result = self.indicator_detector.update(high, low, close);
-        let hmm_result = self.hmm_detector.update_ohlc(high, low, close);
-
-        // Get individual regimes
-        let indicator_regime = indicator_result.regime;
-        let hmm_regime = hmm_result.regime;
+        let results: Vec<RegimeConfidence> = self
+            .sources
+            .iter_mut()
+            .map(|(source, _)| source.update(high, low, close))
+            .collect();
+
+        if self.config.adaptive_weighting {
+            self.resolve_pending_labels(close);
+            let categories = results.iter().map(|r| RegimeCategory::from(r.regime)).collect();
+            self.pending.push_back(PendingPrediction {
+                close_at_prediction: close,
+                source_categories: categories,
+            });
+        }
 
-        // Check if HMM is warmed up
-        let hmm_ready = self.hmm_detector.is_ready();
+        let ready: Vec<bool> = self.sources.iter().map(|(s, _)| s.is_ready()).collect();
+        let usable: Vec<usize> = if self.config.require_full_warmup {
+            if ready.iter().all(|&r| r) {
+                (0..self.sources.len()).collect()
+            } else {
+                Vec::new()
+            }
+        } else {
+            (0..self.sources.len()).filter(|&i| ready[i]).collect()
+        };
 
-        // Determine if methods agree
-        let methods_agree = self.regimes_agree(indicator_regime, hmm_regime);
+        let (regime, confidence, methods_agree) = if usable.is_empty() {
+            // Panel isn't warmed up yet - fall back to the first source's raw
+            // read, bypassing hysteresis entirely
+            self.pending_candidate = None;
+            self.pending_streak = 0;
+            self.pending_score = 0.0;
+            self.regime_dwell = 0;
+            (results[0].regime, results[0].confidence, false)
+        } else {
+            let (candidate, confidence, methods_agree) = self.combine_results(&results, &usable);
+            let regime = self.apply_hysteresis(candidate, confidence, &results, &usable);
+            (regime, confidence, methods_agree)
+        };
 
-        // Track agreement
+        self.current_regime = regime;
         self.agreement_history.push(methods_agree);
         if self.agreement_history.len() > 100 {
             self.agreement_history.remove(0);
         }
 
-        // Calculate combined regime and confidence
-        let (regime, confidence) = if self.config.require_hmm_warmup && !hmm_ready {
-            // Use only indicators until HMM is ready
-            (indicator_regime, indicator_result.confidence)
-        } else {
-            self.combine_results(
-                indicator_regime,
-                indicator_result.confidence,
-                hmm_regime,
-                hmm_result.confidence,
-                methods_agree,
-            )
-        };
-
-        self.current_regime = regime;
-
         EnsembleResult {
             regime,
             confidence,
             methods_agree,
-            indicator_result,
-            hmm_result,
-            indicator_regime,
-            hmm_regime,
+            source_results: results,
         }
     }
 
-    /// Check if two regimes agree (same category)
-    fn regimes_agree(&self, r1: MarketRegime, r2: MarketRegime) -> bool {
-        match (r1, r2) {
-            (MarketRegime::Trending(_), MarketRegime::Trending(_)) => true,
-            (MarketRegime::MeanReverting, MarketRegime::MeanReverting) => true,
-            (MarketRegime::Volatile, MarketRegime::Volatile) => true,
-            (MarketRegime::Uncertain, MarketRegime::Uncertain) => true,
-            _ => false,
+    /// Schmitt-trigger-style debounce over the raw `candidate` vote:
+    /// a candidate that disagrees with `current_regime` only takes over
+    /// once it has either won `hysteresis_confirm_bars` consecutive votes
+    /// or accumulated `hysteresis_enter_threshold` of summed confidence
+    /// across its streak, AND the current regime's own weighted vote share
+    /// has fallen below `hysteresis_exit_threshold`. Agreeing with
+    /// `current_regime` always resets the pending candidate and extends
+    /// `regime_dwell`.
+    fn apply_hysteresis(
+        &mut self,
+        candidate: MarketRegime,
+        confidence: f64,
+        results: &[RegimeConfidence],
+        usable: &[usize],
+    ) -> MarketRegime {
+        self.regime_dwell += 1;
+
+        if candidate == self.current_regime {
+            self.pending_candidate = None;
+            self.pending_streak = 0;
+            self.pending_score = 0.0;
+            return self.current_regime;
+        }
+
+        if self.pending_candidate == Some(candidate) {
+            self.pending_streak += 1;
+            self.pending_score += confidence;
+        } else {
+            self.pending_candidate = Some(candidate);
+            self.pending_streak = 1;
+            self.pending_score = confidence;
+        }
+
+        let current_support = self.regime_support_fraction(results, usable, self.current_regime);
+        let current_exhausted = current_support < self.config.hysteresis_exit_threshold;
+        let confirmed = self.pending_streak >= self.config.hysteresis_confirm_bars.max(1)
+            || self.pending_score >= self.config.hysteresis_enter_threshold;
+
+        if current_exhausted && confirmed {
+            self.regime_dwell = 0;
+            self.pending_candidate = None;
+            self.pending_streak = 0;
+            self.pending_score = 0.0;
+            return candidate;
+        }
+
+        self.current_regime
+    }
+
+    /// Fraction of total usable weight whose vote is exactly `regime`
+    /// (direction included for `Trending`)
+    fn regime_support_fraction(&self, results: &[RegimeConfidence], usable: &[usize], regime: MarketRegime) -> f64 {
+        let total_weight: f64 = usable.iter().map(|&i| self.source_weight(i)).sum();
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+        let matching_weight: f64 = usable
+            .iter()
+            .filter(|&&i| results[i].regime == regime)
+            .map(|&i| self.source_weight(i))
+            .sum();
+        matching_weight / total_weight
+    }
+
+    /// Consecutive bars `current_regime` has held
+    pub fn bars_in_regime(&self) -> usize {
+        self.regime_dwell
+    }
+
+    /// Disagreeing regime currently accumulating votes toward confirmation,
+    /// if any, and how many consecutive bars it has won so far
+    pub fn pending_candidate(&self) -> Option<(MarketRegime, usize)> {
+        self.pending_candidate.map(|regime| (regime, self.pending_streak))
+    }
+
+    /// Current weight for source `i`: the learned adaptive weight once one
+    /// has been computed, otherwise the source's static registration weight
+    fn source_weight(&self, i: usize) -> f64 {
+        self.adaptive_weights
+            .as_ref()
+            .map(|weights| weights[i])
+            .unwrap_or(self.sources[i].1)
+    }
+
+    /// Resolve and learn from every prediction whose label is now available,
+    /// i.e. `config.realized_label_lag` bars have passed since it was made
+    fn resolve_pending_labels(&mut self, current_close: f64) {
+        let lag = self.config.realized_label_lag.max(1);
+        while self.pending.len() >= lag {
+            let Some(prediction) = self.pending.pop_front() else {
+                break;
+            };
+            let label = self.realized_label(prediction.close_at_prediction, current_close);
+            self.apply_multiplicative_weights_update(&prediction.source_categories, label);
+        }
+    }
+
+    /// Classify the realized outcome between a prediction and `lag` bars
+    /// later: `Volatile` if the forward move ranks high in this detector's
+    /// own move history, else `Trending` by sign if the move clears
+    /// `realized_trend_threshold`, else `MeanReverting`
+    fn realized_label(&mut self, close_then: f64, close_now: f64) -> RegimeCategory {
+        let forward_return = (close_now - close_then) / close_then;
+        let forward_move = forward_return.abs();
+
+        let percentile = if self.move_history.len() < 10 {
+            50.0
+        } else {
+            let below = self.move_history.iter().filter(|&&m| m < forward_move).count();
+            (below as f64 / self.move_history.len() as f64) * 100.0
+        };
+        self.move_history.push_back(forward_move);
+        if self.move_history.len() > 100 {
+            self.move_history.pop_front();
+        }
+
+        if percentile >= self.config.realized_volatile_percentile {
+            RegimeCategory::Volatile
+        } else if forward_return > self.config.realized_trend_threshold
+            || forward_return < -self.config.realized_trend_threshold
+        {
+            RegimeCategory::Trending
+        } else {
+            RegimeCategory::MeanReverting
         }
     }
 
-    /// Check if regimes agree on direction too
-    fn regimes_agree_direction(&self, r1: MarketRegime, r2: MarketRegime) -> bool {
-        match (r1, r2) {
-            (MarketRegime::Trending(d1), MarketRegime::Trending(d2)) => d1 == d2,
-            (MarketRegime::MeanReverting, MarketRegime::MeanReverting) => true,
-            (MarketRegime::Volatile, MarketRegime::Volatile) => true,
-            (MarketRegime::Uncertain, MarketRegime::Uncertain) => true,
-            _ => false,
+    /// `w_i ← w_i · exp(−η · l_i)`, renormalized to sum to 1, where `l_i` is
+    /// 0/1 depending on whether source `i`'s category call matched `label`
+    fn apply_multiplicative_weights_update(&mut self, source_categories: &[RegimeCategory], label: RegimeCategory) {
+        let eta = self.config.adaptive_learning_rate;
+        let mut weights = self
+            .adaptive_weights
+            .take()
+            .unwrap_or_else(|| self.sources.iter().map(|(_, w)| *w).collect());
+
+        for (weight, &category) in weights.iter_mut().zip(source_categories) {
+            let loss = if category == label { 0.0 } else { 1.0 };
+            *weight *= (-eta * loss).exp();
         }
+        let total: f64 = weights.iter().sum();
+        if total > 0.0 {
+            for weight in weights.iter_mut() {
+                *weight /= total;
+            }
+        }
+
+        self.adaptive_weights = Some(weights);
     }
 
-    /// Combine results from both methods
-    fn combine_results(
-        &self,
-        indicator_regime: MarketRegime,
-        indicator_conf: f64,
-        hmm_regime: MarketRegime,
-        hmm_conf: f64,
-        agree: bool,
-    ) -> (MarketRegime, f64) {
-        let w_ind = self.config.indicator_weight;
-        let w_hmm = self.config.hmm_weight;
+    /// Online per-source weights, once `config.adaptive_weighting` has
+    /// resolved at least one lagged label
+    pub fn adaptive_weights(&self) -> Option<&[f64]> {
+        self.adaptive_weights.as_deref()
+    }
+
+    /// Weighted plurality vote over regime category, with a direction vote
+    /// within `Trending` and a weighted-average confidence across every
+    /// usable source
+    fn combine_results(&self, results: &[RegimeConfidence], usable: &[usize]) -> (MarketRegime, f64, bool) {
+        let total_weight: f64 = usable.iter().map(|&i| self.source_weight(i)).sum();
+        if total_weight <= 0.0 {
+            let fallback = usable[0];
+            return (results[fallback].regime, results[fallback].confidence, false);
+        }
+
+        let avg_conf: f64 = usable
+            .iter()
+            .map(|&i| self.source_weight(i) * results[i].confidence)
+            .sum::<f64>()
+            / total_weight;
+
+        let mut category_weight: HashMap<RegimeCategory, f64> = HashMap::new();
+        let mut direction_weight: HashMap<TrendDirection, f64> = HashMap::new();
+        for &i in usable {
+            let weight = self.source_weight(i);
+            let regime = results[i].regime;
+            *category_weight.entry(RegimeCategory::from(regime)).or_insert(0.0) += weight;
+            if let MarketRegime::Trending(direction) = regime {
+                *direction_weight.entry(direction).or_insert(0.0) += weight;
+            }
+        }
 
-        // Weighted confidence
-        let mut combined_conf = w_ind * indicator_conf + w_hmm * hmm_conf;
+        let (&winning_category, &winning_weight) = category_weight
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .expect("usable is non-empty");
+
+        let agree = winning_weight / total_weight > 0.5;
+
+        let mut combined_conf = avg_conf;
+        let winning_direction = direction_weight
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(&direction, _)| direction);
 
-        // Adjust confidence based on agreement
         if agree {
-            // Boost confidence when methods agree
             combined_conf += self.config.agreement_confidence_boost;
 
-            // Extra boost if they agree on direction too
-            if self.regimes_agree_direction(indicator_regime, hmm_regime) {
-                combined_conf += 0.05;
+            // Extra boost if the panel also agrees on direction within Trending
+            if winning_category == RegimeCategory::Trending {
+                if let Some(direction) = winning_direction {
+                    if direction_weight[&direction] / winning_weight > 0.5 {
+                        combined_conf += 0.05;
+                    }
+                }
             }
         } else {
-            // Penalty when methods disagree
             combined_conf -= self.config.disagreement_confidence_penalty;
         }
-
         combined_conf = combined_conf.clamp(0.0, 1.0);
 
-        // Determine final regime
         let regime = if agree {
-            // Use the regime they agree on (prefer indicator's direction if trending)
-            match indicator_regime {
-                MarketRegime::Trending(_) => indicator_regime,
-                _ => indicator_regime,
+            match winning_category {
+                RegimeCategory::Trending => {
+                    MarketRegime::Trending(winning_direction.unwrap_or(TrendDirection::Bullish))
+                }
+                RegimeCategory::MeanReverting => MarketRegime::MeanReverting,
+                RegimeCategory::Volatile => MarketRegime::Volatile,
+                RegimeCategory::Squeeze => MarketRegime::Squeeze,
+                RegimeCategory::Uncertain => MarketRegime::Uncertain,
             }
         } else if combined_conf < self.config.agreement_threshold {
             // Low confidence due to disagreement - be conservative
             MarketRegime::Uncertain
         } else {
-            // Use higher-weighted method's regime
-            if w_ind >= w_hmm {
-                indicator_regime
-            } else {
-                hmm_regime
-            }
+            // No majority - defer to the single heaviest source's regime
+            let heaviest = *usable
+                .iter()
+                .max_by(|&&a, &&b| self.source_weight(a).partial_cmp(&self.source_weight(b)).unwrap())
+                .expect("usable is non-empty");
+            results[heaviest].regime
         };
 
-        (regime, combined_conf)
+        (regime, combined_conf, agree)
     }
 
     /// Get current regime
@@ -281,32 +665,43 @@ impl EnsembleRegimeDetector {
         agrees as f64 / self.agreement_history.len() as f64
     }
 
-    /// Check if both detectors are ready
+    /// Check if the panel is ready to vote (see `require_full_warmup`)
     pub fn is_ready(&self) -> bool {
-        self.indicator_detector.is_ready()
-            && (!self.config.require_hmm_warmup || self.hmm_detector.is_ready())
+        if self.config.require_full_warmup {
+            self.sources.iter().all(|(s, _)| s.is_ready())
+        } else {
+            self.sources.iter().any(|(s, _)| s.is_ready())
+        }
+    }
+
+    /// State probabilities from the first source in the panel that tracks
+    /// hidden states (e.g. an HMM source), if any
+    pub fn hmm_state_probabilities(&self) -> Option<Vec<f64>> {
+        self.sources.iter().find_map(|(s, _)| s.state_probabilities())
     }
 
-    /// Get HMM state probabilities
-    pub fn hmm_state_probabilities(&self) -> &[f64] {
-        self.hmm_detector.state_probabilities()
+    /// Expected regime duration from the first source that tracks one
+    pub fn expected_regime_duration(&self) -> Option<f64> {
+        self.sources.iter().find_map(|(s, _)| s.expected_duration())
     }
 
-    /// Get HMM expected regime duration
-    pub fn expected_regime_duration(&self) -> f64 {
-        self.hmm_detector
-            .expected_regime_duration(self.hmm_detector.current_state_index())
+    /// ATR from the first source in the panel that tracks one
+    pub fn atr_value(&self) -> Option<f64> {
+        self.sources.iter().find_map(|(s, _)| s.atr_value())
     }
 
     /// Get detailed status for monitoring
     pub fn status(&self) -> EnsembleStatus {
         EnsembleStatus {
             current_regime: self.current_regime,
-            indicator_ready: self.indicator_detector.is_ready(),
-            hmm_ready: self.hmm_detector.is_ready(),
+            sources_ready: self.sources.iter().filter(|(s, _)| s.is_ready()).count(),
+            source_count: self.sources.len(),
             agreement_rate: self.agreement_rate(),
-            hmm_state_probs: self.hmm_detector.state_probabilities().to_vec(),
+            hmm_state_probs: self.hmm_state_probabilities(),
             expected_duration: self.expected_regime_duration(),
+            adaptive_weights: self.adaptive_weights.clone(),
+            bars_in_regime: self.regime_dwell,
+            pending_candidate: self.pending_candidate(),
         }
     }
 }
@@ -315,22 +710,34 @@ impl EnsembleRegimeDetector {
 #[derive(Debug, Clone)]
 pub struct EnsembleStatus {
     pub current_regime: MarketRegime,
-    pub indicator_ready: bool,
-    pub hmm_ready: bool,
+    pub sources_ready: usize,
+    pub source_count: usize,
     pub agreement_rate: f64,
-    pub hmm_state_probs: Vec<f64>,
-    pub expected_duration: f64,
+    pub hmm_state_probs: Option<Vec<f64>>,
+    pub expected_duration: Option<f64>,
+    /// Learned per-source weights, once `config.adaptive_weighting` has
+    /// resolved at least one lagged label
+    pub adaptive_weights: Option<Vec<f64>>,
+    /// Consecutive bars `current_regime` has held
+    pub bars_in_regime: usize,
+    /// Disagreeing regime currently accumulating votes toward
+    /// confirmation, if any, with its consecutive win count
+    pub pending_candidate: Option<(MarketRegime, usize)>,
 }
 
 impl std::fmt::Display for EnsembleStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Regime: {} | Agreement: {:.1}% | HMM Ready: {} | Expected Duration: {:.1} bars",
+            "Regime: {} | Dwell: {} bars | Agreement: {:.1}% | Ready: {}/{} | Expected Duration: {}",
             self.current_regime,
+            self.bars_in_regime,
             self.agreement_rate * 100.0,
-            self.hmm_ready,
+            self.sources_ready,
+            self.source_count,
             self.expected_duration
+                .map(|d| format!("{:.1} bars", d))
+                .unwrap_or_else(|| "n/a".to_string()),
         )
     }
 }
@@ -345,23 +752,6 @@ mod tests {
         assert!(!ensemble.is_ready());
     }
 
-    #[test]
-    fn test_regimes_agree() {
-        let ensemble = EnsembleRegimeDetector::default_config();
-
-        // Same category should agree
-        assert!(ensemble.regimes_agree(
-            MarketRegime::Trending(TrendDirection::Bullish),
-            MarketRegime::Trending(TrendDirection::Bearish)
-        ));
-
-        // Different categories should not agree
-        assert!(!ensemble.regimes_agree(
-            MarketRegime::Trending(TrendDirection::Bullish),
-            MarketRegime::MeanReverting
-        ));
-    }
-
     #[test]
     fn test_agreement_rate() {
         let mut ensemble = EnsembleRegimeDetector::default_config();
@@ -397,4 +787,195 @@ mod tests {
         // In a strong trend, agreement rate should be reasonable
         assert!(ensemble.agreement_rate() > 0.3);
     }
+
+    #[test]
+    fn test_three_source_panel_warms_up_with_every_source() {
+        // Three equally-weighted, identically configured indicator sources
+        // fed the same steady rally: the panel becomes ready and keeps all
+        // three sources registered
+        let config = EnsembleConfig {
+            agreement_threshold: 0.3,
+            require_full_warmup: false,
+            ..EnsembleConfig::default()
+        };
+        let mut ensemble = EnsembleRegimeDetector::with_sources(
+            config,
+            vec![
+                (Box::new(RegimeDetector::new(RegimeConfig::crypto_optimized())) as Box<dyn RegimeSource>, 1.0),
+                (Box::new(RegimeDetector::new(RegimeConfig::crypto_optimized())) as Box<dyn RegimeSource>, 1.0),
+                (Box::new(RegimeDetector::new(RegimeConfig::crypto_optimized())) as Box<dyn RegimeSource>, 1.0),
+            ],
+        );
+
+        let mut price = 100.0;
+        for _ in 0..200 {
+            price *= 1.004;
+            ensemble.update(price * 1.002, price * 0.998, price);
+        }
+
+        assert!(ensemble.is_ready());
+        assert_eq!(ensemble.sources.len(), 3);
+    }
+
+    #[test]
+    fn test_adaptive_weights_stay_none_until_lag_fills() {
+        let config = EnsembleConfig {
+            adaptive_weighting: true,
+            realized_label_lag: 20,
+            require_full_warmup: false,
+            ..EnsembleConfig::default()
+        };
+        let mut ensemble = EnsembleRegimeDetector::new(config, RegimeConfig::crypto_optimized());
+
+        let mut price = 100.0;
+        for _ in 0..10 {
+            price *= 1.002;
+            ensemble.update(price * 1.002, price * 0.998, price);
+        }
+
+        assert!(ensemble.adaptive_weights().is_none());
+    }
+
+    #[test]
+    fn test_adaptive_weights_penalize_a_consistently_wrong_source() {
+        // A source that always calls `Volatile` is wrong through a steady
+        // uptrend, so its learned weight should end up below the other two
+        struct AlwaysVolatile;
+
+        impl std::fmt::Debug for AlwaysVolatile {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "AlwaysVolatile")
+            }
+        }
+
+        impl RegimeSource for AlwaysVolatile {
+            fn update(&mut self, _high: f64, _low: f64, _close: f64) -> RegimeConfidence {
+                RegimeConfidence::new(MarketRegime::Volatile, 0.9)
+            }
+
+            fn is_ready(&self) -> bool {
+                true
+            }
+        }
+
+        let config = EnsembleConfig {
+            adaptive_weighting: true,
+            realized_label_lag: 5,
+            require_full_warmup: false,
+            realized_trend_threshold: 0.001,
+            ..EnsembleConfig::default()
+        };
+        let mut ensemble = EnsembleRegimeDetector::with_sources(
+            config,
+            vec![
+                (Box::new(RegimeDetector::new(RegimeConfig::crypto_optimized())) as Box<dyn RegimeSource>, 1.0),
+                (Box::new(HMMRegimeDetector::crypto_optimized()) as Box<dyn RegimeSource>, 1.0),
+                (Box::new(AlwaysVolatile) as Box<dyn RegimeSource>, 1.0),
+            ],
+        );
+
+        let mut price = 100.0;
+        for _ in 0..150 {
+            price *= 1.004;
+            ensemble.update(price * 1.002, price * 0.998, price);
+        }
+
+        let weights = ensemble.adaptive_weights().expect("label buffer should have filled by now");
+        assert!(weights[2] < weights[0]);
+        assert!(weights[2] < weights[1]);
+    }
+
+    /// A single source that always reports `regime` at a fixed confidence,
+    /// used to drive `EnsembleRegimeDetector::update` with a deterministic
+    /// vote for the hysteresis tests below.
+    #[derive(Debug)]
+    struct FixedSource {
+        regime: MarketRegime,
+        confidence: f64,
+    }
+
+    impl RegimeSource for FixedSource {
+        fn update(&mut self, _high: f64, _low: f64, _close: f64) -> RegimeConfidence {
+            RegimeConfidence::new(self.regime, self.confidence)
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+    }
+
+    fn single_source_ensemble(config: EnsembleConfig, regime: MarketRegime, confidence: f64) -> EnsembleRegimeDetector {
+        EnsembleRegimeDetector::with_sources(
+            config,
+            vec![(Box::new(FixedSource { regime, confidence }) as Box<dyn RegimeSource>, 1.0)],
+        )
+    }
+
+    #[test]
+    fn test_default_hysteresis_switches_on_first_disagreeing_bar() {
+        let mut ensemble = single_source_ensemble(
+            EnsembleConfig { require_full_warmup: false, ..EnsembleConfig::default() },
+            MarketRegime::Volatile,
+            0.9,
+        );
+
+        let result = ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(result.regime, MarketRegime::Volatile);
+        assert_eq!(ensemble.bars_in_regime(), 0);
+    }
+
+    #[test]
+    fn test_confirm_bars_debounces_a_disagreeing_candidate() {
+        let config = EnsembleConfig {
+            require_full_warmup: false,
+            ..EnsembleConfig::default()
+        }
+        .with_hysteresis(3, 10.0, 1.0);
+        let mut ensemble = single_source_ensemble(config, MarketRegime::Volatile, 0.9);
+
+        let first = ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(first.regime, MarketRegime::Uncertain, "one dissenting bar shouldn't switch yet");
+        assert_eq!(ensemble.pending_candidate(), Some((MarketRegime::Volatile, 1)));
+
+        let second = ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(second.regime, MarketRegime::Uncertain, "two dissenting bars still below confirm_bars");
+        assert_eq!(ensemble.pending_candidate(), Some((MarketRegime::Volatile, 2)));
+
+        let third = ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(third.regime, MarketRegime::Volatile, "third consecutive bar confirms the switch");
+        assert_eq!(ensemble.bars_in_regime(), 0);
+        assert!(ensemble.pending_candidate().is_none());
+    }
+
+    #[test]
+    fn test_enter_threshold_lets_a_high_confidence_candidate_switch_early() {
+        let config = EnsembleConfig {
+            require_full_warmup: false,
+            ..EnsembleConfig::default()
+        }
+        .with_hysteresis(10, 0.5, 1.0);
+        let mut ensemble = single_source_ensemble(config, MarketRegime::Volatile, 0.9);
+
+        // One bar accumulates 1.0 of confidence (0.9 plus the agreement
+        // boost, capped at 1.0), already past the 0.5 enter_threshold, so it
+        // should switch despite confirm_bars: 10.
+        let result = ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(result.regime, MarketRegime::Volatile);
+    }
+
+    #[test]
+    fn test_bars_in_regime_tracks_consecutive_agreeing_updates() {
+        let mut ensemble = single_source_ensemble(
+            EnsembleConfig { require_full_warmup: false, ..EnsembleConfig::default() },
+            MarketRegime::Trending(TrendDirection::Bullish),
+            0.9,
+        );
+
+        ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(ensemble.bars_in_regime(), 0);
+        ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(ensemble.bars_in_regime(), 1);
+        ensemble.update(101.0, 99.0, 100.0);
+        assert_eq!(ensemble.bars_in_regime(), 2);
+    }
 }
@@ -7,6 +7,7 @@
 //! making no assumptions about what indicators define each regime.
 
 use std::collections::VecDeque;
+use rand::Rng;
 use super::types::{MarketRegime, TrendDirection, RegimeConfidence};
 
 /// Configuration for HMM regime detector
@@ -24,6 +25,15 @@ pub struct HMMConfig {
     pub lookback_window: usize,
     /// Confidence threshold for regime classification
     pub min_confidence: f64,
+    /// Track per-state dwell-time histograms and fit them during
+    /// `baum_welch_update`, enabling `residual_regime_life`. When false,
+    /// the only duration signal is the geometric dwell time implied by the
+    /// transition diagonal (`expected_regime_duration`).
+    pub track_durations: bool,
+    /// Number of time buckets for a periodic ("controlled HMM") transition
+    /// matrix, e.g. 24 for hourly intraday seasonality or 7 for weekday
+    /// seasonality. `None` (the default) keeps a single static matrix.
+    pub period_length: Option<usize>,
 }
 
 impl Default for HMMConfig {
@@ -35,6 +45,8 @@ impl Default for HMMConfig {
             transition_smoothing: 0.1,
             lookback_window: 252,  // ~1 year of daily data
             min_confidence: 0.6,
+            track_durations: false,
+            period_length: None,
         }
     }
 }
@@ -49,6 +61,8 @@ impl HMMConfig {
             transition_smoothing: 0.05,
             lookback_window: 100,
             min_confidence: 0.5,
+            track_durations: false,
+            period_length: None,
         }
     }
     
@@ -61,6 +75,8 @@ impl HMMConfig {
             transition_smoothing: 0.15,
             lookback_window: 500,
             min_confidence: 0.7,
+            track_durations: false,
+            period_length: None,
         }
     }
 }
@@ -94,6 +110,13 @@ impl GaussianState {
         let normalizer = (2.0 * std::f64::consts::PI * self.variance).sqrt();
         exponent.exp() / normalizer
     }
+
+    /// Log probability density - avoids the underflow that plain `pdf()`
+    /// hits for tiny variances or large lookback windows
+    fn log_pdf(&self, x: f64) -> f64 {
+        let diff = x - self.mean;
+        -0.5 * (2.0 * std::f64::consts::PI * self.variance).ln() - 0.5 * diff * diff / self.variance
+    }
     
     /// Update statistics with new observation
     fn update(&mut self, x: f64, weight: f64, learning_rate: f64) {
@@ -112,6 +135,22 @@ impl GaussianState {
     }
 }
 
+/// Forward-looking regime-occupancy forecast produced by
+/// `HMMRegimeDetector::forecast_regime_distribution`, a Monte-Carlo
+/// alternative to the point-estimate `expected_regime_duration`.
+#[derive(Debug, Clone)]
+pub struct RegimeForecast {
+    /// `step_probabilities[t][j]` = fraction of simulated paths in state
+    /// `j` at bar `t + 1` (`t` in `0..horizon`)
+    pub step_probabilities: Vec<Vec<f64>>,
+    /// Fraction of paths still in the regime active at simulation start
+    /// by the end of the horizon
+    pub prob_still_current_regime: f64,
+    /// Mean number of bars until a path first leaves the current regime,
+    /// right-censored at `horizon` for paths that never leave
+    pub expected_bars_to_exit: f64,
+}
+
 /// Hidden Markov Model for regime detection
 #[derive(Debug)]
 pub struct HMMRegimeDetector {
@@ -122,7 +161,26 @@ pub struct HMMRegimeDetector {
     
     /// Transition probability matrix A[i][j] = P(state_j | state_i)
     transition_matrix: Vec<Vec<f64>>,
-    
+
+    /// Per-bucket transition matrices for the "controlled HMM" mode
+    /// (`config.period_length` = `Some(L)`), indexed `[bucket][i][j]`. Seeded
+    /// from `transition_matrix` and re-estimated per bucket by
+    /// `baum_welch_update`. `None` when `period_length` is `None`, in which
+    /// case `transition_matrix` is used for every step.
+    periodic_transition_matrices: Option<Vec<Vec<Vec<f64>>>>,
+
+    /// Time bucket (`timestamp % period_length`, pre-reduction) supplied
+    /// alongside each entry in `returns_history`, in lockstep with it.
+    /// `None` for observations submitted through the non-periodic
+    /// `update`/`update_ohlc` entry points.
+    time_buckets: VecDeque<Option<usize>>,
+
+    /// Bucket used to select the transition matrix for the most recent
+    /// `forward_step`, so `predict_next_state` projects forward with the
+    /// same period-appropriate matrix rather than always falling back to
+    /// the static one.
+    last_time_bucket: Option<usize>,
+
     /// Initial state probabilities
     initial_probs: Vec<f64>,
     
@@ -146,6 +204,22 @@ pub struct HMMRegimeDetector {
     
     /// Last detected regime
     last_regime: MarketRegime,
+
+    /// Accumulated sequence log-likelihood `-Σₜ ln(cₜ)`, where `cₜ` is the
+    /// per-step forward scaling factor. Tracked incrementally by
+    /// `forward_step` and refreshed wholesale by `baum_welch_update`; used
+    /// for model comparison (e.g. choosing `n_states`) rather than for any
+    /// routing decision.
+    log_likelihood: f64,
+
+    /// Observed dwell-time (regime duration) samples per state, refit from
+    /// the decoded Viterbi path during `baum_welch_update` when
+    /// `config.track_durations` is set. Used by `residual_regime_life`.
+    duration_histories: Vec<Vec<usize>>,
+
+    /// Number of consecutive steps (including the most recent) that
+    /// `current_state` has held
+    current_state_dwell: usize,
 }
 
 impl HMMRegimeDetector {
@@ -190,10 +264,16 @@ impl HMMRegimeDetector {
         let initial_probs = vec![1.0 / n as f64; n];
         let state_probs = initial_probs.clone();
         
+        let periodic_transition_matrices = config.period_length
+            .map(|l| vec![transition_matrix.clone(); l]);
+
         Self {
             config: config.clone(),
             states,
             transition_matrix,
+            periodic_transition_matrices,
+            time_buckets: VecDeque::with_capacity(config.lookback_window),
+            last_time_bucket: None,
             initial_probs,
             state_probs,
             returns_history: VecDeque::with_capacity(config.lookback_window),
@@ -202,6 +282,9 @@ impl HMMRegimeDetector {
             current_confidence: 0.0,
             n_observations: 0,
             last_regime: MarketRegime::Uncertain,
+            log_likelihood: 0.0,
+            duration_histories: vec![Vec::new(); n],
+            current_state_dwell: 0,
         }
     }
     
@@ -214,78 +297,138 @@ impl HMMRegimeDetector {
     pub fn crypto_optimized() -> Self {
         Self::new(HMMConfig::crypto_optimized())
     }
-    
+
+    /// Build a single-state detector seeded with a specific Gaussian prior
+    /// instead of the built-in bull/bear/high-vol priors `new` picks for
+    /// `n_states` of 2 or 3. Used by `MultiModelRegimeDetector` to fit one
+    /// dedicated model per candidate regime rather than sharing states
+    /// across a multi-state HMM; `config.n_states` is forced to 1.
+    pub fn single_state(mean: f64, variance: f64, mut config: HMMConfig) -> Self {
+        config.n_states = 1;
+        let mut detector = Self::new(config);
+        detector.states = vec![GaussianState::new(mean, variance)];
+        detector
+    }
+
     /// Update with new price and get regime
     pub fn update(&mut self, close: f64) -> RegimeConfidence {
+        self.update_optionally_at(close, None)
+    }
+
+    /// Update with OHLC data
+    pub fn update_ohlc(&mut self, _high: f64, _low: f64, close: f64) -> RegimeConfidence {
+        self.update(close)
+    }
+
+    /// Update with new price, attributing the observation to time bucket
+    /// `timestamp % period_length` for the periodic ("controlled HMM")
+    /// transition matrices. Pass the raw timestamp/index, not a
+    /// pre-reduced bucket - the modulo is applied wherever the bucket is
+    /// consumed. Has no effect unless `config.period_length` is `Some`.
+    pub fn update_at(&mut self, close: f64, timestamp: usize) -> RegimeConfidence {
+        self.update_optionally_at(close, Some(timestamp))
+    }
+
+    /// Update with OHLC data, attributing the observation to time bucket
+    /// `timestamp % period_length`. See [`Self::update_at`].
+    pub fn update_ohlc_at(&mut self, _high: f64, _low: f64, close: f64, timestamp: usize) -> RegimeConfidence {
+        self.update_at(close, timestamp)
+    }
+
+    fn update_optionally_at(&mut self, close: f64, timestamp: Option<usize>) -> RegimeConfidence {
         // Calculate log return
         if let Some(&prev_close) = self.prices.back() {
             let log_return = (close / prev_close).ln();
-            self.process_return(log_return);
+            self.process_return(log_return, timestamp);
         }
-        
+
         // Store price
         self.prices.push_back(close);
         if self.prices.len() > 10 {
             self.prices.pop_front();
         }
-        
+
         // Return current regime
         self.get_regime_confidence()
     }
-    
-    /// Update with OHLC data
-    pub fn update_ohlc(&mut self, _high: f64, _low: f64, close: f64) -> RegimeConfidence {
-        self.update(close)
-    }
-    
+
     /// Process a single return observation
-    fn process_return(&mut self, ret: f64) {
+    fn process_return(&mut self, ret: f64, timestamp: Option<usize>) {
         self.n_observations += 1;
-        
+
         // Store return
         self.returns_history.push_back(ret);
         if self.returns_history.len() > self.config.lookback_window {
             self.returns_history.pop_front();
         }
-        
+        self.time_buckets.push_back(timestamp);
+        if self.time_buckets.len() > self.config.lookback_window {
+            self.time_buckets.pop_front();
+        }
+
         // Forward algorithm step (filtering)
-        self.forward_step(ret);
-        
+        self.forward_step(ret, timestamp);
+
         // Update state parameters if we have enough data
         if self.n_observations > self.config.min_observations && self.config.learning_rate > 0.0 {
             self.online_parameter_update(ret);
         }
-        
+
         // Periodically re-estimate with Baum-Welch if we have enough data
-        if self.n_observations > 0 && 
+        if self.n_observations > 0 &&
            self.n_observations % (self.config.lookback_window / 2) == 0 &&
            self.returns_history.len() >= self.config.min_observations {
             self.baum_welch_update();
         }
     }
+
+    /// The transition matrix to use for a step attributed to `timestamp`:
+    /// `periodic_transition_matrices[timestamp % L]` when periodic mode is
+    /// configured, or the static `transition_matrix` otherwise (including
+    /// when the step itself carries no timestamp).
+    fn transition_for_bucket(&self, timestamp: Option<usize>) -> &Vec<Vec<f64>> {
+        match (&self.periodic_transition_matrices, timestamp) {
+            (Some(mats), Some(t)) => &mats[t % mats.len()],
+            _ => &self.transition_matrix,
+        }
+    }
     
     /// Forward algorithm step - update state probabilities given new observation
-    fn forward_step(&mut self, ret: f64) {
+    ///
+    /// Emissions are computed in log-space and shifted by the step's max
+    /// log-density before exponentiating, so a tiny-variance state with a
+    /// vanishingly small raw density doesn't underflow to an exact zero
+    /// relative to the others. The pre-normalization sum (the reciprocal of
+    /// the scaling factor `cₜ` in Rabiner's formulation) is recovered and
+    /// its log accumulated into `log_likelihood`.
+    fn forward_step(&mut self, ret: f64, timestamp: Option<usize>) {
         let n = self.config.n_states;
         let mut new_probs = vec![0.0; n];
-        
-        // Calculate emission probabilities
-        let emissions: Vec<f64> = self.states.iter()
-            .map(|s| s.pdf(ret))
+        let matrix = self.transition_for_bucket(timestamp).clone();
+
+        // Calculate emission probabilities, shifted into a safe range
+        let log_emissions: Vec<f64> = self.states.iter()
+            .map(|s| s.log_pdf(ret))
             .collect();
-        
+        let max_log_emission = log_emissions.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let emissions: Vec<f64> = log_emissions.iter()
+            .map(|le| (le - max_log_emission).exp())
+            .collect();
+
         // Forward step: P(state_j | obs) ∝ P(obs | state_j) * Σᵢ P(state_j | state_i) * P(state_i)
         for j in 0..n {
             let mut sum = 0.0;
             for i in 0..n {
-                sum += self.transition_matrix[i][j] * self.state_probs[i];
+                sum += matrix[i][j] * self.state_probs[i];
             }
             new_probs[j] = emissions[j] * sum;
         }
-        
-        // Normalize
+
+        // Normalize, undoing the max-log-emission shift to recover the true
+        // scaling factor for the log-likelihood accumulator
         let total: f64 = new_probs.iter().sum();
         if total > 1e-300 {
+            self.log_likelihood += total.ln() + max_log_emission;
             for p in &mut new_probs {
                 *p /= total;
             }
@@ -293,17 +436,29 @@ impl HMMRegimeDetector {
             // Reset to uniform if probabilities collapse
             new_probs = vec![1.0 / n as f64; n];
         }
-        
+
         self.state_probs = new_probs;
-        
+
         // Update current state and confidence
         let (max_idx, max_prob) = self.state_probs.iter()
             .enumerate()
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
             .unwrap();
-        
+
+        if self.config.track_durations {
+            if max_idx == self.current_state {
+                self.current_state_dwell += 1;
+            } else {
+                if self.current_state_dwell > 0 {
+                    self.duration_histories[self.current_state].push(self.current_state_dwell);
+                }
+                self.current_state_dwell = 1;
+            }
+        }
+
         self.current_state = max_idx;
         self.current_confidence = *max_prob;
+        self.last_time_bucket = timestamp;
     }
     
     /// Online parameter update using soft assignments
@@ -337,45 +492,102 @@ impl HMMRegimeDetector {
         
         let n = self.config.n_states;
         let t = returns.len();
-        
-        // Forward pass
+
+        // State occupancy probabilities from the full forward-backward pass
+        let gamma = self.forward_backward_gamma(&returns);
+
+        // Re-estimate emission parameters
+        for j in 0..n {
+            let mut weight_sum = 0.0;
+            let mut mean_sum = 0.0;
+            let mut var_sum = 0.0;
+            
+            for time in 0..t {
+                let w = gamma[time][j];
+                weight_sum += w;
+                mean_sum += w * returns[time];
+            }
+            
+            if weight_sum > 1e-8 {
+                let new_mean = mean_sum / weight_sum;
+                
+                for time in 0..t {
+                    let w = gamma[time][j];
+                    var_sum += w * (returns[time] - new_mean).powi(2);
+                }
+                
+                let new_var = (var_sum / weight_sum).max(1e-8);
+                
+                // Blend with existing parameters (prevents sudden jumps)
+                let blend = 0.3;
+                self.states[j].mean = (1.0 - blend) * self.states[j].mean + blend * new_mean;
+                self.states[j].variance = (1.0 - blend) * self.states[j].variance + blend * new_var;
+            }
+        }
+
+        if self.config.track_durations {
+            self.fit_duration_histograms();
+        }
+
+        if self.config.period_length.is_some() {
+            self.update_periodic_transition_matrices();
+        }
+    }
+
+    /// Re-estimate each bucket's transition matrix from only the
+    /// transitions whose target time fell in that bucket, via a
+    /// bucket-aware forward-backward pass over `returns_history` and
+    /// `time_buckets`. Observations with no timestamp (`time_buckets[t] ==
+    /// None`) don't contribute to any bucket's re-estimation, since there's
+    /// no period information to attribute them to. Blended with the
+    /// existing per-bucket matrix using the same 0.3 weight as the
+    /// emission re-estimation above, and each row renormalized afterwards.
+    fn update_periodic_transition_matrices(&mut self) {
+        let Some(period_length) = self.config.period_length else { return };
+        let returns: Vec<f64> = self.returns_history.iter().copied().collect();
+        let buckets: Vec<Option<usize>> = self.time_buckets.iter().copied().collect();
+        let n = self.config.n_states;
+        let t = returns.len();
+        if t < 2 {
+            return;
+        }
+
+        // Bucket-aware forward pass
         let mut alpha = vec![vec![0.0; n]; t];
-        
-        // Initialize
+        let (emissions0, _shift0) = self.scaled_emissions(returns[0]);
         for j in 0..n {
-            alpha[0][j] = self.initial_probs[j] * self.states[j].pdf(returns[0]);
+            alpha[0][j] = self.initial_probs[j] * emissions0[j];
         }
         self.normalize_vec(&mut alpha[0]);
-        
-        // Forward
+
         for time in 1..t {
+            let matrix = self.transition_for_bucket(buckets[time]).clone();
+            let (emissions, _shift) = self.scaled_emissions(returns[time]);
             for j in 0..n {
                 let mut sum = 0.0;
                 for i in 0..n {
-                    sum += alpha[time - 1][i] * self.transition_matrix[i][j];
+                    sum += alpha[time - 1][i] * matrix[i][j];
                 }
-                alpha[time][j] = sum * self.states[j].pdf(returns[time]);
+                alpha[time][j] = sum * emissions[j];
             }
             self.normalize_vec(&mut alpha[time]);
         }
-        
-        // Backward pass
+
+        // Bucket-aware backward pass
         let mut beta = vec![vec![1.0; n]; t];
-        
         for time in (0..t - 1).rev() {
+            let matrix = self.transition_for_bucket(buckets[time + 1]).clone();
+            let (emissions, _shift) = self.scaled_emissions(returns[time + 1]);
             for i in 0..n {
                 let mut sum = 0.0;
                 for j in 0..n {
-                    sum += self.transition_matrix[i][j] * 
-                           self.states[j].pdf(returns[time + 1]) * 
-                           beta[time + 1][j];
+                    sum += matrix[i][j] * emissions[j] * beta[time + 1][j];
                 }
                 beta[time][i] = sum;
             }
             self.normalize_vec(&mut beta[time]);
         }
-        
-        // Compute gamma (state occupancy probabilities)
+
         let mut gamma = vec![vec![0.0; n]; t];
         for time in 0..t {
             let mut sum = 0.0;
@@ -389,112 +601,664 @@ impl HMMRegimeDetector {
                 }
             }
         }
-        
-        // Re-estimate emission parameters
-        for j in 0..n {
-            let mut weight_sum = 0.0;
-            let mut mean_sum = 0.0;
-            let mut var_sum = 0.0;
-            
-            for time in 0..t {
-                let w = gamma[time][j];
-                weight_sum += w;
-                mean_sum += w * returns[time];
+
+        // Accumulate expected transition counts per bucket from xi[time][i][j]
+        let mut numerator = vec![vec![vec![0.0; n]; n]; period_length];
+        let mut denominator = vec![vec![0.0; n]; period_length];
+
+        for time in 0..t - 1 {
+            let Some(bucket) = buckets[time + 1].map(|b| b % period_length) else { continue };
+            let matrix = self.transition_for_bucket(buckets[time + 1]).clone();
+            let (emissions, _shift) = self.scaled_emissions(returns[time + 1]);
+
+            let mut xi = vec![vec![0.0; n]; n];
+            let mut total = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    let v = alpha[time][i] * matrix[i][j] * emissions[j] * beta[time + 1][j];
+                    xi[i][j] = v;
+                    total += v;
+                }
             }
-            
-            if weight_sum > 1e-8 {
-                let new_mean = mean_sum / weight_sum;
-                
-                for time in 0..t {
-                    let w = gamma[time][j];
-                    var_sum += w * (returns[time] - new_mean).powi(2);
+            if total > 1e-300 {
+                for i in 0..n {
+                    denominator[bucket][i] += gamma[time][i];
+                    for j in 0..n {
+                        numerator[bucket][i][j] += xi[i][j] / total;
+                    }
+                }
+            }
+        }
+
+        let blend = 0.3;
+        if self.periodic_transition_matrices.is_none() {
+            self.periodic_transition_matrices = Some(vec![self.transition_matrix.clone(); period_length]);
+        }
+        let Some(matrices) = &mut self.periodic_transition_matrices else { return };
+        for bucket in 0..period_length {
+            for i in 0..n {
+                if denominator[bucket][i] <= 1e-8 {
+                    continue;
+                }
+                for j in 0..n {
+                    let new_val = numerator[bucket][i][j] / denominator[bucket][i];
+                    matrices[bucket][i][j] = (1.0 - blend) * matrices[bucket][i][j] + blend * new_val;
+                }
+                let row_sum: f64 = matrices[bucket][i].iter().sum();
+                if row_sum > 1e-300 {
+                    for v in matrices[bucket][i].iter_mut() {
+                        *v /= row_sum;
+                    }
                 }
-                
-                let new_var = (var_sum / weight_sum).max(1e-8);
-                
-                // Blend with existing parameters (prevents sudden jumps)
-                let blend = 0.3;
-                self.states[j].mean = (1.0 - blend) * self.states[j].mean + blend * new_mean;
-                self.states[j].variance = (1.0 - blend) * self.states[j].variance + blend * new_var;
             }
         }
     }
-    
-    /// Helper to normalize a probability vector
-    fn normalize_vec(&self, vec: &mut [f64]) {
-        let sum: f64 = vec.iter().sum();
-        if sum > 1e-300 {
-            for v in vec.iter_mut() {
-                *v /= sum;
+
+    /// Rebuild the per-state dwell-time histograms from the decoded Viterbi
+    /// path over `returns_history`. This is less noisy than accumulating
+    /// run lengths from the online filtered state in `forward_step`, which
+    /// can flicker between states for a step or two around a regime change.
+    fn fit_duration_histograms(&mut self) {
+        let path = self.viterbi_path();
+        if path.is_empty() {
+            return;
+        }
+
+        let mut histories = vec![Vec::new(); self.config.n_states];
+        let mut run_state = path[0].0;
+        let mut run_len = 1usize;
+        for &(state, _) in &path[1..] {
+            if state == run_state {
+                run_len += 1;
+            } else {
+                histories[run_state].push(run_len);
+                run_state = state;
+                run_len = 1;
             }
         }
+        histories[run_state].push(run_len);
+
+        self.duration_histories = histories;
     }
-    
-    /// Get current regime with confidence
-    pub fn get_regime_confidence(&self) -> RegimeConfidence {
-        if self.n_observations < self.config.min_observations {
-            return RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
+
+    /// Offline Bayesian change-point re-segmentation over `returns_history`,
+    /// returning the refined `(start, end, regime)` blocks (`end` exclusive).
+    ///
+    /// Online filtering can flicker between states for a bar or two around
+    /// a real regime change on noisy crypto data. This treats the Viterbi
+    /// path's run-length boundaries as latent change points and refines
+    /// them with a bounded Gibbs-style sweep: merge/split decisions compare
+    /// each pair of adjacent segments' BIC score (an approximation of the
+    /// Bayesian marginal-likelihood ratio - exact evidence integrals would
+    /// need a conjugate prior this model doesn't keep) kept separate versus
+    /// combined, and single-point moves nudge a boundary by one observation
+    /// when that improves the two segments' combined fit. No segment is ever produced
+    /// shorter than `min_regime_length`. On convergence (or after a bounded
+    /// number of sweeps), each final segment's Gaussian is re-estimated
+    /// from its own members and blended into the nearest existing state
+    /// (same 0.3 blend weight `baum_welch_update` uses), and
+    /// `transition_matrix` is redrawn from the segment-to-segment counts.
+    pub fn resegment(&mut self, min_regime_length: usize) -> Vec<(usize, usize, MarketRegime)> {
+        let returns: Vec<f64> = self.returns_history.iter().copied().collect();
+        if returns.is_empty() {
+            return Vec::new();
         }
-        
-        let regime = self.state_to_regime(self.current_state);
-        let confidence = self.current_confidence;
-        
-        RegimeConfidence::with_metrics(
-            regime,
-            confidence,
-            self.states[self.current_state].mean * 100.0 * 252.0,  // Annualized return %
-            self.states[self.current_state].variance.sqrt() * 100.0 * 252.0_f64.sqrt(),  // Annualized vol %
-            0.0,  // No trend strength in HMM
-        )
+        let min_len = min_regime_length.max(1);
+
+        let path = self.viterbi_path();
+        let mut segments = Self::runs_from_path(&path);
+
+        const MAX_SWEEPS: usize = 5;
+        for _ in 0..MAX_SWEEPS {
+            let merged = Self::enforce_min_segment_length(&returns, &mut segments, min_len);
+            let split_merged = Self::split_merge_sweep(&returns, &mut segments);
+            let shifted = Self::shift_boundaries_sweep(&returns, &mut segments, min_len);
+            if !merged && !split_merged && !shifted {
+                break;
+            }
+        }
+
+        self.reestimate_from_segments(&returns, &segments);
+
+        segments.iter()
+            .map(|&(start, end)| {
+                let (mean, var) = Self::segment_stats(&returns, start, end);
+                (start, end, self.classify(mean, var.sqrt()))
+            })
+            .collect()
     }
-    
-    /// Map state index to MarketRegime
-    fn state_to_regime(&self, state: usize) -> MarketRegime {
-        let state_params = &self.states[state];
-        let mean = state_params.mean;
-        let vol = state_params.variance.sqrt();
-        
-        // Classify based on learned parameters
-        let is_high_vol = vol > 0.02;  // > 2% daily vol
-        let is_positive = mean > 0.0005;  // > 0.05% daily
-        let is_negative = mean < -0.0005;
-        
-        if is_high_vol {
-            MarketRegime::Volatile
-        } else if is_positive {
-            MarketRegime::Trending(TrendDirection::Bullish)
-        } else if is_negative {
-            MarketRegime::Trending(TrendDirection::Bearish)
-        } else {
-            MarketRegime::MeanReverting  // Low vol, neutral returns = ranging
+
+    /// Group a decoded path into `(start, end)` runs of consecutive
+    /// identical states, the seed segmentation for `resegment`
+    fn runs_from_path(path: &[(usize, MarketRegime)]) -> Vec<(usize, usize)> {
+        if path.is_empty() {
+            return Vec::new();
         }
+
+        let mut runs = Vec::new();
+        let mut start = 0;
+        let mut run_state = path[0].0;
+        for (i, &(state, _)) in path.iter().enumerate().skip(1) {
+            if state != run_state {
+                runs.push((start, i));
+                start = i;
+                run_state = state;
+            }
+        }
+        runs.push((start, path.len()));
+        runs
     }
-    
-    /// Get state probabilities
-    pub fn state_probabilities(&self) -> &[f64] {
-        &self.state_probs
+
+    /// Mean and (floored) variance of `returns[start..end]`
+    fn segment_stats(returns: &[f64], start: usize, end: usize) -> (f64, f64) {
+        let n = (end - start).max(1) as f64;
+        let mean = returns[start..end].iter().sum::<f64>() / n;
+        let var = returns[start..end].iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        (mean, var.max(1e-8))
     }
-    
-    /// Get state parameters (mean, variance) for inspection
-    pub fn state_parameters(&self) -> Vec<(f64, f64)> {
-        self.states.iter()
-            .map(|s| (s.mean, s.variance))
-            .collect()
+
+    /// Log-likelihood of `returns[start..end]` under its own MLE-fit Gaussian
+    fn segment_log_likelihood(returns: &[f64], start: usize, end: usize) -> f64 {
+        if end <= start {
+            return 0.0;
+        }
+        let (mean, var) = Self::segment_stats(returns, start, end);
+        returns[start..end].iter()
+            .map(|&r| {
+                let diff = r - mean;
+                -0.5 * (2.0 * std::f64::consts::PI * var).ln() - 0.5 * diff * diff / var
+            })
+            .sum()
     }
-    
-    /// Get transition matrix
-    pub fn transition_matrix(&self) -> &Vec<Vec<f64>> {
-        &self.transition_matrix
+
+    /// BIC-style score (log-likelihood penalized by `n_params * ln(n_obs) /
+    /// 2`), used as the approximate marginal-likelihood comparison between
+    /// keeping segments separate (more parameters, better fit) and merging
+    /// them (fewer parameters, worse fit)
+    fn bic_score(log_likelihood: f64, n_params: usize, n_obs: usize) -> f64 {
+        log_likelihood - 0.5 * n_params as f64 * (n_obs.max(1) as f64).ln()
     }
-    
-    /// Get current state index
-    pub fn current_state_index(&self) -> usize {
-        self.current_state
+
+    /// Repeatedly merge any segment shorter than `min_len` into whichever
+    /// neighbor yields the better combined log-likelihood, until every
+    /// segment satisfies the floor (or only one segment remains)
+    fn enforce_min_segment_length(returns: &[f64], segments: &mut Vec<(usize, usize)>, min_len: usize) -> bool {
+        let mut changed = false;
+        loop {
+            if segments.len() <= 1 {
+                break;
+            }
+            let Some(i) = segments.iter().position(|&(s, e)| e - s < min_len) else {
+                break;
+            };
+
+            let merge_with_left = match (i > 0, i + 1 < segments.len()) {
+                (true, true) => {
+                    let (ls, _) = segments[i - 1];
+                    let (rs, _) = segments[i];
+                    let (_, le) = segments[i];
+                    let (_, re) = segments[i + 1];
+                    Self::segment_log_likelihood(returns, ls, le)
+                        >= Self::segment_log_likelihood(returns, rs, re)
+                }
+                (true, false) => true,
+                (false, true) => false,
+                (false, false) => break,
+            };
+
+            if merge_with_left {
+                let (ls, _) = segments[i - 1];
+                let (_, e) = segments[i];
+                segments[i - 1] = (ls, e);
+                segments.remove(i);
+            } else {
+                let (s, _) = segments[i];
+                let (_, re) = segments[i + 1];
+                segments[i] = (s, re);
+                segments.remove(i + 1);
+            }
+            changed = true;
+        }
+        changed
     }
-    
-    /// Check if model is warmed up
-    pub fn is_ready(&self) -> bool {
-        self.n_observations >= self.config.min_observations
+
+    /// For each adjacent pair of segments, merge them when the BIC score of
+    /// treating them as one segment is at least as good as treating them
+    /// separately
+    fn split_merge_sweep(returns: &[f64], segments: &mut Vec<(usize, usize)>) -> bool {
+        let mut changed = false;
+        let mut i = 0;
+        while i + 1 < segments.len() {
+            let (s1, e1) = segments[i];
+            let (s2, e2) = segments[i + 1];
+            let n_total = e2 - s1;
+
+            let ll_separate = Self::segment_log_likelihood(returns, s1, e1)
+                + Self::segment_log_likelihood(returns, s2, e2);
+            let ll_merged = Self::segment_log_likelihood(returns, s1, e2);
+
+            let score_separate = Self::bic_score(ll_separate, 4, n_total);
+            let score_merged = Self::bic_score(ll_merged, 2, n_total);
+
+            if score_merged >= score_separate {
+                segments[i] = (s1, e2);
+                segments.remove(i + 1);
+                changed = true;
+                // Re-check the newly merged segment against its new neighbor
+            } else {
+                i += 1;
+            }
+        }
+        changed
+    }
+
+    /// Nudge each internal boundary left or right by a single observation
+    /// when that improves the combined log-likelihood of the two segments
+    /// it separates, without shrinking either below `min_len`
+    fn shift_boundaries_sweep(returns: &[f64], segments: &mut [(usize, usize)], min_len: usize) -> bool {
+        let mut changed = false;
+        for i in 0..segments.len().saturating_sub(1) {
+            let (s1, e1) = segments[i];
+            let (s2, e2) = segments[i + 1];
+            let len1 = e1 - s1;
+            let len2 = e2 - s2;
+
+            let current = Self::segment_log_likelihood(returns, s1, e1)
+                + Self::segment_log_likelihood(returns, s2, e2);
+            let mut best = current;
+            let mut best_boundary = e1;
+
+            if len1 > min_len {
+                let candidate_boundary = e1 - 1;
+                let candidate = Self::segment_log_likelihood(returns, s1, candidate_boundary)
+                    + Self::segment_log_likelihood(returns, candidate_boundary, e2);
+                if candidate > best {
+                    best = candidate;
+                    best_boundary = candidate_boundary;
+                }
+            }
+            if len2 > min_len {
+                let candidate_boundary = e1 + 1;
+                let candidate = Self::segment_log_likelihood(returns, s1, candidate_boundary)
+                    + Self::segment_log_likelihood(returns, candidate_boundary, e2);
+                if candidate > best {
+                    best_boundary = candidate_boundary;
+                }
+            }
+
+            if best_boundary != e1 {
+                segments[i] = (s1, best_boundary);
+                segments[i + 1] = (best_boundary, e2);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Re-estimate emission and transition parameters from the final
+    /// segmentation: each segment is assigned to whichever existing state's
+    /// Gaussian is closest in (mean, variance), that state's parameters are
+    /// refit from its assigned segments' members (blended 0.3, same weight
+    /// `baum_welch_update` uses), and `transition_matrix` is redrawn from
+    /// segment-to-segment counts - within-segment dwell counts toward the
+    /// diagonal, one count per segment boundary toward the off-diagonal.
+    fn reestimate_from_segments(&mut self, returns: &[f64], segments: &[(usize, usize)]) {
+        if segments.is_empty() {
+            return;
+        }
+        let n = self.config.n_states;
+
+        let assigned: Vec<usize> = segments.iter()
+            .map(|&(start, end)| {
+                let (seg_mean, seg_var) = Self::segment_stats(returns, start, end);
+                (0..n)
+                    .min_by(|&a, &b| {
+                        let da = (self.states[a].mean - seg_mean).powi(2)
+                            + (self.states[a].variance - seg_var).powi(2);
+                        let db = (self.states[b].mean - seg_mean).powi(2)
+                            + (self.states[b].variance - seg_var).powi(2);
+                        da.partial_cmp(&db).unwrap()
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        let blend = 0.3;
+        for state in 0..n {
+            let members: Vec<usize> = (0..segments.len()).filter(|&i| assigned[i] == state).collect();
+            if members.is_empty() {
+                continue;
+            }
+
+            let mut weight_sum = 0.0;
+            let mut mean_sum = 0.0;
+            for &i in &members {
+                let (start, end) = segments[i];
+                for &r in &returns[start..end] {
+                    mean_sum += r;
+                    weight_sum += 1.0;
+                }
+            }
+            if weight_sum < 1.0 {
+                continue;
+            }
+            let new_mean = mean_sum / weight_sum;
+
+            let mut var_sum = 0.0;
+            for &i in &members {
+                let (start, end) = segments[i];
+                for &r in &returns[start..end] {
+                    var_sum += (r - new_mean).powi(2);
+                }
+            }
+            let new_var = (var_sum / weight_sum).max(1e-8);
+
+            self.states[state].mean = (1.0 - blend) * self.states[state].mean + blend * new_mean;
+            self.states[state].variance = (1.0 - blend) * self.states[state].variance + blend * new_var;
+        }
+
+        let mut counts = vec![vec![0.0; n]; n];
+        for (i, &(start, end)) in segments.iter().enumerate() {
+            let state = assigned[i];
+            counts[state][state] += (end - start).saturating_sub(1) as f64;
+            if i + 1 < segments.len() {
+                counts[state][assigned[i + 1]] += 1.0;
+            }
+        }
+
+        let smoothing = self.config.transition_smoothing;
+        for i in 0..n {
+            let row_total: f64 = counts[i].iter().sum();
+            if row_total <= 0.0 {
+                continue;
+            }
+            for j in 0..n {
+                let empirical = counts[i][j] / row_total;
+                let prior = if i == j { 0.9 } else { 0.1 / (n - 1).max(1) as f64 };
+                self.transition_matrix[i][j] = (1.0 - smoothing) * empirical + smoothing * prior;
+            }
+        }
+    }
+
+    /// Decode the single most probable state sequence over `returns_history`
+    ///
+    /// `forward_step` only tracks the marginal filtered state at each time
+    /// step, which is not the globally optimal path. This runs the standard
+    /// Viterbi recurrence in log-space (so long windows don't underflow),
+    /// floors emission probabilities at a small epsilon so a zero-density
+    /// observation doesn't produce a `-inf` that poisons the rest of the
+    /// path, and maps each decoded state to `MarketRegime`.
+    pub fn viterbi_path(&self) -> Vec<(usize, MarketRegime)> {
+        const EPSILON: f64 = 1e-300;
+
+        let returns: Vec<f64> = self.returns_history.iter().copied().collect();
+        let t = returns.len();
+        let n = self.config.n_states;
+        if t == 0 {
+            return Vec::new();
+        }
+
+        let ln_emission = |state: usize, ret: f64| self.states[state].log_pdf(ret).max(EPSILON.ln());
+
+        // delta[t][j] = highest log-probability of any path ending in state j at time t
+        let mut delta = vec![vec![0.0; n]; t];
+        // psi[t][j] = the state at t-1 that produced delta[t][j]
+        let mut psi = vec![vec![0usize; n]; t];
+
+        for j in 0..n {
+            delta[0][j] = self.initial_probs[j].max(EPSILON).ln() + ln_emission(j, returns[0]);
+        }
+
+        for time in 1..t {
+            for j in 0..n {
+                let (best_prev, best_score) = (0..n)
+                    .map(|i| {
+                        (
+                            i,
+                            delta[time - 1][i] + self.transition_matrix[i][j].max(EPSILON).ln(),
+                        )
+                    })
+                    .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                    .unwrap();
+                psi[time][j] = best_prev;
+                delta[time][j] = best_score + ln_emission(j, returns[time]);
+            }
+        }
+
+        let mut state = delta[t - 1]
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i)
+            .unwrap();
+
+        let mut path = vec![0usize; t];
+        path[t - 1] = state;
+        for time in (0..t - 1).rev() {
+            state = psi[time + 1][state];
+            path[time] = state;
+        }
+
+        path.into_iter()
+            .map(|s| (s, self.state_to_regime(s)))
+            .collect()
+    }
+
+    /// Helper to normalize a probability vector
+    fn normalize_vec(&self, vec: &mut [f64]) {
+        let sum: f64 = vec.iter().sum();
+        if sum > 1e-300 {
+            for v in vec.iter_mut() {
+                *v /= sum;
+            }
+        }
+    }
+
+    /// Scaled emission densities for `ret`, shifted by the max log-density
+    /// across states so a tiny-variance state's vanishing raw density
+    /// doesn't underflow to an exact zero relative to the others. Returns
+    /// the scaled emissions alongside the shift, so callers that need the
+    /// true (unscaled) total can add the shift back in log-space.
+    fn scaled_emissions(&self, ret: f64) -> (Vec<f64>, f64) {
+        let log_emissions: Vec<f64> = self.states.iter().map(|s| s.log_pdf(ret)).collect();
+        let max_log_emission = log_emissions.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let emissions = log_emissions.iter().map(|le| (le - max_log_emission).exp()).collect();
+        (emissions, max_log_emission)
+    }
+
+    /// Forward-backward pass over `returns`, returning the full gamma matrix
+    /// (`gamma[t][j] = P(state_t = j | all observations)`), shared by
+    /// `baum_welch_update` and `smoothed_state_probabilities`. As a side
+    /// effect, refreshes `log_likelihood` with the batch sequence
+    /// log-likelihood accumulated from the forward pass's scaling factors.
+    fn forward_backward_gamma(&mut self, returns: &[f64]) -> Vec<Vec<f64>> {
+        let n = self.config.n_states;
+        let t = returns.len();
+        if t == 0 {
+            return Vec::new();
+        }
+
+        // Forward pass
+        let mut alpha = vec![vec![0.0; n]; t];
+        let mut log_likelihood = 0.0;
+
+        let (emissions0, shift0) = self.scaled_emissions(returns[0]);
+        for j in 0..n {
+            alpha[0][j] = self.initial_probs[j] * emissions0[j];
+        }
+        let total0: f64 = alpha[0].iter().sum();
+        if total0 > 1e-300 {
+            log_likelihood += total0.ln() + shift0;
+        }
+        self.normalize_vec(&mut alpha[0]);
+
+        for time in 1..t {
+            let (emissions, shift) = self.scaled_emissions(returns[time]);
+            for j in 0..n {
+                let mut sum = 0.0;
+                for i in 0..n {
+                    sum += alpha[time - 1][i] * self.transition_matrix[i][j];
+                }
+                alpha[time][j] = sum * emissions[j];
+            }
+            let total: f64 = alpha[time].iter().sum();
+            if total > 1e-300 {
+                log_likelihood += total.ln() + shift;
+            }
+            self.normalize_vec(&mut alpha[time]);
+        }
+        self.log_likelihood = log_likelihood;
+
+        // Backward pass
+        let mut beta = vec![vec![1.0; n]; t];
+        for time in (0..t - 1).rev() {
+            let (emissions, _shift) = self.scaled_emissions(returns[time + 1]);
+            for i in 0..n {
+                let mut sum = 0.0;
+                for j in 0..n {
+                    sum += self.transition_matrix[i][j] * emissions[j] * beta[time + 1][j];
+                }
+                beta[time][i] = sum;
+            }
+            self.normalize_vec(&mut beta[time]);
+        }
+
+        // Gamma: state occupancy probabilities
+        let mut gamma = vec![vec![0.0; n]; t];
+        for time in 0..t {
+            let mut sum = 0.0;
+            for j in 0..n {
+                gamma[time][j] = alpha[time][j] * beta[time][j];
+                sum += gamma[time][j];
+            }
+            if sum > 1e-300 {
+                for j in 0..n {
+                    gamma[time][j] /= sum;
+                }
+            }
+        }
+
+        gamma
+    }
+
+    /// Smoothed posterior `P(state_t | x_1..x_T)` for every `t` in
+    /// `returns_history`, via the forward-backward algorithm ("local
+    /// decoding"). Unlike the filtered `state_probabilities()` — which only
+    /// conditions on observations up to `t` — this uses the full history,
+    /// giving a sharper view of past regime transitions for plotting or
+    /// labeling.
+    pub fn smoothed_state_probabilities(&mut self) -> Vec<Vec<f64>> {
+        let returns: Vec<f64> = self.returns_history.iter().copied().collect();
+        self.forward_backward_gamma(&returns)
+    }
+
+    /// Smoothed regime classification at time index `t` within
+    /// `returns_history`, or `None` if `t` is out of range
+    pub fn smoothed_regime_at(&mut self, t: usize) -> Option<MarketRegime> {
+        let gamma = self.smoothed_state_probabilities();
+        let probs = gamma.get(t)?;
+        let (state, _) = probs
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())?;
+        Some(self.state_to_regime(state))
+    }
+    
+    /// Get current regime with confidence
+    pub fn get_regime_confidence(&self) -> RegimeConfidence {
+        if self.n_observations < self.config.min_observations {
+            return RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
+        }
+        
+        let regime = self.state_to_regime(self.current_state);
+        let confidence = self.current_confidence;
+
+        RegimeConfidence::with_metrics(
+            regime,
+            confidence,
+            self.states[self.current_state].mean * 100.0 * 252.0,  // Annualized return %
+            self.states[self.current_state].variance.sqrt() * 100.0 * 252.0_f64.sqrt(),  // Annualized vol %
+            0.0,  // No trend strength in HMM
+        ).with_posterior(self.state_probs.clone())
+    }
+
+    /// One-step-ahead regime forecast the threshold `RegimeDetector` can't
+    /// produce: the most likely `MarketRegime` at the next bar (and its
+    /// probability), projected from the current filtered posterior through
+    /// the transition matrix rather than read off the current bar's
+    /// classification. See [`Self::predict_next_state`] for the raw state
+    /// index this is built on.
+    pub fn forecast_next_regime(&self) -> (MarketRegime, f64) {
+        let (state, prob) = self.predict_next_state();
+        (self.state_to_regime(state), prob)
+    }
+    
+    /// Map state index to MarketRegime
+    fn state_to_regime(&self, state: usize) -> MarketRegime {
+        let state_params = &self.states[state];
+        self.classify(state_params.mean, state_params.variance.sqrt())
+    }
+
+    /// Classify a mean/volatility pair into a MarketRegime. Shared by
+    /// `state_to_regime` (for one of the model's fitted states) and
+    /// `resegment` (for an offline-refit segment's own mean/vol, which
+    /// isn't tied to any single state's Gaussian).
+    fn classify(&self, mean: f64, vol: f64) -> MarketRegime {
+        // Classify based on learned parameters
+        let is_high_vol = vol > 0.02;  // > 2% daily vol
+        let is_positive = mean > 0.0005;  // > 0.05% daily
+        let is_negative = mean < -0.0005;
+
+        if is_high_vol {
+            MarketRegime::Volatile
+        } else if is_positive {
+            MarketRegime::Trending(TrendDirection::Bullish)
+        } else if is_negative {
+            MarketRegime::Trending(TrendDirection::Bearish)
+        } else {
+            MarketRegime::MeanReverting  // Low vol, neutral returns = ranging
+        }
+    }
+    
+    /// Get state probabilities
+    pub fn state_probabilities(&self) -> &[f64] {
+        &self.state_probs
+    }
+    
+    /// Get state parameters (mean, variance) for inspection
+    pub fn state_parameters(&self) -> Vec<(f64, f64)> {
+        self.states.iter()
+            .map(|s| (s.mean, s.variance))
+            .collect()
+    }
+    
+    /// Get transition matrix
+    pub fn transition_matrix(&self) -> &Vec<Vec<f64>> {
+        &self.transition_matrix
+    }
+
+    /// Get the per-bucket transition matrices for periodic mode, or `None`
+    /// when `config.period_length` is `None`
+    pub fn periodic_transition_matrices(&self) -> Option<&Vec<Vec<Vec<f64>>>> {
+        self.periodic_transition_matrices.as_ref()
+    }
+
+    /// Get current state index
+    pub fn current_state_index(&self) -> usize {
+        self.current_state
+    }
+    
+    /// Check if model is warmed up
+    pub fn is_ready(&self) -> bool {
+        self.n_observations >= self.config.min_observations
+    }
+
+    /// Accumulated sequence log-likelihood, `-Σₜ ln(cₜ)` over the scaling
+    /// factors used by `forward_step`/`forward_backward_gamma`. Useful for
+    /// comparing fitted models (e.g. choosing `n_states`) - not used in any
+    /// routing decision.
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
     }
     
     /// Get expected regime duration (from transition matrix)
@@ -506,14 +1270,60 @@ impl HMMRegimeDetector {
             0.0
         }
     }
+
+    /// Expected remaining duration of the current regime as `(point, lower,
+    /// upper)`, i.e. `E[D - d | D > d]` where `d` is the elapsed dwell
+    /// length of `current_state` and `D` is its fitted duration
+    /// distribution (`duration_histories`, requires
+    /// `config.track_durations`). The bounds are the 10th/90th percentiles
+    /// of the same conditional distribution.
+    ///
+    /// Falls back to the geometric dwell time implied by the transition
+    /// diagonal when too few samples have been observed for this state,
+    /// since the geometric distribution is memoryless - its remaining-life
+    /// estimate doesn't depend on `d`.
+    pub fn residual_regime_life(&self) -> (f64, f64, f64) {
+        let state = self.current_state;
+        let d = self.current_state_dwell as f64;
+
+        let mut conditional: Vec<f64> = self.duration_histories[state]
+            .iter()
+            .filter(|&&dur| dur as f64 > d)
+            .map(|&dur| dur as f64 - d)
+            .collect();
+
+        const MIN_SAMPLES: usize = 5;
+        if conditional.len() < MIN_SAMPLES {
+            let geometric_remaining = self.expected_regime_duration(state);
+            return (geometric_remaining, geometric_remaining * 0.5, geometric_remaining * 1.5);
+        }
+
+        conditional.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let point = conditional.iter().sum::<f64>() / conditional.len() as f64;
+        let lower = Self::percentile(&conditional, 0.1);
+        let upper = Self::percentile(&conditional, 0.9);
+        (point, lower, upper)
+    }
+
+    /// Nearest-rank percentile of an already-sorted slice
+    fn percentile(sorted: &[f64], q: f64) -> f64 {
+        if sorted.is_empty() {
+            return 0.0;
+        }
+        let idx = (((sorted.len() - 1) as f64) * q).round() as usize;
+        sorted[idx.min(sorted.len() - 1)]
+    }
     
-    /// Predict most likely next state
+    /// Predict most likely next state. In periodic mode this projects
+    /// forward using the matrix for `last_time_bucket` - the bucket of the
+    /// most recent observation - rather than the static matrix.
     pub fn predict_next_state(&self) -> (usize, f64) {
+        let matrix = self.transition_for_bucket(self.last_time_bucket);
         let mut next_probs = vec![0.0; self.config.n_states];
-        
+
         for j in 0..self.config.n_states {
             for i in 0..self.config.n_states {
-                next_probs[j] += self.transition_matrix[i][j] * self.state_probs[i];
+                next_probs[j] += matrix[i][j] * self.state_probs[i];
             }
         }
         
@@ -524,12 +1334,131 @@ impl HMMRegimeDetector {
         
         (max_idx, *max_prob)
     }
+
+    /// Draw a synthetic sequence of `(state, return)` pairs from the fitted
+    /// model: starts from `state_probs` once any observation has been
+    /// processed (or `initial_probs` before that), then alternates sampling the next
+    /// state from the current state's row of `transition_matrix` and a
+    /// return from that state's Gaussian (`mean + sqrt(variance) * z`).
+    /// Useful for Monte-Carlo stress-testing a strategy, or for sanity
+    /// checking that the fitted parameters reproduce realistic
+    /// bull/bear/volatile return statistics before trusting the detector
+    /// live. Ignores periodic transition matrices - it describes the
+    /// model's steady-state dynamics, not a specific point in time.
+    pub fn sample(&self, n: usize, rng: &mut impl Rng) -> Vec<(usize, f64)> {
+        let starting_probs = if self.n_observations > 0 { &self.state_probs } else { &self.initial_probs };
+        let mut state = Self::sample_categorical(starting_probs, rng);
+
+        let mut draws = Vec::with_capacity(n);
+        for _ in 0..n {
+            let gaussian = &self.states[state];
+            let z = Self::standard_normal(rng);
+            let ret = gaussian.mean + gaussian.variance.sqrt() * z;
+            draws.push((state, ret));
+            state = Self::sample_categorical(&self.transition_matrix[state], rng);
+        }
+        draws
+    }
+
+    /// Monte-Carlo forecast of future regime occupancy, mirroring
+    /// `sample`'s path-generation but reporting occupancy statistics
+    /// instead of raw draws. Returns, for each of `horizon` future bars,
+    /// the fraction of `paths` simulations occupying each state at that
+    /// bar - a forward probability distribution rather than
+    /// `expected_regime_duration`'s single scalar.
+    pub fn forecast_regime_distribution(&self, horizon: usize, paths: usize, rng: &mut impl Rng) -> RegimeForecast {
+        let n = self.config.n_states;
+        if horizon == 0 || paths == 0 || n == 0 {
+            return RegimeForecast {
+                step_probabilities: Vec::new(),
+                prob_still_current_regime: 0.0,
+                expected_bars_to_exit: 0.0,
+            };
+        }
+
+        let current = self.current_state;
+        let mut counts = vec![vec![0usize; n]; horizon];
+        let mut still_current_at_horizon = 0usize;
+        let mut exit_bars_sum = 0usize;
+
+        for _ in 0..paths {
+            let mut state = Self::sample_categorical(&self.state_probs, rng);
+            let mut exited = false;
+            for (step, step_counts) in counts.iter_mut().enumerate() {
+                state = Self::sample_categorical(&self.transition_matrix[state], rng);
+                step_counts[state] += 1;
+                if !exited && state != current {
+                    exit_bars_sum += step + 1;
+                    exited = true;
+                }
+            }
+            if !exited {
+                // Never left the current regime within the horizon - treat
+                // the exit as right-censored at the horizon itself.
+                exit_bars_sum += horizon;
+            }
+            if state == current {
+                still_current_at_horizon += 1;
+            }
+        }
+
+        let step_probabilities = counts.into_iter()
+            .map(|step_counts| step_counts.into_iter().map(|c| c as f64 / paths as f64).collect())
+            .collect();
+
+        RegimeForecast {
+            step_probabilities,
+            prob_still_current_regime: still_current_at_horizon as f64 / paths as f64,
+            expected_bars_to_exit: exit_bars_sum as f64 / paths as f64,
+        }
+    }
+
+    /// Sample a synthetic price path starting at `start_price`, by
+    /// exponentiating the cumulative log-returns from [`Self::sample`].
+    /// Returns `n + 1` prices (the starting price followed by `n` draws).
+    pub fn sample_price_path(&self, start_price: f64, n: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let draws = self.sample(n, rng);
+        let mut path = Vec::with_capacity(n + 1);
+        let mut price = start_price;
+        path.push(price);
+        for (_, log_return) in draws {
+            price *= log_return.exp();
+            path.push(price);
+        }
+        path
+    }
+
+    /// Sample an index from a discrete distribution given as raw (not
+    /// necessarily normalized) weights, via inverse-CDF over a draw scaled
+    /// to the weights' total. Falls back to the last index if rounding
+    /// leaves the draw just short of the total.
+    fn sample_categorical(weights: &[f64], rng: &mut impl Rng) -> usize {
+        let total: f64 = weights.iter().sum();
+        let draw: f64 = rng.gen::<f64>() * total;
+
+        let mut cumulative = 0.0;
+        for (i, w) in weights.iter().enumerate() {
+            cumulative += w;
+            if draw < cumulative {
+                return i;
+            }
+        }
+        weights.len() - 1
+    }
+
+    /// Standard normal draw via the Box-Muller transform
+    fn standard_normal(rng: &mut impl Rng) -> f64 {
+        let u1: f64 = rng.gen::<f64>().max(f64::EPSILON);
+        let u2: f64 = rng.gen::<f64>();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+    use rand::SeedableRng;
+
     #[test]
     fn test_hmm_initialization() {
         let hmm = HMMRegimeDetector::default_config();
@@ -608,4 +1537,381 @@ mod tests {
         let sum: f64 = probs.iter().sum();
         assert!((sum - 1.0).abs() < 0.001, "Probabilities should sum to 1");
     }
+
+    #[test]
+    fn test_viterbi_path_length_matches_history() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..60 {
+            price *= 1.002;
+            hmm.update(price);
+        }
+
+        let path = hmm.viterbi_path();
+        assert_eq!(path.len(), hmm.returns_history.len());
+        for (state, _) in &path {
+            assert!(*state < hmm.config.n_states);
+        }
+    }
+
+    #[test]
+    fn test_viterbi_path_empty_before_any_observations() {
+        let hmm = HMMRegimeDetector::default_config();
+        assert!(hmm.viterbi_path().is_empty());
+    }
+
+    #[test]
+    fn test_log_likelihood_accumulates_and_stays_finite() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 3,
+            lookback_window: 300,
+            ..Default::default()
+        });
+
+        // A long, low-variance run is exactly the regime that used to
+        // collapse the raw-probability recursion toward the 1e-300 floor.
+        let mut price = 100.0;
+        for _ in 0..300 {
+            price *= 1.0001;
+            hmm.update(price);
+        }
+
+        let ll = hmm.log_likelihood();
+        assert!(ll.is_finite(), "log-likelihood should not degrade to -inf: {ll}");
+    }
+
+    #[test]
+    fn test_residual_regime_life_falls_back_to_geometric_without_samples() {
+        let hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 2,
+            track_durations: true,
+            ..Default::default()
+        });
+
+        let (point, lower, upper) = hmm.residual_regime_life();
+        assert_eq!(point, hmm.expected_regime_duration(0));
+        assert!(lower <= point && point <= upper);
+    }
+
+    #[test]
+    fn test_duration_histograms_populate_when_tracking_enabled() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            track_durations: true,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..120 {
+            price *= 1.002;
+            hmm.update(price);
+        }
+
+        let total_samples: usize = hmm.duration_histories.iter().map(|h| h.len()).sum();
+        assert!(total_samples > 0 || hmm.current_state_dwell > 0);
+    }
+
+    #[test]
+    fn test_smoothed_probabilities_sum_to_one_at_every_step() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..60 {
+            price *= 1.002;
+            hmm.update(price);
+        }
+
+        let gamma = hmm.smoothed_state_probabilities();
+        assert_eq!(gamma.len(), hmm.returns_history.len());
+        for probs in &gamma {
+            let sum: f64 = probs.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_smoothed_regime_at_matches_gamma_argmax() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..60 {
+            price *= 1.002;
+            hmm.update(price);
+        }
+
+        let gamma = hmm.smoothed_state_probabilities();
+        let last = gamma.len() - 1;
+        let (expected_state, _) = gamma[last]
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            hmm.smoothed_regime_at(last),
+            Some(hmm.state_to_regime(expected_state))
+        );
+        assert_eq!(hmm.smoothed_regime_at(gamma.len()), None);
+    }
+
+    #[test]
+    fn test_periodic_transition_matrices_absent_without_period_length() {
+        let hmm = HMMRegimeDetector::default_config();
+        assert!(hmm.periodic_transition_matrices().is_none());
+    }
+
+    #[test]
+    fn test_periodic_transition_matrices_seeded_with_period_length() {
+        let hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 2,
+            period_length: Some(24),
+            ..Default::default()
+        });
+
+        let matrices = hmm.periodic_transition_matrices().unwrap();
+        assert_eq!(matrices.len(), 24);
+        for matrix in matrices {
+            assert_eq!(matrix, hmm.transition_matrix());
+        }
+    }
+
+    #[test]
+    fn test_update_at_tracks_last_time_bucket() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 10,
+            n_states: 2,
+            period_length: Some(7),
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for hour in 0..20 {
+            price *= 1.001;
+            hmm.update_at(price, hour);
+        }
+
+        // predict_next_state should not panic when selecting a bucketed
+        // matrix after a run of timestamped updates
+        let (state, prob) = hmm.predict_next_state();
+        assert!(state < hmm.config.n_states);
+        assert!(prob >= 0.0 && prob <= 1.0);
+    }
+
+    #[test]
+    fn test_baum_welch_diversifies_periodic_matrices_with_bucket_structure() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            lookback_window: 80,
+            period_length: Some(2),
+            ..Default::default()
+        });
+
+        // Bucket 0 stays put; bucket 1 alternates hard, so the fitted
+        // per-bucket matrices should end up visibly different.
+        let mut price = 100.0;
+        for step in 0..80 {
+            let change = if step % 2 == 1 { 1.05 } else { 1.0005 };
+            price *= change;
+            hmm.update_at(price, step);
+        }
+
+        let matrices = hmm.periodic_transition_matrices().unwrap();
+        assert_ne!(matrices[0], matrices[1]);
+    }
+
+    #[test]
+    fn test_sample_returns_n_valid_state_return_pairs() {
+        let hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 3,
+            ..Default::default()
+        });
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let draws = hmm.sample(50, &mut rng);
+
+        assert_eq!(draws.len(), 50);
+        for (state, ret) in &draws {
+            assert!(*state < hmm.config.n_states);
+            assert!(ret.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_sample_price_path_starts_at_start_price_and_has_n_plus_one_points() {
+        let hmm = HMMRegimeDetector::default_config();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        let path = hmm.sample_price_path(100.0, 20, &mut rng);
+
+        assert_eq!(path.len(), 21);
+        assert_eq!(path[0], 100.0);
+        assert!(path.iter().all(|p| p.is_finite() && *p > 0.0));
+    }
+
+    #[test]
+    fn test_sample_is_reproducible_for_the_same_seed() {
+        let hmm = HMMRegimeDetector::default_config();
+
+        let mut rng_a = rand::rngs::StdRng::seed_from_u64(123);
+        let mut rng_b = rand::rngs::StdRng::seed_from_u64(123);
+
+        let draws_a = hmm.sample(30, &mut rng_a);
+        let draws_b = hmm.sample(30, &mut rng_b);
+
+        assert_eq!(draws_a, draws_b);
+    }
+
+    #[test]
+    fn test_forecast_regime_distribution_step_probabilities_sum_to_one() {
+        let hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 3,
+            ..Default::default()
+        });
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let forecast = hmm.forecast_regime_distribution(10, 2000, &mut rng);
+
+        assert_eq!(forecast.step_probabilities.len(), 10);
+        for step_probs in &forecast.step_probabilities {
+            assert_eq!(step_probs.len(), 3);
+            let sum: f64 = step_probs.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-9);
+        }
+        assert!(forecast.prob_still_current_regime >= 0.0 && forecast.prob_still_current_regime <= 1.0);
+        assert!(forecast.expected_bars_to_exit >= 0.0 && forecast.expected_bars_to_exit <= 10.0);
+    }
+
+    #[test]
+    fn test_forecast_regime_distribution_persistent_state_rarely_exits() {
+        // A near-absorbing transition matrix: state 0 stays put 99.9% of
+        // the time, so most paths should still be in the current regime
+        // at the end of a short horizon.
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 2,
+            ..Default::default()
+        });
+        hmm.transition_matrix = vec![vec![0.999, 0.001], vec![0.1, 0.9]];
+        hmm.state_probs = vec![1.0, 0.0];
+        hmm.current_state = 0;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(99);
+        let forecast = hmm.forecast_regime_distribution(5, 1000, &mut rng);
+
+        assert!(forecast.prob_still_current_regime > 0.9);
+        assert!(forecast.step_probabilities[0][0] > 0.9);
+    }
+
+    #[test]
+    fn test_forecast_regime_distribution_empty_for_zero_horizon_or_paths() {
+        let hmm = HMMRegimeDetector::default_config();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+
+        assert!(hmm.forecast_regime_distribution(0, 100, &mut rng).step_probabilities.is_empty());
+        assert!(hmm.forecast_regime_distribution(10, 0, &mut rng).step_probabilities.is_empty());
+    }
+
+    #[test]
+    fn test_resegment_covers_the_full_history_with_no_gaps() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 2,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for i in 0..100 {
+            price *= if i < 50 { 1.002 } else { 0.998 };
+            hmm.update(price);
+        }
+
+        let segments = hmm.resegment(5);
+        assert!(!segments.is_empty());
+        assert_eq!(segments[0].0, 0);
+        assert_eq!(segments.last().unwrap().1, hmm.returns_history.len());
+        for pair in segments.windows(2) {
+            assert_eq!(pair[0].1, pair[1].0, "segments should tile the history with no gaps");
+        }
+    }
+
+    #[test]
+    fn test_resegment_respects_min_regime_length() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 3,
+            ..Default::default()
+        });
+
+        // Choppy series to encourage lots of short Viterbi runs
+        let mut price = 100.0;
+        for i in 0..120 {
+            let change = if i % 3 == 0 { 1.02 } else { 0.99 };
+            price *= change;
+            hmm.update(price);
+        }
+
+        let min_len = 8;
+        let segments = hmm.resegment(min_len);
+        for &(start, end, _) in &segments {
+            assert!(end - start >= min_len, "segment [{start}, {end}) is shorter than min_regime_length");
+        }
+    }
+
+    #[test]
+    fn test_resegment_on_empty_history_returns_empty() {
+        let mut hmm = HMMRegimeDetector::default_config();
+        assert!(hmm.resegment(5).is_empty());
+    }
+
+    #[test]
+    fn test_regime_confidence_exposes_full_posterior() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            n_states: 3,
+            ..Default::default()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..60 {
+            price *= 1.002;
+            hmm.update(price);
+        }
+
+        let regime = hmm.get_regime_confidence();
+        assert_eq!(regime.state_posterior.len(), 3);
+        let sum: f64 = regime.state_posterior.iter().sum();
+        assert!((sum - 1.0).abs() < 0.001, "posterior should sum to 1");
+        assert_eq!(regime.confidence, regime.state_posterior[hmm.current_state_index()]);
+    }
+
+    #[test]
+    fn test_forecast_next_regime_tracks_predict_next_state() {
+        let mut hmm = HMMRegimeDetector::new(HMMConfig {
+            n_states: 2,
+            ..Default::default()
+        });
+        hmm.transition_matrix = vec![vec![0.999, 0.001], vec![0.1, 0.9]];
+        hmm.state_probs = vec![1.0, 0.0];
+        hmm.current_state = 0;
+
+        let (state, prob) = hmm.predict_next_state();
+        let (regime, forecast_prob) = hmm.forecast_next_regime();
+        assert_eq!(forecast_prob, prob);
+        assert_eq!(regime, hmm.state_to_regime(state));
+    }
 }
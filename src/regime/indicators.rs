@@ -4,6 +4,19 @@
 
 use std::collections::VecDeque;
 
+/// Common surface for the stateful streaming calculators below, so the
+/// regime engine can hold heterogeneous indicators in one collection and
+/// clear accumulated state back to construction defaults after a data gap
+/// or symbol switch, without reallocating.
+pub trait Indicator {
+    /// The value this indicator reports once warmed up
+    type Output;
+
+    fn is_ready(&self) -> bool;
+    fn value(&self) -> Self::Output;
+    fn reset(&mut self);
+}
+
 /// Exponential Moving Average calculator
 #[derive(Debug, Clone)]
 pub struct EMA {
@@ -63,6 +76,24 @@ impl EMA {
     }
 }
 
+impl Indicator for EMA {
+    type Output = Option<f64>;
+
+    fn is_ready(&self) -> bool {
+        self.initialized
+    }
+
+    fn value(&self) -> Self::Output {
+        self.value()
+    }
+
+    fn reset(&mut self) {
+        self.current_value = None;
+        self.initialized = false;
+        self.warmup_count = 0;
+    }
+}
+
 /// Average True Range (ATR) calculator
 #[derive(Debug, Clone)]
 pub struct ATR {
@@ -128,6 +159,24 @@ impl ATR {
     }
 }
 
+impl Indicator for ATR {
+    type Output = Option<f64>;
+
+    fn is_ready(&self) -> bool {
+        self.current_atr.is_some()
+    }
+
+    fn value(&self) -> Self::Output {
+        self.current_atr
+    }
+
+    fn reset(&mut self) {
+        self.values.clear();
+        self.prev_close = None;
+        self.current_atr = None;
+    }
+}
+
 /// Average Directional Index (ADX) calculator
 /// Measures trend strength (not direction)
 #[derive(Debug, Clone)]
@@ -265,6 +314,30 @@ impl ADX {
     }
 }
 
+impl Indicator for ADX {
+    type Output = Option<f64>;
+
+    fn is_ready(&self) -> bool {
+        self.current_adx.is_some()
+    }
+
+    fn value(&self) -> Self::Output {
+        self.current_adx
+    }
+
+    fn reset(&mut self) {
+        self.atr.reset();
+        self.plus_dm_ema.reset();
+        self.minus_dm_ema.reset();
+        self.dx_values.clear();
+        self.prev_high = None;
+        self.prev_low = None;
+        self.current_adx = None;
+        self.plus_di = None;
+        self.minus_di = None;
+    }
+}
+
 /// Bollinger Bands calculator
 #[derive(Debug, Clone)]
 pub struct BollingerBands {
@@ -273,6 +346,7 @@ pub struct BollingerBands {
     prices: VecDeque<f64>,
     width_history: VecDeque<f64>,
     width_history_size: usize,
+    current: Option<BollingerBandsValues>,
 }
 
 impl BollingerBands {
@@ -283,6 +357,7 @@ impl BollingerBands {
             prices: VecDeque::with_capacity(period),
             width_history: VecDeque::with_capacity(100),
             width_history_size: 100, // Keep 100 periods for percentile calc
+            current: None,
         }
     }
 
@@ -326,7 +401,7 @@ impl BollingerBands {
             0.5
         };
 
-        Some(BollingerBandsValues {
+        let values = BollingerBandsValues {
             upper,
             middle: sma,
             lower,
@@ -334,7 +409,9 @@ impl BollingerBands {
             width_percentile,
             percent_b,
             std_dev,
-        })
+        };
+        self.current = Some(values);
+        Some(values)
     }
 
     fn calculate_width_percentile(&self, current_width: f64) -> f64 {
@@ -354,10 +431,32 @@ impl BollingerBands {
     pub fn is_ready(&self) -> bool {
         self.prices.len() >= self.period
     }
+
+    pub fn value(&self) -> Option<BollingerBandsValues> {
+        self.current
+    }
+}
+
+impl Indicator for BollingerBands {
+    type Output = Option<BollingerBandsValues>;
+
+    fn is_ready(&self) -> bool {
+        self.prices.len() >= self.period
+    }
+
+    fn value(&self) -> Self::Output {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+        self.width_history.clear();
+        self.current = None;
+    }
 }
 
 /// Bollinger Bands output values
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BollingerBandsValues {
     pub upper: f64,
     pub middle: f64,
@@ -390,6 +489,210 @@ impl BollingerBandsValues {
     }
 }
 
+/// Relative Strength Index (RSI) calculator using Wilder's smoothing
+#[derive(Debug, Clone)]
+pub struct RSI {
+    period: usize,
+    prev_close: Option<f64>,
+    avg_gain: Option<f64>,
+    avg_loss: Option<f64>,
+    gains: VecDeque<f64>,
+    losses: VecDeque<f64>,
+}
+
+impl RSI {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev_close: None,
+            avg_gain: None,
+            avg_loss: None,
+            gains: VecDeque::with_capacity(period),
+            losses: VecDeque::with_capacity(period),
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<f64> {
+        let (gain, loss) = match self.prev_close {
+            Some(prev) => {
+                let change = close - prev;
+                (change.max(0.0), (-change).max(0.0))
+            }
+            None => (0.0, 0.0),
+        };
+        self.prev_close = Some(close);
+
+        if self.avg_gain.is_none() {
+            self.gains.push_back(gain);
+            self.losses.push_back(loss);
+            if self.gains.len() > self.period {
+                self.gains.pop_front();
+            }
+            if self.losses.len() > self.period {
+                self.losses.pop_front();
+            }
+
+            if self.gains.len() >= self.period {
+                self.avg_gain = Some(self.gains.iter().sum::<f64>() / self.period as f64);
+                self.avg_loss = Some(self.losses.iter().sum::<f64>() / self.period as f64);
+            }
+        } else {
+            let prev_avg_gain = self.avg_gain.unwrap();
+            let prev_avg_loss = self.avg_loss.unwrap();
+            self.avg_gain = Some((prev_avg_gain * (self.period - 1) as f64 + gain) / self.period as f64);
+            self.avg_loss = Some((prev_avg_loss * (self.period - 1) as f64 + loss) / self.period as f64);
+        }
+
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        let (avg_gain, avg_loss) = (self.avg_gain?, self.avg_loss?);
+        if avg_loss == 0.0 {
+            return Some(100.0);
+        }
+        let rs = avg_gain / avg_loss;
+        Some(100.0 - (100.0 / (1.0 + rs)))
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.avg_gain.is_some()
+    }
+}
+
+/// Stochastic %K oscillator: `(close - lowest_low) / (highest_high - lowest_low) * 100`
+/// over a rolling `period`-bar window of highs and lows
+#[derive(Debug, Clone)]
+pub struct StochasticK {
+    period: usize,
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+    current: Option<f64>,
+}
+
+impl StochasticK {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            highs: VecDeque::with_capacity(period),
+            lows: VecDeque::with_capacity(period),
+            current: None,
+        }
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<f64> {
+        self.highs.push_back(high);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+        }
+        self.lows.push_back(low);
+        if self.lows.len() > self.period {
+            self.lows.pop_front();
+        }
+
+        if self.highs.len() < self.period {
+            return None;
+        }
+
+        let highest_high = self.highs.iter().cloned().fold(f64::MIN, f64::max);
+        let lowest_low = self.lows.iter().cloned().fold(f64::MAX, f64::min);
+        let range = highest_high - lowest_low;
+        self.current = Some(if range > 0.0 {
+            (close - lowest_low) / range * 100.0
+        } else {
+            50.0  // Flat range: neither overbought nor oversold
+        });
+
+        self.current
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.current
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.highs.len() >= self.period
+    }
+}
+
+/// Accumulation/Distribution line accumulator
+///
+/// Tracks cumulative money flow volume, weighting each bar's volume by where
+/// the close sat within its high/low range: `((close - low) - (high -
+/// close)) / (high - low) * volume`. A bar with `high == low` has no range
+/// to place the close within, so it contributes zero money flow.
+#[derive(Debug, Clone)]
+pub struct AccumulationDistribution {
+    ad: f64,
+}
+
+impl AccumulationDistribution {
+    pub fn new() -> Self {
+        Self { ad: 0.0 }
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64, volume: f64) -> f64 {
+        let range = high - low;
+        let money_flow_multiplier = if range > 0.0 {
+            ((close - low) - (high - close)) / range
+        } else {
+            0.0
+        };
+        self.ad += money_flow_multiplier * volume;
+        self.ad
+    }
+
+    pub fn value(&self) -> f64 {
+        self.ad
+    }
+}
+
+impl Default for AccumulationDistribution {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Chaikin Oscillator: the difference between a fast and slow EMA of the
+/// Accumulation/Distribution line, highlighting momentum shifts in money
+/// flow (e.g. a breakout backed by participation vs. a thin drift)
+#[derive(Debug, Clone)]
+pub struct ChaikinOscillator {
+    ad: AccumulationDistribution,
+    fast_ema: EMA,
+    slow_ema: EMA,
+}
+
+impl ChaikinOscillator {
+    pub fn new(fast_period: usize, slow_period: usize) -> Self {
+        Self {
+            ad: AccumulationDistribution::new(),
+            fast_ema: EMA::new(fast_period),
+            slow_ema: EMA::new(slow_period),
+        }
+    }
+
+    /// Default periods (3, 10) as commonly used for the Chaikin Oscillator
+    pub fn default_periods() -> Self {
+        Self::new(3, 10)
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64, volume: f64) -> Option<f64> {
+        let ad = self.ad.update(high, low, close, volume);
+        let fast = self.fast_ema.update(ad);
+        let slow = self.slow_ema.update(ad);
+
+        match (fast, slow) {
+            (Some(fast), Some(slow)) => Some(fast - slow),
+            _ => None,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.fast_ema.is_ready() && self.slow_ema.is_ready()
+    }
+}
+
 /// Simple Moving Average helper
 pub fn calculate_sma(prices: &[f64]) -> f64 {
     if prices.is_empty() {
@@ -398,52 +701,1247 @@ pub fn calculate_sma(prices: &[f64]) -> f64 {
     prices.iter().sum::<f64>() / prices.len() as f64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Which smoothing kernel a `MovingAverage` should compute. Letting the
+/// regime config pick this per indicator allows tuning the
+/// responsiveness/lag tradeoff without rewriting ADX/Bollinger internals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovingAverageKind {
+    /// Plain windowed average
+    Sma,
+    /// Exponential moving average
+    Ema,
+    /// Wilder's smoothing: `prev*(n-1)/n + price/n`
+    Wilder,
+    /// Linearly weighted by recency (most recent bar weighted `n`)
+    Weighted,
+    /// Hull MA: `WMA(2*WMA(n/2) - WMA(n), round(sqrt(n)))` - low lag
+    Hull,
+    /// Double EMA: `2*EMA - EMA(EMA)` - reduced lag vs plain EMA
+    Dema,
+    /// Triple EMA: `3*EMA - 3*EMA(EMA) + EMA(EMA(EMA))` - reduced lag vs DEMA
+    Tema,
+    /// Kaufman adaptive MA - smoothing constant scales with trend efficiency
+    Kama,
+}
 
-    #[test]
-    fn test_ema_calculation() {
-        let mut ema = EMA::new(10);
+/// Streaming windowed average (the non-exponential counterpart to `EMA`)
+#[derive(Debug, Clone)]
+pub struct SmaCalculator {
+    period: usize,
+    prices: VecDeque<f64>,
+}
 
-        // Warm up
-        for i in 1..=10 {
-            ema.update(i as f64 * 10.0);
+impl SmaCalculator {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prices: VecDeque::with_capacity(period),
         }
+    }
 
-        assert!(ema.is_ready());
-        let value = ema.value().unwrap();
-        assert!(value > 50.0 && value < 100.0);
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        self.prices.push_back(price);
+        if self.prices.len() > self.period {
+            self.prices.pop_front();
+        }
+        if self.prices.len() < self.period {
+            return None;
+        }
+        Some(self.prices.iter().sum::<f64>() / self.period as f64)
     }
 
-    #[test]
-    fn test_bollinger_bands() {
-        let mut bb = BollingerBands::new(20, 2.0);
+    pub fn is_ready(&self) -> bool {
+        self.prices.len() >= self.period
+    }
+}
 
-        // Feed price data
-        for i in 1..=25 {
-            let price = 100.0 + (i as f64 % 5.0);
-            bb.update(price);
+/// Wilder's smoothing method: `prev*(n-1)/n + price/n`, seeded by a plain
+/// average over the first `n` prices (the same warmup `ATR` uses)
+#[derive(Debug, Clone)]
+pub struct WilderMA {
+    period: usize,
+    seed_values: VecDeque<f64>,
+    current: Option<f64>,
+}
+
+impl WilderMA {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            seed_values: VecDeque::with_capacity(period),
+            current: None,
         }
+    }
 
-        assert!(bb.is_ready());
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        match self.current {
+            Some(prev) => {
+                let n = self.period as f64;
+                self.current = Some(prev * (n - 1.0) / n + price / n);
+            }
+            None => {
+                self.seed_values.push_back(price);
+                if self.seed_values.len() >= self.period {
+                    let sum: f64 = self.seed_values.iter().sum();
+                    self.current = Some(sum / self.period as f64);
+                }
+            }
+        }
+        self.current
     }
 
-    #[test]
-    fn test_adx_trending_detection() {
-        let mut adx = ADX::new(14);
+    pub fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+}
 
-        // Simulate trending market (prices going up steadily)
-        for i in 1..=50 {
-            let high = 100.0 + i as f64 * 2.0;
-            let low = 100.0 + i as f64 * 2.0 - 1.0;
-            let close = 100.0 + i as f64 * 2.0 - 0.5;
-            adx.update(high, low, close);
+/// Linearly weighted moving average - the last `n` prices weighted by
+/// recency (the most recent bar carries weight `n`)
+#[derive(Debug, Clone)]
+pub struct Wma {
+    period: usize,
+    prices: VecDeque<f64>,
+}
+
+impl Wma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prices: VecDeque::with_capacity(period),
         }
+    }
 
-        if let Some(adx_value) = adx.value() {
-            println!("ADX value in uptrend: {}", adx_value);
-            assert!(adx_value > 20.0, "ADX should indicate trend");
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        self.prices.push_back(price);
+        if self.prices.len() > self.period {
+            self.prices.pop_front();
+        }
+        if self.prices.len() < self.period {
+            return None;
+        }
+        let weight_sum: f64 = (1..=self.period).sum::<usize>() as f64;
+        let weighted: f64 = self
+            .prices
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (i + 1) as f64 * p)
+            .sum();
+        Some(weighted / weight_sum)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.prices.len() >= self.period
+    }
+}
+
+/// Hull moving average: `WMA(2*WMA(n/2) - WMA(n), round(sqrt(n)))`. Tracks
+/// lower lag than a plain WMA/EMA of the same period.
+#[derive(Debug, Clone)]
+pub struct Hull {
+    half: Wma,
+    full: Wma,
+    smoothed: Wma,
+}
+
+impl Hull {
+    pub fn new(period: usize) -> Self {
+        let half_period = (period / 2).max(1);
+        let smoothed_period = (period as f64).sqrt().round().max(1.0) as usize;
+        Self {
+            half: Wma::new(half_period),
+            full: Wma::new(period),
+            smoothed: Wma::new(smoothed_period),
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let half = self.half.update(price);
+        let full = self.full.update(price);
+        match (half, full) {
+            (Some(half), Some(full)) => self.smoothed.update(2.0 * half - full),
+            _ => None,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.smoothed.is_ready()
+    }
+}
+
+/// Double exponential moving average: `2*EMA - EMA(EMA)`
+#[derive(Debug, Clone)]
+pub struct Dema {
+    ema1: EMA,
+    ema2: EMA,
+}
+
+impl Dema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            ema1: EMA::new(period),
+            ema2: EMA::new(period),
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let e1 = self.ema1.update(price)?;
+        let e2 = self.ema2.update(e1)?;
+        Some(2.0 * e1 - e2)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ema2.is_ready()
+    }
+}
+
+/// Triple exponential moving average: `3*EMA - 3*EMA(EMA) + EMA(EMA(EMA))`
+#[derive(Debug, Clone)]
+pub struct Tema {
+    ema1: EMA,
+    ema2: EMA,
+    ema3: EMA,
+}
+
+impl Tema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            ema1: EMA::new(period),
+            ema2: EMA::new(period),
+            ema3: EMA::new(period),
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let e1 = self.ema1.update(price)?;
+        let e2 = self.ema2.update(e1)?;
+        let e3 = self.ema3.update(e2)?;
+        Some(3.0 * e1 - 3.0 * e2 + e3)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ema3.is_ready()
+    }
+}
+
+/// Kaufman Adaptive Moving Average - blends a fast and slow smoothing
+/// constant by the trend efficiency ratio `ER = |price_t - price_{t-n}| /
+/// sum(|price_i - price_{i-1}|)` over the last `n` prices, so it tracks
+/// closely in a clean trend and flattens out in a choppy one
+#[derive(Debug, Clone)]
+pub struct Kama {
+    period: usize,
+    fast_sc: f64,
+    slow_sc: f64,
+    prices: VecDeque<f64>,
+    current: Option<f64>,
+}
+
+impl Kama {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            fast_sc: 2.0 / (2.0 + 1.0),
+            slow_sc: 2.0 / (30.0 + 1.0),
+            prices: VecDeque::with_capacity(period + 1),
+            current: None,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        self.prices.push_back(price);
+        if self.prices.len() > self.period + 1 {
+            self.prices.pop_front();
+        }
+        if self.prices.len() < self.period + 1 {
+            return None;
+        }
+
+        let change = (price - self.prices[0]).abs();
+        let volatility: f64 = self
+            .prices
+            .iter()
+            .zip(self.prices.iter().skip(1))
+            .map(|(a, b)| (b - a).abs())
+            .sum();
+        let efficiency_ratio = if volatility > 0.0 { change / volatility } else { 0.0 };
+        let smoothing_constant =
+            (efficiency_ratio * (self.fast_sc - self.slow_sc) + self.slow_sc).powi(2);
+
+        let prev = self.current.unwrap_or(price);
+        let new_value = prev + smoothing_constant * (price - prev);
+        self.current = Some(new_value);
+        self.current
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+}
+
+/// Configurable moving-average kernel: picks among `Sma`/`Ema`/`Wilder`/
+/// `Weighted`/`Hull`/`Dema`/`Tema`/`Kama` so regime detection can trade off
+/// responsiveness against lag per indicator without rewriting ADX/Bollinger
+/// internals. All variants share the same streaming `update`/`is_ready`
+/// surface.
+#[derive(Debug, Clone)]
+pub enum MovingAverage {
+    Sma(SmaCalculator),
+    Ema(EMA),
+    Wilder(WilderMA),
+    Weighted(Wma),
+    Hull(Hull),
+    Dema(Dema),
+    Tema(Tema),
+    Kama(Kama),
+}
+
+impl MovingAverage {
+    pub fn new(kind: MovingAverageKind, period: usize) -> Self {
+        match kind {
+            MovingAverageKind::Sma => MovingAverage::Sma(SmaCalculator::new(period)),
+            MovingAverageKind::Ema => MovingAverage::Ema(EMA::new(period)),
+            MovingAverageKind::Wilder => MovingAverage::Wilder(WilderMA::new(period)),
+            MovingAverageKind::Weighted => MovingAverage::Weighted(Wma::new(period)),
+            MovingAverageKind::Hull => MovingAverage::Hull(Hull::new(period)),
+            MovingAverageKind::Dema => MovingAverage::Dema(Dema::new(period)),
+            MovingAverageKind::Tema => MovingAverage::Tema(Tema::new(period)),
+            MovingAverageKind::Kama => MovingAverage::Kama(Kama::new(period)),
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        match self {
+            MovingAverage::Sma(ma) => ma.update(price),
+            MovingAverage::Ema(ma) => ma.update(price),
+            MovingAverage::Wilder(ma) => ma.update(price),
+            MovingAverage::Weighted(ma) => ma.update(price),
+            MovingAverage::Hull(ma) => ma.update(price),
+            MovingAverage::Dema(ma) => ma.update(price),
+            MovingAverage::Tema(ma) => ma.update(price),
+            MovingAverage::Kama(ma) => ma.update(price),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        match self {
+            MovingAverage::Sma(ma) => ma.is_ready(),
+            MovingAverage::Ema(ma) => ma.is_ready(),
+            MovingAverage::Wilder(ma) => ma.is_ready(),
+            MovingAverage::Weighted(ma) => ma.is_ready(),
+            MovingAverage::Hull(ma) => ma.is_ready(),
+            MovingAverage::Dema(ma) => ma.is_ready(),
+            MovingAverage::Tema(ma) => ma.is_ready(),
+            MovingAverage::Kama(ma) => ma.is_ready(),
+        }
+    }
+}
+
+/// Keltner Channels output values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeltnerChannelsValues {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Keltner Channels calculator: middle is an `EMA(period)` of closes, the
+/// bands are the middle plus/minus `multiplier * ATR(period)`
+#[derive(Debug, Clone)]
+pub struct KeltnerChannels {
+    multiplier: f64,
+    ema: EMA,
+    atr: ATR,
+    current: Option<KeltnerChannelsValues>,
+}
+
+impl KeltnerChannels {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            ema: EMA::new(period),
+            atr: ATR::new(period),
+            current: None,
+        }
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<KeltnerChannelsValues> {
+        let middle = self.ema.update(close);
+        let atr = self.atr.update(high, low, close);
+
+        self.current = match (middle, atr) {
+            (Some(middle), Some(atr)) => Some(KeltnerChannelsValues {
+                upper: middle + self.multiplier * atr,
+                middle,
+                lower: middle - self.multiplier * atr,
+            }),
+            _ => None,
+        };
+        self.current
+    }
+
+    pub fn value(&self) -> Option<KeltnerChannelsValues> {
+        self.current
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ema.is_ready() && self.atr.is_ready()
+    }
+}
+
+/// Result of one `SqueezeDetector::update` call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SqueezeStatus {
+    /// Bollinger Bands are currently inside the Keltner Channels
+    pub in_squeeze: bool,
+    /// The squeeze was active last bar and has just released - the classic
+    /// low-volatility-to-breakout transition
+    pub squeeze_fired: bool,
+    pub bollinger: Option<BollingerBandsValues>,
+    pub keltner: Option<KeltnerChannelsValues>,
+}
+
+/// Compares `BollingerBands` against `KeltnerChannels` driven from the same
+/// bars - a squeeze (Bollinger inside Keltner) signals a coiling,
+/// low-volatility market, and its release (`squeeze_fired`) flags the
+/// breakout multi-indicator trend systems watch for
+#[derive(Debug, Clone)]
+pub struct SqueezeDetector {
+    bollinger: BollingerBands,
+    keltner: KeltnerChannels,
+    in_squeeze: bool,
+}
+
+impl SqueezeDetector {
+    pub fn new(bb_period: usize, bb_std_dev: f64, kc_period: usize, kc_multiplier: f64) -> Self {
+        Self {
+            bollinger: BollingerBands::new(bb_period, bb_std_dev),
+            keltner: KeltnerChannels::new(kc_period, kc_multiplier),
+            in_squeeze: false,
+        }
+    }
+
+    /// Default config: BB(20, 2.0) against KC(20, 1.5), the common pairing
+    pub fn default_config() -> Self {
+        Self::new(20, 2.0, 20, 1.5)
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> SqueezeStatus {
+        let bollinger = self.bollinger.update(close);
+        let keltner = self.keltner.update(high, low, close);
+
+        let currently_in_squeeze = match (bollinger, keltner) {
+            (Some(bb), Some(kc)) => bb.upper < kc.upper && bb.lower > kc.lower,
+            _ => false,
+        };
+        let squeeze_fired = self.in_squeeze && !currently_in_squeeze;
+        self.in_squeeze = currently_in_squeeze;
+
+        SqueezeStatus {
+            in_squeeze: currently_in_squeeze,
+            squeeze_fired,
+            bollinger,
+            keltner,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.bollinger.is_ready() && self.keltner.is_ready()
+    }
+}
+
+/// Chandelier Exit output: both candidate stops, plus whether each was
+/// just violated by `close`. A caller in a long position watches
+/// `long_stop`/`long_stop_violated`; one in a short position watches
+/// `short_stop`/`short_stop_violated`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChandelierExitValues {
+    pub long_stop: f64,
+    pub short_stop: f64,
+    pub long_stop_violated: bool,
+    pub short_stop_violated: bool,
+}
+
+/// ATR-driven trailing stop: `long_stop = highest_high(period) -
+/// multiplier * ATR`, `short_stop = lowest_low(period) + multiplier *
+/// ATR`. Each stop ratchets - the long stop only moves up and the short
+/// stop only moves down - until `close` violates it, at which point it
+/// resets to the fresh candidate. Gives the regime layer a
+/// volatility-scaled exit level instead of a fixed percentage.
+#[derive(Debug, Clone)]
+pub struct ChandelierExit {
+    period: usize,
+    multiplier: f64,
+    atr: ATR,
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+    long_stop: Option<f64>,
+    short_stop: Option<f64>,
+}
+
+impl ChandelierExit {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            period,
+            multiplier,
+            atr: ATR::new(period),
+            highs: VecDeque::with_capacity(period),
+            lows: VecDeque::with_capacity(period),
+            long_stop: None,
+            short_stop: None,
+        }
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<ChandelierExitValues> {
+        let atr = self.atr.update(high, low, close);
+
+        self.highs.push_back(high);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+        }
+        self.lows.push_back(low);
+        if self.lows.len() > self.period {
+            self.lows.pop_front();
+        }
+
+        let atr = atr?;
+        if self.highs.len() < self.period {
+            return None;
+        }
+
+        let highest_high = self.highs.iter().cloned().fold(f64::MIN, f64::max);
+        let lowest_low = self.lows.iter().cloned().fold(f64::MAX, f64::min);
+
+        let candidate_long_stop = highest_high - self.multiplier * atr;
+        let candidate_short_stop = lowest_low + self.multiplier * atr;
+
+        let long_stop_violated = self.long_stop.is_some_and(|stop| close < stop);
+        self.long_stop = Some(match self.long_stop {
+            Some(prev) if !long_stop_violated => prev.max(candidate_long_stop),
+            _ => candidate_long_stop,
+        });
+
+        let short_stop_violated = self.short_stop.is_some_and(|stop| close > stop);
+        self.short_stop = Some(match self.short_stop {
+            Some(prev) if !short_stop_violated => prev.min(candidate_short_stop),
+            _ => candidate_short_stop,
+        });
+
+        Some(ChandelierExitValues {
+            long_stop: self.long_stop.unwrap(),
+            short_stop: self.short_stop.unwrap(),
+            long_stop_violated,
+            short_stop_violated,
+        })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.atr.is_ready() && self.highs.len() >= self.period
+    }
+}
+
+/// Sub-scores behind `ConsensusScore::total`, each roughly in `[-1.0,
+/// 1.0]` before the ADX strength gate is applied, so callers can see *why*
+/// a bar was scored the way it was rather than just the blended number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsensusBreakdown {
+    /// DI crossover and MA slope, averaged (positive = bullish)
+    pub direction_score: f64,
+    /// Bollinger %B pressure: overbought pulls negative, oversold positive
+    pub mean_reversion_score: f64,
+    /// RSI confirmation, `(50 - RSI) / 50`: negative above 50, positive below
+    pub rsi_score: f64,
+    /// `min(ADX / 20, 1.0)` - scales everything toward zero in chop
+    pub adx_strength_gate: f64,
+    /// The gated, weighted blend of the three sub-scores, in `[-1.0, 1.0]`
+    pub total: f64,
+}
+
+/// Combines ADX (trend strength gate), DI crossover + MA slope (direction),
+/// Bollinger %B (mean-reversion pressure), and RSI (confirmation) into one
+/// signed bias in `[-1.0, 1.0]` - the "confirmation/consensus" pattern
+/// where a breakout is only trusted when multiple studies agree
+#[derive(Debug, Clone)]
+pub struct ConsensusScore {
+    adx: ADX,
+    bollinger: BollingerBands,
+    rsi: RSI,
+    trend_ma: EMA,
+    prev_trend_ma: Option<f64>,
+}
+
+impl ConsensusScore {
+    pub fn new(
+        adx_period: usize,
+        bb_period: usize,
+        bb_std_dev: f64,
+        rsi_period: usize,
+        trend_ma_period: usize,
+    ) -> Self {
+        Self {
+            adx: ADX::new(adx_period),
+            bollinger: BollingerBands::new(bb_period, bb_std_dev),
+            rsi: RSI::new(rsi_period),
+            trend_ma: EMA::new(trend_ma_period),
+            prev_trend_ma: None,
+        }
+    }
+
+    /// Default config: ADX(14), BB(20, 2.0), RSI(14), trend MA EMA(21)
+    pub fn default_config() -> Self {
+        Self::new(14, 20, 2.0, 14, 21)
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<ConsensusBreakdown> {
+        let adx_value = self.adx.update(high, low, close);
+        let bb = self.bollinger.update(close);
+        let rsi_value = self.rsi.update(close);
+        let trend_ma = self.trend_ma.update(close);
+
+        let (adx_value, bb, rsi_value, trend_ma) = match (adx_value, bb, rsi_value, trend_ma) {
+            (Some(a), Some(b), Some(r), Some(m)) => (a, b, r, m),
+            _ => return None,
+        };
+
+        let di_direction = match (self.adx.plus_di(), self.adx.minus_di()) {
+            (Some(plus), Some(minus)) => (plus - minus).signum(),
+            _ => 0.0,
+        };
+        let slope_direction = match self.prev_trend_ma {
+            Some(prev) => (trend_ma - prev).signum(),
+            None => 0.0,
+        };
+        self.prev_trend_ma = Some(trend_ma);
+        let direction_score = (di_direction + slope_direction) / 2.0;
+
+        let mean_reversion_score = ((0.5 - bb.percent_b) * 2.0).clamp(-1.0, 1.0);
+        let rsi_score = ((50.0 - rsi_value) / 50.0).clamp(-1.0, 1.0);
+        let adx_strength_gate = (adx_value / 20.0).min(1.0);
+
+        let blended =
+            direction_score * 0.4 + mean_reversion_score * 0.3 + rsi_score * 0.3;
+        let total = (blended * adx_strength_gate).clamp(-1.0, 1.0);
+
+        Some(ConsensusBreakdown {
+            direction_score,
+            mean_reversion_score,
+            rsi_score,
+            adx_strength_gate,
+            total,
+        })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.adx.is_ready() && self.bollinger.is_ready() && self.rsi.is_ready() && self.trend_ma.is_ready()
+    }
+}
+
+/// SuperTrend output for one bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuperTrendValues {
+    /// The active trailing band - `final_lower` while bullish, `final_upper`
+    /// while bearish
+    pub line: f64,
+    pub direction: super::TrendDirection,
+}
+
+/// ATR-band trend filter: trails `final_upper`/`final_lower` bands off the
+/// HL2 midpoint (`mult * ATR` wide) and only flips direction once `close`
+/// closes through the active band, rather than an EMA crossover which can
+/// flicker right at the cross. Gives `classify_regime` a second, more
+/// stable vote on trend direction to require agreement from.
+#[derive(Debug, Clone)]
+pub struct SuperTrend {
+    multiplier: f64,
+    atr: ATR,
+    final_upper: Option<f64>,
+    final_lower: Option<f64>,
+    prev_close: Option<f64>,
+    direction: Option<super::TrendDirection>,
+}
+
+impl SuperTrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            atr: ATR::new(period),
+            final_upper: None,
+            final_lower: None,
+            prev_close: None,
+            direction: None,
+        }
+    }
+
+    /// Default multiplier of 3.0, matching the common `SuperTrend(10, 3.0)` pairing
+    pub fn default_config(period: usize) -> Self {
+        Self::new(period, 3.0)
+    }
+
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Option<SuperTrendValues> {
+        let atr = self.atr.update(high, low, close)?;
+        let hl2 = (high + low) / 2.0;
+        let basic_upper = hl2 + self.multiplier * atr;
+        let basic_lower = hl2 - self.multiplier * atr;
+
+        let final_upper = match (self.final_upper, self.prev_close) {
+            (Some(prev_final_upper), Some(prev_close)) => {
+                if basic_upper < prev_final_upper || prev_close > prev_final_upper {
+                    basic_upper
+                } else {
+                    prev_final_upper
+                }
+            }
+            _ => basic_upper,
+        };
+        let final_lower = match (self.final_lower, self.prev_close) {
+            (Some(prev_final_lower), Some(prev_close)) => {
+                if basic_lower > prev_final_lower || prev_close < prev_final_lower {
+                    basic_lower
+                } else {
+                    prev_final_lower
+                }
+            }
+            _ => basic_lower,
+        };
+
+        let direction = match self.direction {
+            Some(super::TrendDirection::Bullish) => {
+                if close < final_lower {
+                    super::TrendDirection::Bearish
+                } else {
+                    super::TrendDirection::Bullish
+                }
+            }
+            Some(super::TrendDirection::Bearish) => {
+                if close > final_upper {
+                    super::TrendDirection::Bullish
+                } else {
+                    super::TrendDirection::Bearish
+                }
+            }
+            // First bar with no prior direction - seed it off which side of
+            // the midpoint close sits on
+            None => if close >= hl2 {
+                super::TrendDirection::Bullish
+            } else {
+                super::TrendDirection::Bearish
+            },
+        };
+        let line = match direction {
+            super::TrendDirection::Bullish => final_lower,
+            super::TrendDirection::Bearish => final_upper,
+        };
+
+        self.final_upper = Some(final_upper);
+        self.final_lower = Some(final_lower);
+        self.prev_close = Some(close);
+        self.direction = Some(direction);
+
+        Some(SuperTrendValues { line, direction })
+    }
+
+    pub fn direction(&self) -> Option<super::TrendDirection> {
+        self.direction
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.direction.is_some()
+    }
+}
+
+/// One Heikin-Ashi smoothed candle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeikinAshiValues {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Heikin-Ashi candle smoother: `HA_close` averages all four raw prices,
+/// and `HA_open` averages the *previous* HA candle's open/close, so each
+/// new bar is pulled toward the recent trend rather than reacting fully to
+/// a single-bar spike. Feeding these into ADX/ATR/Bollinger/EMA instead of
+/// raw OHLC suppresses whipsaws and keeps trend regimes "sticky" at the
+/// cost of a bar of lag.
+#[derive(Debug, Clone, Default)]
+pub struct HeikinAshi {
+    prev: Option<HeikinAshiValues>,
+}
+
+impl HeikinAshi {
+    pub fn new() -> Self {
+        Self { prev: None }
+    }
+
+    /// `open` seeds `HA_open` on the very first bar only; every bar after
+    /// that derives `HA_open` purely from the prior HA candle.
+    pub fn update(&mut self, open: f64, high: f64, low: f64, close: f64) -> HeikinAshiValues {
+        let ha_close = (open + high + low + close) / 4.0;
+        let ha_open = match self.prev {
+            Some(prev) => (prev.open + prev.close) / 2.0,
+            None => (open + close) / 2.0,
+        };
+        let ha_high = high.max(ha_open).max(ha_close);
+        let ha_low = low.min(ha_open).min(ha_close);
+
+        let values = HeikinAshiValues { open: ha_open, high: ha_high, low: ha_low, close: ha_close };
+        self.prev = Some(values);
+        values
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.prev.is_some()
+    }
+}
+
+/// Percentage spread between a fast and slow EMA of volume:
+/// `100 * (EMA_fast(vol) - EMA_slow(vol)) / EMA_slow(vol)`. Positive means
+/// participation is rising, which `classify_regime` treats as confirmation
+/// for a breakout; negative flags a move happening on thinning volume.
+#[derive(Debug, Clone)]
+pub struct VolumeOscillator {
+    fast: EMA,
+    slow: EMA,
+}
+
+impl VolumeOscillator {
+    pub fn new(fast_period: usize, slow_period: usize) -> Self {
+        Self {
+            fast: EMA::new(fast_period),
+            slow: EMA::new(slow_period),
+        }
+    }
+
+    /// Default 14/28 fast/slow pairing
+    pub fn default_config() -> Self {
+        Self::new(14, 28)
+    }
+
+    pub fn update(&mut self, volume: f64) -> Option<f64> {
+        let fast = self.fast.update(volume);
+        let slow = self.slow.update(volume);
+
+        match (fast, slow) {
+            (Some(fast), Some(slow)) if slow != 0.0 => Some(100.0 * (fast - slow) / slow),
+            (Some(_), Some(_)) => Some(0.0),  // Slow EMA at zero - no meaningful spread to report
+            _ => None,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.fast.is_ready() && self.slow.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::TrendDirection;
+
+    #[test]
+    fn test_ema_calculation() {
+        let mut ema = EMA::new(10);
+
+        // Warm up
+        for i in 1..=10 {
+            ema.update(i as f64 * 10.0);
+        }
+
+        assert!(ema.is_ready());
+        let value = ema.value().unwrap();
+        assert!(value > 50.0 && value < 100.0);
+    }
+
+    #[test]
+    fn test_bollinger_bands() {
+        let mut bb = BollingerBands::new(20, 2.0);
+
+        // Feed price data
+        for i in 1..=25 {
+            let price = 100.0 + (i as f64 % 5.0);
+            bb.update(price);
+        }
+
+        assert!(bb.is_ready());
+    }
+
+    #[test]
+    fn test_rsi_bounds() {
+        let mut rsi = RSI::new(14);
+
+        for i in 0..30 {
+            let price = 100.0 + (i as f64 % 7.0) - 3.0;
+            rsi.update(price);
+        }
+
+        assert!(rsi.is_ready());
+        let value = rsi.value().unwrap();
+        assert!((0.0..=100.0).contains(&value));
+    }
+
+    #[test]
+    fn test_rsi_strong_uptrend_is_high() {
+        let mut rsi = RSI::new(14);
+
+        for i in 1..=30 {
+            rsi.update(100.0 + i as f64);
+        }
+
+        assert!(rsi.value().unwrap() > 70.0);
+    }
+
+    #[test]
+    fn test_adx_trending_detection() {
+        let mut adx = ADX::new(14);
+
+        // Simulate trending market (prices going up steadily)
+        for i in 1..=50 {
+            let high = 100.0 + i as f64 * 2.0;
+            let low = 100.0 + i as f64 * 2.0 - 1.0;
+            let close = 100.0 + i as f64 * 2.0 - 0.5;
+            adx.update(high, low, close);
+        }
+
+        if let Some(adx_value) = adx.value() {
+            println!("ADX value in uptrend: {}", adx_value);
+            assert!(adx_value > 20.0, "ADX should indicate trend");
+        }
+    }
+
+    #[test]
+    fn test_accumulation_distribution_flat_bar_contributes_zero() {
+        let mut ad = AccumulationDistribution::new();
+        ad.update(100.0, 100.0, 100.0, 500.0);
+        assert_eq!(ad.value(), 0.0);
+    }
+
+    #[test]
+    fn test_accumulation_distribution_accumulates_close_near_high() {
+        let mut ad = AccumulationDistribution::new();
+        let first = ad.update(110.0, 100.0, 109.0, 1000.0);
+        let second = ad.update(112.0, 102.0, 111.0, 1000.0);
+        assert!(first > 0.0);
+        assert!(second > first);
+    }
+
+    #[test]
+    fn test_chaikin_oscillator_ready_after_slow_period() {
+        let mut co = ChaikinOscillator::default_periods();
+
+        for i in 1..=9 {
+            let price = 100.0 + i as f64;
+            assert!(co.update(price + 1.0, price - 1.0, price, 1000.0).is_none());
+        }
+
+        assert!(co.update(111.0, 109.0, 110.0, 1000.0).is_some());
+        assert!(co.is_ready());
+    }
+
+    #[test]
+    fn test_ema_reset_clears_state_back_to_construction_defaults() {
+        let mut ema = EMA::new(10);
+        for i in 1..=10 {
+            ema.update(i as f64 * 10.0);
+        }
+        assert!(Indicator::is_ready(&ema));
+
+        Indicator::reset(&mut ema);
+
+        assert!(!Indicator::is_ready(&ema));
+        assert_eq!(Indicator::value(&ema), None);
+    }
+
+    #[test]
+    fn test_bollinger_bands_reset_clears_history() {
+        let mut bb = BollingerBands::new(20, 2.0);
+        for i in 1..=25 {
+            bb.update(100.0 + (i as f64 % 5.0));
+        }
+        assert!(Indicator::is_ready(&bb));
+
+        Indicator::reset(&mut bb);
+
+        assert!(!Indicator::is_ready(&bb));
+        assert_eq!(Indicator::value(&bb), None);
+    }
+
+    #[test]
+    fn test_moving_average_sma_matches_calculate_sma() {
+        let mut sma = MovingAverage::new(MovingAverageKind::Sma, 5);
+        let prices = [10.0, 12.0, 11.0, 13.0, 14.0];
+        let mut last = None;
+        for &p in &prices {
+            last = sma.update(p);
+        }
+        assert_eq!(last, Some(calculate_sma(&prices)));
+    }
+
+    #[test]
+    fn test_moving_average_wilder_matches_manual_smoothing() {
+        let mut wilder = MovingAverage::new(MovingAverageKind::Wilder, 3);
+        assert_eq!(wilder.update(10.0), None);
+        assert_eq!(wilder.update(12.0), None);
+        let seeded = wilder.update(14.0).unwrap();
+        assert_eq!(seeded, (10.0 + 12.0 + 14.0) / 3.0);
+
+        let next = wilder.update(20.0).unwrap();
+        assert_eq!(next, seeded * 2.0 / 3.0 + 20.0 / 3.0);
+    }
+
+    #[test]
+    fn test_moving_average_hull_tracks_a_steady_uptrend() {
+        let mut hull = MovingAverage::new(MovingAverageKind::Hull, 9);
+        let mut last = None;
+        for i in 1..=30 {
+            last = hull.update(100.0 + i as f64);
+        }
+        assert!(hull.is_ready());
+        assert!(last.unwrap() > 100.0);
+    }
+
+    #[test]
+    fn test_moving_average_dema_and_tema_are_ready_and_finite() {
+        let mut dema = MovingAverage::new(MovingAverageKind::Dema, 5);
+        let mut tema = MovingAverage::new(MovingAverageKind::Tema, 5);
+        for i in 1..=40 {
+            dema.update(100.0 + (i as f64 % 7.0));
+            tema.update(100.0 + (i as f64 % 7.0));
+        }
+        assert!(dema.is_ready());
+        assert!(tema.is_ready());
+        assert!(dema.update(105.0).unwrap().is_finite());
+        assert!(tema.update(105.0).unwrap().is_finite());
+    }
+
+    #[test]
+    fn test_moving_average_kama_is_more_responsive_in_a_clean_trend_than_a_chop() {
+        let mut trending = MovingAverage::new(MovingAverageKind::Kama, 10);
+        let mut choppy = MovingAverage::new(MovingAverageKind::Kama, 10);
+
+        let mut trend_last = 0.0;
+        for i in 1..=20 {
+            trend_last = trending.update(100.0 + i as f64).unwrap_or(0.0);
+        }
+
+        let mut choppy_last = 0.0;
+        for i in 1..=20 {
+            let price = 100.0 + if i % 2 == 0 { 1.0 } else { -1.0 };
+            choppy_last = choppy.update(price).unwrap_or(0.0);
+        }
+
+        // A clean trend has an efficiency ratio near 1, so KAMA should
+        // track close to the latest price; a pure back-and-forth chop has
+        // an efficiency ratio near 0, so it should barely move from 100.
+        assert!(trend_last > 110.0);
+        assert!((choppy_last - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_keltner_channels_bands_widen_with_atr() {
+        let mut kc = KeltnerChannels::new(10, 2.0);
+
+        for i in 1..=15 {
+            let price = 100.0 + i as f64 % 3.0;
+            kc.update(price + 1.0, price - 1.0, price);
+        }
+
+        assert!(kc.is_ready());
+        let values = kc.value().unwrap();
+        assert!(values.upper > values.middle);
+        assert!(values.lower < values.middle);
+    }
+
+    #[test]
+    fn test_squeeze_detector_flags_squeeze_in_a_tight_range() {
+        let mut squeeze = SqueezeDetector::default_config();
+        let mut status = None;
+
+        // Tight, low-volatility chop: Bollinger should pull in tighter than
+        // the ATR-driven Keltner Channels.
+        for i in 0..30 {
+            let price = 100.0 + (i as f64 % 2.0) * 0.1;
+            status = Some(squeeze.update(price + 0.2, price - 0.2, price));
+        }
+
+        assert!(squeeze.is_ready());
+        assert!(status.unwrap().in_squeeze);
+    }
+
+    #[test]
+    fn test_squeeze_detector_fires_on_release_after_expansion() {
+        let mut squeeze = SqueezeDetector::default_config();
+
+        for i in 0..30 {
+            let price = 100.0 + (i as f64 % 2.0) * 0.1;
+            squeeze.update(price + 0.2, price - 0.2, price);
+        }
+        assert!(squeeze.update(100.0, 99.9, 100.0).in_squeeze);
+
+        // A sharp expansion should push Bollinger outside Keltner and fire
+        // the release on the bar it happens.
+        let mut fired = false;
+        let mut price = 100.0;
+        for i in 0..10 {
+            price += 5.0 + i as f64;
+            let status = squeeze.update(price + 1.0, price - 1.0, price);
+            if status.squeeze_fired {
+                fired = true;
+                break;
+            }
+        }
+        assert!(fired, "expected squeeze_fired after a sharp expansion");
+    }
+
+    #[test]
+    fn test_super_trend_flips_bullish_on_a_sustained_rally() {
+        let mut st = SuperTrend::default_config(10);
+        let mut price = 100.0;
+
+        // Flat chop first so the bands settle, then a rally that should
+        // break well clear of the trailing final_upper band.
+        for i in 0..15 {
+            let p = price + (i as f64 % 2.0) * 0.1;
+            st.update(p + 0.2, p - 0.2, p);
+        }
+        for _ in 0..10 {
+            price += 5.0;
+            st.update(price + 0.2, price - 0.2, price);
+        }
+
+        assert!(st.is_ready());
+        assert_eq!(st.direction(), Some(TrendDirection::Bullish));
+    }
+
+    #[test]
+    fn test_super_trend_flips_bearish_on_a_sustained_selloff() {
+        let mut st = SuperTrend::default_config(10);
+        let mut price = 100.0;
+
+        for i in 0..15 {
+            let p = price + (i as f64 % 2.0) * 0.1;
+            st.update(p + 0.2, p - 0.2, p);
+        }
+        for _ in 0..10 {
+            price -= 5.0;
+            st.update(price + 0.2, price - 0.2, price);
+        }
+
+        assert_eq!(st.direction(), Some(TrendDirection::Bearish));
+    }
+
+    #[test]
+    fn test_chandelier_exit_long_stop_ratchets_up_in_an_uptrend() {
+        let mut ce = ChandelierExit::new(10, 3.0);
+        let mut prev_long_stop = f64::MIN;
+
+        for i in 1..=20 {
+            let price = 100.0 + i as f64;
+            if let Some(values) = ce.update(price + 1.0, price - 1.0, price) {
+                assert!(values.long_stop >= prev_long_stop);
+                prev_long_stop = values.long_stop;
+                assert!(!values.long_stop_violated);
+            }
+        }
+        assert!(ce.is_ready());
+    }
+
+    #[test]
+    fn test_chandelier_exit_flags_long_stop_violation_on_a_sharp_drop() {
+        let mut ce = ChandelierExit::new(10, 2.0);
+
+        for i in 1..=15 {
+            let price = 100.0 + i as f64;
+            ce.update(price + 1.0, price - 1.0, price);
+        }
+
+        let values = ce.update(50.0, 48.0, 49.0).unwrap();
+        assert!(values.long_stop_violated);
+    }
+
+    #[test]
+    fn test_consensus_score_direction_is_bullish_in_a_strong_uptrend() {
+        let mut consensus = ConsensusScore::default_config();
+        let mut last = None;
+
+        for i in 1..=60 {
+            let price = 100.0 + i as f64 * 1.5;
+            last = consensus.update(price + 1.0, price - 1.0, price);
+        }
+
+        assert!(consensus.is_ready());
+        let breakdown = last.unwrap();
+        // DI crossover and MA slope both point up...
+        assert!(breakdown.direction_score > 0.0);
+        // ...but a relentless, un-pulled-back rally is overbought on both
+        // %B and RSI, so the consensus pulls the blended total down rather
+        // than blindly trusting unconfirmed momentum.
+        assert!(breakdown.mean_reversion_score < 0.0);
+        assert!(breakdown.rsi_score < 0.0);
+    }
+
+    #[test]
+    fn test_consensus_score_is_gated_toward_zero_in_a_choppy_market() {
+        let mut consensus = ConsensusScore::default_config();
+        let mut last = None;
+
+        for i in 0..60 {
+            let price = 100.0 + (i as f64 % 2.0) * 0.1;
+            last = consensus.update(price + 0.2, price - 0.2, price);
+        }
+
+        let breakdown = last.unwrap();
+        assert!(breakdown.adx_strength_gate < 1.0);
+        assert!(breakdown.total.abs() < 0.5);
+    }
+
+    #[test]
+    fn test_heikin_ashi_smooths_a_single_bar_spike() {
+        let mut ha = HeikinAshi::new();
+        ha.update(100.0, 100.5, 99.5, 100.0);
+        ha.update(100.0, 100.5, 99.5, 100.2);
+
+        // A sharp one-bar spike shouldn't fully show up in HA_close, since
+        // it's averaged against the open/high/low of the same bar.
+        let spiked = ha.update(100.2, 110.0, 100.0, 109.0);
+        assert!(spiked.close < 109.0);
+        assert!(ha.is_ready());
+    }
+
+    #[test]
+    fn test_heikin_ashi_open_trails_the_prior_candle() {
+        let mut ha = HeikinAshi::new();
+        let first = ha.update(100.0, 101.0, 99.0, 100.5);
+        let second = ha.update(100.5, 102.0, 100.0, 101.5);
+
+        assert_eq!(second.open, (first.open + first.close) / 2.0);
+    }
+
+    #[test]
+    fn test_volume_oscillator_positive_on_rising_volume() {
+        let mut vo = VolumeOscillator::new(3, 6);
+        let mut last = None;
+        for v in [100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 200.0, 220.0, 240.0] {
+            last = vo.update(v);
+        }
+        assert!(last.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_volume_oscillator_negative_on_fading_volume() {
+        let mut vo = VolumeOscillator::new(3, 6);
+        let mut last = None;
+        for v in [200.0, 200.0, 200.0, 200.0, 200.0, 200.0, 100.0, 80.0, 60.0] {
+            last = vo.update(v);
+        }
+        assert!(last.unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_volume_oscillator_not_ready_until_slow_ema_warms_up() {
+        let mut vo = VolumeOscillator::new(3, 6);
+        for _ in 0..5 {
+            vo.update(100.0);
         }
+        assert!(!vo.is_ready());
     }
 }
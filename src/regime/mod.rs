@@ -3,11 +3,12 @@
 //! Detects market regime (Trending, Mean-Reverting, Volatile) to enable
 //! strategy switching based on current market conditions.
 //! 
-//! Three detection approaches available:
+//! Four detection approaches available:
 //! 1. **Technical Indicators** (RegimeDetector) - Fast, rule-based using ADX/BB/ATR
 //! 2. **Hidden Markov Model** (HMMRegimeDetector) - Statistical, learns from returns
 //! 3. **Ensemble** (EnsembleRegimeDetector) - Combines both for robustness
-//! 
+//! 4. **Multi-Model** (MultiModelRegimeDetector) - One dedicated HMM per candidate regime
+//!
 //! Based on research showing regime-aware strategies outperform static ones by 20-40%
 
 mod detector;
@@ -15,9 +16,11 @@ mod indicators;
 mod types;
 mod hmm;
 mod ensemble;
+mod multi_model;
 
 pub use detector::RegimeDetector;
 pub use indicators::*;
 pub use types::*;
-pub use hmm::{HMMRegimeDetector, HMMConfig};
-pub use ensemble::{EnsembleRegimeDetector, EnsembleConfig, EnsembleResult, EnsembleStatus};
+pub use hmm::{HMMRegimeDetector, HMMConfig, RegimeForecast};
+pub use ensemble::{EnsembleRegimeDetector, EnsembleConfig, EnsembleResult, EnsembleStatus, RegimeSource};
+pub use multi_model::MultiModelRegimeDetector;
@@ -0,0 +1,209 @@
+//! Multi-Model (One-HMM-Per-Regime) Detector
+//!
+//! The single `HMMRegimeDetector` fits one shared transition matrix and one
+//! Gaussian per state, which assumes every regime's internal dynamics are
+//! comparable enough to co-exist in the same model. This is the second
+//! classic HMM regime-detection approach: train a dedicated single-state
+//! Gaussian HMM per candidate regime and classify the current window by
+//! whichever model fits it best. More robust when regimes have very
+//! different internal dynamics (e.g. a high-vol regime's variance would
+//! otherwise drag the shared transition matrix's emission estimates around).
+
+use super::{
+    hmm::{HMMConfig, HMMRegimeDetector},
+    types::{MarketRegime, RegimeConfidence, TrendDirection},
+};
+
+/// One dedicated single-state Gaussian HMM per candidate regime, selected by
+/// whichever model assigns the highest (scaled) log-likelihood to the
+/// recent return window.
+#[derive(Debug)]
+pub struct MultiModelRegimeDetector {
+    /// One detector per candidate regime, same order as `labels`
+    models: Vec<HMMRegimeDetector>,
+
+    /// Regime label for each entry in `models`
+    labels: Vec<MarketRegime>,
+
+    /// Most recently selected regime
+    current_regime: MarketRegime,
+
+    n_observations: usize,
+    min_observations: usize,
+}
+
+impl MultiModelRegimeDetector {
+    /// Build the default four-way split: bull / bear / ranging / volatile.
+    /// The bull/bear/volatile priors match `HMMRegimeDetector::new`'s
+    /// 3-state initialization; ranging gets its own low-vol, neutral-mean
+    /// prior rather than sharing the high-vol state's catch-all.
+    pub fn new(model_config: HMMConfig) -> Self {
+        let candidates: [(MarketRegime, f64, f64); 4] = [
+            (MarketRegime::Trending(TrendDirection::Bullish), 0.001, 0.0001),
+            (MarketRegime::Trending(TrendDirection::Bearish), -0.001, 0.0002),
+            (MarketRegime::MeanReverting, 0.0, 0.00015),
+            (MarketRegime::Volatile, 0.0, 0.0009),
+        ];
+
+        let mut labels = Vec::with_capacity(candidates.len());
+        let mut models = Vec::with_capacity(candidates.len());
+        for (label, mean, variance) in candidates {
+            labels.push(label);
+            models.push(HMMRegimeDetector::single_state(mean, variance, model_config.clone()));
+        }
+
+        Self {
+            min_observations: model_config.min_observations,
+            models,
+            labels,
+            current_regime: MarketRegime::Uncertain,
+            n_observations: 0,
+        }
+    }
+
+    /// Create with a crypto-optimized shared config across all candidates
+    pub fn default_config() -> Self {
+        Self::new(HMMConfig::crypto_optimized())
+    }
+
+    /// Update every candidate model with the new price and reclassify by
+    /// softmax over their log-likelihoods
+    pub fn update(&mut self, close: f64) -> RegimeConfidence {
+        self.n_observations += 1;
+        for model in &mut self.models {
+            model.update(close);
+        }
+
+        if self.n_observations < self.min_observations {
+            return RegimeConfidence::new(MarketRegime::Uncertain, 0.0);
+        }
+
+        let log_likelihoods: Vec<f64> = self.models.iter().map(|m| m.log_likelihood()).collect();
+        let (best_idx, confidence) = Self::softmax_argmax(&log_likelihoods);
+        self.current_regime = self.labels[best_idx];
+
+        let (mean, variance) = self.models[best_idx].state_parameters()[0];
+        RegimeConfidence::with_metrics(
+            self.current_regime,
+            confidence,
+            mean * 100.0 * 252.0,                       // Annualized return %
+            variance.sqrt() * 100.0 * 252.0_f64.sqrt(),  // Annualized vol %
+            0.0,                                         // No trend strength here
+        )
+    }
+
+    /// Update with OHLC data (close-only, matching `HMMRegimeDetector::update_ohlc`)
+    pub fn update_ohlc(&mut self, _high: f64, _low: f64, close: f64) -> RegimeConfidence {
+        self.update(close)
+    }
+
+    /// Softmax the per-model log-likelihoods, returning `(argmax, that
+    /// model's softmax weight)` as the classification confidence. Shifted
+    /// by the max log-likelihood before exponentiating to avoid overflow,
+    /// the same trick `HMMRegimeDetector::forward_step` uses for emissions.
+    fn softmax_argmax(log_likelihoods: &[f64]) -> (usize, f64) {
+        let max_ll = log_likelihoods.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = log_likelihoods.iter().map(|ll| (ll - max_ll).exp()).collect();
+        let total: f64 = weights.iter().sum();
+
+        let (best_idx, best_weight) = weights
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .unwrap();
+
+        let confidence = if total > 1e-300 {
+            best_weight / total
+        } else {
+            1.0 / weights.len() as f64
+        };
+        (best_idx, confidence)
+    }
+
+    /// Get current regime
+    pub fn current_regime(&self) -> MarketRegime {
+        self.current_regime
+    }
+
+    /// Check if `min_observations` prices have been seen, the point from
+    /// which `update` classifies (each candidate model has then seen one
+    /// return fewer than that)
+    pub fn is_ready(&self) -> bool {
+        self.n_observations >= self.min_observations
+    }
+
+    /// Per-candidate log-likelihoods, in the same order as `labels()`
+    pub fn model_log_likelihoods(&self) -> Vec<f64> {
+        self.models.iter().map(|m| m.log_likelihood()).collect()
+    }
+
+    /// Regime label for each candidate model, in `model_log_likelihoods()` order
+    pub fn labels(&self) -> &[MarketRegime] {
+        &self.labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_model_creation() {
+        let detector = MultiModelRegimeDetector::default_config();
+        assert!(!detector.is_ready());
+        assert_eq!(detector.labels().len(), 4);
+    }
+
+    #[test]
+    fn test_multi_model_warmup() {
+        let mut detector = MultiModelRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            ..HMMConfig::crypto_optimized()
+        });
+
+        let mut price = 100.0;
+        for _ in 0..30 {
+            price *= 1.002;
+            detector.update(price);
+        }
+
+        assert!(detector.is_ready());
+        assert_eq!(detector.model_log_likelihoods().len(), 4);
+    }
+
+    #[test]
+    fn test_multi_model_selects_bull_in_steady_uptrend() {
+        let mut detector = MultiModelRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            ..HMMConfig::crypto_optimized()
+        });
+
+        let mut price = 100.0;
+        let mut result = detector.update(price);
+        for _ in 0..100 {
+            price *= 1.003; // steady, low-variance gain - matches the bull prior
+            result = detector.update(price);
+        }
+
+        assert_eq!(result.regime, MarketRegime::Trending(TrendDirection::Bullish));
+        assert!(result.confidence > 0.0);
+    }
+
+    #[test]
+    fn test_multi_model_confidence_is_probability() {
+        let mut detector = MultiModelRegimeDetector::new(HMMConfig {
+            min_observations: 20,
+            ..HMMConfig::crypto_optimized()
+        });
+
+        let mut price = 100.0;
+        let mut result = detector.update(price);
+        for i in 0..50 {
+            let change = if i % 2 == 0 { 1.03 } else { 0.97 };
+            price *= change;
+            result = detector.update(price);
+        }
+
+        assert!(result.confidence >= 0.0 && result.confidence <= 1.0);
+    }
+}
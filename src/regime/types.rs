@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Market regime classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MarketRegime {
     /// Strong directional movement - use trend-following strategies
     /// Characteristics: High ADX (>25), price above/below MAs, clear momentum
@@ -17,13 +17,18 @@ pub enum MarketRegime {
     /// High volatility, no clear direction - reduce exposure or stay cash
     /// Characteristics: ATR expansion, wide Bollinger Bands, choppy price action
     Volatile,
-    
+
+    /// Low-volatility coiling that precedes a breakout - Bollinger Bands
+    /// have pulled inside the Keltner Channel. Reduce exposure until the
+    /// squeeze releases rather than trading the chop inside it.
+    Squeeze,
+
     /// Insufficient data or unclear signals - be cautious
     Uncertain,
 }
 
 /// Direction of trend when in Trending regime
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TrendDirection {
     Bullish,
     Bearish,
@@ -36,19 +41,37 @@ impl fmt::Display for MarketRegime {
             MarketRegime::Trending(TrendDirection::Bearish) => write!(f, "Trending (Bearish)"),
             MarketRegime::MeanReverting => write!(f, "Mean-Reverting"),
             MarketRegime::Volatile => write!(f, "Volatile/Choppy"),
+            MarketRegime::Squeeze => write!(f, "Squeeze (Pre-Breakout)"),
             MarketRegime::Uncertain => write!(f, "Uncertain"),
         }
     }
 }
 
 /// Confidence level in regime classification
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RegimeConfidence {
     pub regime: MarketRegime,
     pub confidence: f64,  // 0.0 to 1.0
     pub adx_value: f64,
     pub bb_width_percentile: f64,
     pub trend_strength: f64,
+    /// RSI reading behind the oscillator confirmation folded into
+    /// `confidence` - `0.0` for detectors that don't compute one
+    pub rsi_value: f64,
+    /// Stochastic %K reading behind the oscillator confirmation - `0.0`
+    /// for detectors that don't compute one
+    pub stochastic_k: f64,
+    /// Bollinger Bands are currently sitting inside the Keltner Channel -
+    /// the market is coiling ahead of a breakout
+    pub squeeze_active: bool,
+    /// Consecutive bars `squeeze_active` has held true - `0` once the
+    /// squeeze releases or for detectors that don't track one
+    pub bars_in_squeeze: usize,
+    /// Full state-occupancy posterior (`P(state_k | observations)` for each
+    /// hidden state `k`) behind `confidence`, for detectors that maintain
+    /// one - empty for detectors that only produce a single scalar
+    /// confidence. `confidence` is `state_posterior[argmax]` when present.
+    pub state_posterior: Vec<f64>,
 }
 
 impl RegimeConfidence {
@@ -59,9 +82,14 @@ impl RegimeConfidence {
             adx_value: 0.0,
             bb_width_percentile: 0.0,
             trend_strength: 0.0,
+            rsi_value: 0.0,
+            stochastic_k: 0.0,
+            squeeze_active: false,
+            bars_in_squeeze: 0,
+            state_posterior: Vec::new(),
         }
     }
-    
+
     pub fn with_metrics(
         regime: MarketRegime,
         confidence: f64,
@@ -75,9 +103,40 @@ impl RegimeConfidence {
             adx_value: adx,
             bb_width_percentile: bb_width,
             trend_strength,
+            rsi_value: 0.0,
+            stochastic_k: 0.0,
+            squeeze_active: false,
+            bars_in_squeeze: 0,
+            state_posterior: Vec::new(),
         }
     }
-    
+
+    /// Attach the momentum-oscillator readings that confirmed (or
+    /// penalized) `confidence`. Kept as a separate builder step rather than
+    /// growing `with_metrics` further, since the HMM and multi-model
+    /// detectors have no oscillators of their own to report.
+    pub fn with_oscillators(mut self, rsi_value: f64, stochastic_k: f64) -> Self {
+        self.rsi_value = rsi_value;
+        self.stochastic_k = stochastic_k;
+        self
+    }
+
+    /// Attach the Keltner-squeeze coil state so routers can act on the
+    /// coil-to-expansion transition without re-deriving it from `regime`.
+    pub fn with_squeeze(mut self, squeeze_active: bool, bars_in_squeeze: usize) -> Self {
+        self.squeeze_active = squeeze_active;
+        self.bars_in_squeeze = bars_in_squeeze;
+        self
+    }
+
+    /// Attach the full state-occupancy posterior behind `confidence`, for
+    /// probabilistic detectors (`HMMRegimeDetector`) that maintain one
+    /// rather than a single point estimate
+    pub fn with_posterior(mut self, state_posterior: Vec<f64>) -> Self {
+        self.state_posterior = state_posterior;
+        self
+    }
+
     /// Whether confidence is high enough to act on
     pub fn is_actionable(&self) -> bool {
         self.confidence >= 0.6
@@ -109,11 +168,43 @@ pub struct RegimeConfig {
     pub atr_period: usize,
     /// ATR expansion multiplier (current vs average) for volatile regime
     pub atr_expansion_threshold: f64,
-    
+
+    /// Keltner Channel ATR multiplier used by the `Squeeze` detector - a
+    /// squeeze is ON when the Bollinger Bands sit fully inside this channel
+    pub keltner_mult: f64,
+
+    /// ATR multiplier for the SuperTrend trailing bands (over `atr_period`)
+    /// - `classify_regime` only commits to `Trending` when this agrees with
+    /// the EMA-ordering direction
+    pub super_trend_mult: f64,
+
+    /// RSI period used for momentum-oscillator confirmation of `confidence`
+    pub rsi_period: usize,
+    /// Stochastic %K period used alongside RSI for oscillator confirmation
+    pub stochastic_period: usize,
+
     /// Lookback period for regime stability (avoid whipsaws)
     pub regime_stability_bars: usize,
     /// Minimum bars in current regime before switching
     pub min_regime_duration: usize,
+
+    /// Feed Heikin-Ashi smoothed candles into ADX/ATR/Bollinger/EMA instead
+    /// of raw OHLC - trades a bar of lag for noise-tolerant, "stickier"
+    /// trend regimes. Off by default since it changes what the reported
+    /// ATR/ADX values mean (they reflect HA candles, not raw price action).
+    pub use_heikin_ashi: bool,
+
+    /// Fast EMA period for the volume oscillator used by
+    /// `update_with_volume` to confirm breakouts
+    pub volume_osc_fast_period: usize,
+    /// Slow EMA period for the volume oscillator
+    pub volume_osc_slow_period: usize,
+
+    /// EMA-spread percentage above which `classify_regime` calls a trend
+    /// breakout (mirrors the `bb`/`atr` breakout thresholds above)
+    pub ema_spread_breakout_pct: f64,
+    /// EMA-spread percentage below which `classify_regime` favors ranging
+    pub ema_spread_ranging_pct: f64,
 }
 
 impl Default for RegimeConfig {
@@ -129,8 +220,17 @@ impl Default for RegimeConfig {
             ema_long_period: 200,
             atr_period: 14,
             atr_expansion_threshold: 1.5,
+            keltner_mult: 1.5,
+            super_trend_mult: 3.0,
+            rsi_period: 14,
+            stochastic_period: 14,
             regime_stability_bars: 3,
             min_regime_duration: 5,
+            use_heikin_ashi: false,
+            volume_osc_fast_period: 14,
+            volume_osc_slow_period: 28,
+            ema_spread_breakout_pct: 2.0,
+            ema_spread_ranging_pct: 1.0,
         }
     }
 }
@@ -149,11 +249,20 @@ impl RegimeConfig {
             ema_long_period: 50,
             atr_period: 14,
             atr_expansion_threshold: 1.3,  // Crypto is naturally volatile
+            keltner_mult: 1.25,  // Crypto's baseline ATR is wide, so a tighter channel still catches real squeezes
+            super_trend_mult: 3.0,
+            rsi_period: 14,
+            stochastic_period: 14,
             regime_stability_bars: 2,
             min_regime_duration: 3,
+            use_heikin_ashi: false,
+            volume_osc_fast_period: 14,
+            volume_osc_slow_period: 28,
+            ema_spread_breakout_pct: 2.0,
+            ema_spread_ranging_pct: 1.0,
         }
     }
-    
+
     /// Conservative config - requires stronger signals
     pub fn conservative() -> Self {
         Self {
@@ -167,32 +276,141 @@ impl RegimeConfig {
             ema_long_period: 200,
             atr_period: 14,
             atr_expansion_threshold: 2.0,
+            keltner_mult: 1.75,
+            super_trend_mult: 3.5,  // Wider band, fewer whipsaw flips for the conservative profile
+            rsi_period: 14,
+            stochastic_period: 14,
             regime_stability_bars: 5,
             min_regime_duration: 10,
+            use_heikin_ashi: false,
+            volume_osc_fast_period: 14,
+            volume_osc_slow_period: 28,
+            ema_spread_breakout_pct: 2.0,
+            ema_spread_ranging_pct: 1.0,
         }
     }
 }
 
+/// Threshold presets `RegimeDetector` switches between based on where
+/// price sits relative to a long EMA of close (its "macro trend"),
+/// mirroring multi-indicator systems that gate continuation trades behind
+/// a ~300-period EMA. `aligned` and `neutral` must use the same indicator
+/// periods (`adx_period`, `bb_period`, `ema_short_period`/`ema_long_period`,
+/// `atr_period`, etc.) since the detector's indicators are built once from
+/// this config and only its threshold fields are swapped bar to bar - a
+/// period mismatch between presets would silently desync the config from
+/// what the indicators are actually tracking.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveRegimeConfig {
+    /// Period of the macro-trend gating EMA
+    pub macro_ema_period: usize,
+    /// Band around the macro EMA, as a fraction of its value (e.g. `0.02`
+    /// = 2%), inside which price counts as oscillating around the macro
+    /// trend rather than decisively above or below it
+    pub macro_band_pct: f64,
+    /// Active while price sits beyond the band, above or below the macro
+    /// EMA - tightened trending thresholds so continuation trades aligned
+    /// with the macro bias confirm more readily
+    pub aligned: RegimeConfig,
+    /// Active while price oscillates within the band - loosened toward
+    /// mean-reversion parameters
+    pub neutral: RegimeConfig,
+}
+
+impl Default for AdaptiveRegimeConfig {
+    fn default() -> Self {
+        let mut aligned = RegimeConfig::default();
+        aligned.adx_trending_threshold = 18.0;
+        aligned.ema_spread_breakout_pct = 1.0;
+
+        let mut neutral = RegimeConfig::default();
+        neutral.adx_trending_threshold = 30.0;
+        neutral.adx_ranging_threshold = 22.0;
+        neutral.ema_spread_breakout_pct = 3.0;
+        neutral.ema_spread_ranging_pct = 1.5;
+
+        Self {
+            macro_ema_period: 300,
+            macro_band_pct: 0.02,
+            aligned,
+            neutral,
+        }
+    }
+}
+
+/// Direction of an open (or about-to-open) position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionDirection {
+    Long,
+    Short,
+}
+
+/// ATR-derived risk sizing for a recommended strategy - a stop-loss
+/// distance expressed as a multiple of ATR, and a take-profit target
+/// expressed as a reward:risk ratio off that stop distance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskParameters {
+    /// Stop-loss distance from entry, as a multiple of ATR
+    pub stop_atr_multiple: f64,
+    /// Take-profit distance, as a multiple of the stop distance
+    pub reward_risk_ratio: f64,
+}
+
 /// Recommended strategy for current regime
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum RecommendedStrategy {
-    /// Use trend-following (Golden Cross, EMA Pullback)
-    TrendFollowing,
-    /// Use mean reversion (Bollinger Bands)
-    MeanReversion,
-    /// Reduce position size, tight stops
-    ReducedExposure,
+    /// Use trend-following (Golden Cross, EMA Pullback) - wide trailing
+    /// stop to ride the move
+    TrendFollowing(RiskParameters),
+    /// Use mean reversion (Bollinger Bands) - tight stop targeting the
+    /// Bollinger midline
+    MeanReversion(RiskParameters),
+    /// Reduce position size, wide stop
+    ReducedExposure(RiskParameters),
     /// Stay in cash, wait for clarity
     StayCash,
 }
 
+impl RecommendedStrategy {
+    /// The risk parameters behind this recommendation - `None` for
+    /// `StayCash`, which takes no position to size a stop against
+    pub fn risk_parameters(&self) -> Option<RiskParameters> {
+        match self {
+            RecommendedStrategy::TrendFollowing(risk)
+            | RecommendedStrategy::MeanReversion(risk)
+            | RecommendedStrategy::ReducedExposure(risk) => Some(*risk),
+            RecommendedStrategy::StayCash => None,
+        }
+    }
+}
+
 impl From<&MarketRegime> for RecommendedStrategy {
     fn from(regime: &MarketRegime) -> Self {
         match regime {
-            MarketRegime::Trending(_) => RecommendedStrategy::TrendFollowing,
-            MarketRegime::MeanReverting => RecommendedStrategy::MeanReversion,
-            MarketRegime::Volatile => RecommendedStrategy::ReducedExposure,
+            MarketRegime::Trending(_) => RecommendedStrategy::TrendFollowing(RiskParameters {
+                stop_atr_multiple: 2.5,
+                reward_risk_ratio: 2.0,
+            }),
+            MarketRegime::MeanReverting => RecommendedStrategy::MeanReversion(RiskParameters {
+                stop_atr_multiple: 1.25,
+                reward_risk_ratio: 1.0,
+            }),
+            MarketRegime::Volatile => RecommendedStrategy::ReducedExposure(RiskParameters {
+                stop_atr_multiple: 3.5,
+                reward_risk_ratio: 1.5,
+            }),
+            MarketRegime::Squeeze => RecommendedStrategy::ReducedExposure(RiskParameters {
+                stop_atr_multiple: 2.0,
+                reward_risk_ratio: 1.5,
+            }),
             MarketRegime::Uncertain => RecommendedStrategy::StayCash,
         }
     }
 }
+
+/// Concrete stop-loss/take-profit prices for one side of a trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StopTakeProfitLevels {
+    pub stop_loss: f64,
+    pub take_profit: f64,
+}
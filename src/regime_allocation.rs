@@ -0,0 +1,218 @@
+//! Regime-Conditioned Target Allocation
+//!
+//! `EnsembleRegimeDetector` only classifies - it has no notion of how much
+//! capital a given regime call should actually put to work. `RegimeAllocator`
+//! closes that gap, turning an `EnsembleResult` into a concrete target
+//! exposure under a user-supplied `AllocationPolicy`, following the same
+//! top-down rebalancing shape `PortfolioAllocator` uses for cross-asset
+//! sizing: compute strict per-regime min/max limits, scale the target
+//! exposure by confidence within those limits, and suppress any change
+//! smaller than `min_trade_volume` to avoid churn.
+
+use std::collections::HashMap;
+
+use crate::regime::{EnsembleResult, MarketRegime};
+
+/// A single regime's target risk weight with hard bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegimeLimits {
+    /// Risk weight to target at full confidence, in `[min_weight, max_weight]`
+    pub target_weight: f64,
+    /// Minimum risk weight this regime may hold
+    pub min_weight: f64,
+    /// Maximum risk weight this regime may hold
+    pub max_weight: f64,
+}
+
+impl RegimeLimits {
+    /// `target_weight` is clamped into `[min_weight, max_weight]` so a
+    /// misconfigured policy can't exceed its own bounds
+    pub fn new(target_weight: f64, min_weight: f64, max_weight: f64) -> Self {
+        Self {
+            target_weight: target_weight.clamp(min_weight, max_weight),
+            min_weight,
+            max_weight,
+        }
+    }
+}
+
+/// Maps each `MarketRegime` the ensemble can call to its `RegimeLimits`,
+/// plus the de-risking and churn-suppression knobs `RegimeAllocator` uses
+#[derive(Debug, Clone)]
+pub struct AllocationPolicy {
+    limits: HashMap<MarketRegime, RegimeLimits>,
+    /// Limits used whenever the panel disagrees or falls below
+    /// `agreement_threshold` - normally the most conservative entry
+    pub uncertain: RegimeLimits,
+    /// Below this confidence, or whenever `EnsembleResult::methods_agree`
+    /// is false, the allocator de-risks to `uncertain` instead of trusting
+    /// the voted regime's own limits
+    pub agreement_threshold: f64,
+    /// Target changes smaller than this fraction of total risk weight are
+    /// suppressed to avoid churn
+    pub min_trade_volume: f64,
+}
+
+impl AllocationPolicy {
+    pub fn new(uncertain: RegimeLimits, agreement_threshold: f64, min_trade_volume: f64) -> Self {
+        Self {
+            limits: HashMap::new(),
+            uncertain,
+            agreement_threshold,
+            min_trade_volume,
+        }
+    }
+
+    /// Register (or replace) the limits for a specific regime
+    pub fn with_regime(mut self, regime: MarketRegime, limits: RegimeLimits) -> Self {
+        self.limits.insert(regime, limits);
+        self
+    }
+
+    fn limits_for(&self, regime: MarketRegime) -> RegimeLimits {
+        self.limits.get(&regime).copied().unwrap_or(self.uncertain)
+    }
+}
+
+/// A concrete target exposure derived from an `EnsembleResult`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetAllocation {
+    /// The regime the target exposure was sized for - `Uncertain` whenever
+    /// `de_risked` is set, regardless of what the panel actually voted
+    pub regime: MarketRegime,
+    /// Target risk weight, already clamped to its regime's limits
+    pub target_weight: f64,
+    /// Whether the panel's call was untrusted (disagreement or low
+    /// confidence) and the allocator fell back to `AllocationPolicy::uncertain`
+    pub de_risked: bool,
+}
+
+/// Bridges `EnsembleRegimeDetector` output to position sizing under an
+/// `AllocationPolicy`
+#[derive(Debug, Clone)]
+pub struct RegimeAllocator {
+    policy: AllocationPolicy,
+    current_weight: f64,
+}
+
+impl RegimeAllocator {
+    pub fn new(policy: AllocationPolicy) -> Self {
+        Self {
+            policy,
+            current_weight: 0.0,
+        }
+    }
+
+    /// Record the portfolio's current risk weight for this instrument, used
+    /// to suppress sub-`min_trade_volume` rebalances
+    pub fn set_current_weight(&mut self, weight: f64) {
+        self.current_weight = weight;
+    }
+
+    /// Compute the target exposure for `result`.
+    ///
+    /// When the panel disagrees or `result.confidence` is below
+    /// `agreement_threshold`, sizing falls back to
+    /// `AllocationPolicy::uncertain` instead of the voted regime's limits.
+    /// Otherwise the target is `min_weight + (target_weight - min_weight) *
+    /// confidence`, clamped to `[min_weight, max_weight]` - full confidence
+    /// reaches the regime's target weight, low confidence decays toward its
+    /// floor. Returns `None` when the resulting change from the current
+    /// weight is smaller than `min_trade_volume`.
+    pub fn allocate(&self, result: &EnsembleResult) -> Option<TargetAllocation> {
+        let trusted = result.methods_agree && result.confidence >= self.policy.agreement_threshold;
+        let (regime, limits, de_risked) = if trusted {
+            (result.regime, self.policy.limits_for(result.regime), false)
+        } else {
+            (MarketRegime::Uncertain, self.policy.uncertain, true)
+        };
+
+        let raw = limits.min_weight + (limits.target_weight - limits.min_weight) * result.confidence;
+        let target_weight = raw.clamp(limits.min_weight, limits.max_weight);
+
+        if (target_weight - self.current_weight).abs() < self.policy.min_trade_volume {
+            return None;
+        }
+
+        Some(TargetAllocation {
+            regime,
+            target_weight,
+            de_risked,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::{RegimeConfidence, TrendDirection};
+
+    fn ensemble_result(regime: MarketRegime, confidence: f64, methods_agree: bool) -> EnsembleResult {
+        EnsembleResult {
+            regime,
+            confidence,
+            methods_agree,
+            source_results: vec![RegimeConfidence::new(regime, confidence)],
+        }
+    }
+
+    fn policy() -> AllocationPolicy {
+        AllocationPolicy::new(RegimeLimits::new(0.0, 0.0, 0.1), 0.6, 0.02)
+            .with_regime(MarketRegime::Trending(TrendDirection::Bullish), RegimeLimits::new(0.8, 0.1, 1.0))
+            .with_regime(MarketRegime::Volatile, RegimeLimits::new(0.1, 0.0, 0.2))
+    }
+
+    #[test]
+    fn test_full_confidence_reaches_regime_target_weight() {
+        let allocator = RegimeAllocator::new(policy());
+        let result = ensemble_result(MarketRegime::Trending(TrendDirection::Bullish), 1.0, true);
+
+        let target = allocator.allocate(&result).unwrap();
+        assert!(!target.de_risked);
+        assert!((target.target_weight - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_disagreement_de_risks_to_uncertain_limits() {
+        let mut allocator = RegimeAllocator::new(policy());
+        // Start from an open position so de-risking to the uncertain limits
+        // is a change above min_trade_volume rather than a suppressed no-op
+        allocator.set_current_weight(0.5);
+        let result = ensemble_result(MarketRegime::Trending(TrendDirection::Bullish), 0.9, false);
+
+        let target = allocator.allocate(&result).unwrap();
+        assert!(target.de_risked);
+        assert_eq!(target.regime, MarketRegime::Uncertain);
+        assert!(target.target_weight <= 0.1 + 1e-9);
+    }
+
+    #[test]
+    fn test_low_confidence_de_risks_even_when_methods_agree() {
+        let mut allocator = RegimeAllocator::new(policy());
+        allocator.set_current_weight(0.5);
+        let result = ensemble_result(MarketRegime::Volatile, 0.3, true);
+
+        let target = allocator.allocate(&result).unwrap();
+        assert!(target.de_risked);
+        assert_eq!(target.regime, MarketRegime::Uncertain);
+    }
+
+    #[test]
+    fn test_sub_threshold_change_is_suppressed() {
+        let mut allocator = RegimeAllocator::new(policy());
+        allocator.set_current_weight(0.79);
+        let result = ensemble_result(MarketRegime::Trending(TrendDirection::Bullish), 1.0, true);
+
+        assert!(allocator.allocate(&result).is_none());
+    }
+
+    #[test]
+    fn test_unregistered_regime_falls_back_to_uncertain_limits() {
+        let mut allocator = RegimeAllocator::new(policy());
+        allocator.set_current_weight(0.5);
+        let result = ensemble_result(MarketRegime::MeanReverting, 1.0, true);
+
+        let target = allocator.allocate(&result).unwrap();
+        assert!(target.target_weight <= 0.1 + 1e-9);
+    }
+}
@@ -0,0 +1,186 @@
+//! Position Sizing
+//!
+//! `examples/backtest.rs` previously hard-coded every entry's notional as
+//! `equity * 0.01 * action.size_factor`, baking "risk 1% of equity" into
+//! the backtest loop itself. `OrderSizer` pulls that decision out into a
+//! pluggable strategy, selectable independently of the router's own
+//! `position_size_factor` (which remains a multiplier applied on top of
+//! whatever the sizer returns), so different sizing regimes can be
+//! compared side by side in the results table.
+
+/// Inputs available to an `OrderSizer` at entry time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizingContext {
+    pub equity: f64,
+    pub entry_price: f64,
+    pub stop_loss: Option<f64>,
+    /// Router-level `position_size_factor`, applied as a multiplier on top
+    /// of the sizer's own output rather than folded into it
+    pub size_factor: f64,
+    /// ATR at entry, used as the stop distance by `VolatilityTargetSizer`
+    /// when no explicit `stop_loss` was set
+    pub atr: Option<f64>,
+    /// Rolling win rate over recent closed trades, used by `KellySizer`
+    pub recent_win_rate: f64,
+    /// Rolling average winning/losing trade return (both positive), used
+    /// by `KellySizer` to estimate the win/loss odds
+    pub recent_avg_win_pct: f64,
+    pub recent_avg_loss_pct: f64,
+}
+
+/// Produces the notional (in `equity`'s currency) committed to a new position
+pub trait OrderSizer: std::fmt::Debug {
+    fn size(&self, ctx: &SizingContext) -> f64;
+}
+
+/// Reproduces the backtest's original hard-coded sizing: a fixed fraction
+/// of equity, scaled by the router's `size_factor`. This is the default.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedFractional {
+    pub risk_fraction: f64,
+}
+
+impl Default for FixedFractional {
+    fn default() -> Self {
+        Self { risk_fraction: 0.01 }
+    }
+}
+
+impl OrderSizer for FixedFractional {
+    fn size(&self, ctx: &SizingContext) -> f64 {
+        ctx.equity * self.risk_fraction * ctx.size_factor
+    }
+}
+
+/// Sizes so a stop-out loses about `target_risk_fraction` of equity:
+/// `stop_distance * units ~= target_risk_fraction * equity`, where
+/// `stop_distance` comes from the position's own stop-loss, falling back
+/// to `ctx.atr` when no stop was set.
+#[derive(Debug, Clone, Copy)]
+pub struct VolatilityTargetSizer {
+    pub target_risk_fraction: f64,
+}
+
+impl Default for VolatilityTargetSizer {
+    fn default() -> Self {
+        Self { target_risk_fraction: 0.01 }
+    }
+}
+
+impl OrderSizer for VolatilityTargetSizer {
+    fn size(&self, ctx: &SizingContext) -> f64 {
+        let stop_distance = match ctx.stop_loss {
+            Some(stop) => (ctx.entry_price - stop).abs(),
+            None => ctx.atr.unwrap_or(0.0),
+        };
+        if stop_distance <= 0.0 || ctx.entry_price <= 0.0 {
+            return 0.0;
+        }
+        let stop_distance_pct = stop_distance / ctx.entry_price;
+        ctx.equity * self.target_risk_fraction / stop_distance_pct * ctx.size_factor
+    }
+}
+
+/// Capped fractional-Kelly sizer: `f = clamp(edge/odds, 0, kelly_cap)`,
+/// with `odds = recent_avg_win_pct / recent_avg_loss_pct` and
+/// `edge = recent_win_rate * odds - (1 - recent_win_rate)`.
+#[derive(Debug, Clone, Copy)]
+pub struct KellySizer {
+    pub kelly_cap: f64,
+}
+
+impl Default for KellySizer {
+    fn default() -> Self {
+        Self { kelly_cap: 0.25 }
+    }
+}
+
+impl OrderSizer for KellySizer {
+    fn size(&self, ctx: &SizingContext) -> f64 {
+        if ctx.recent_avg_loss_pct <= 0.0 {
+            return 0.0;
+        }
+        let odds = ctx.recent_avg_win_pct / ctx.recent_avg_loss_pct;
+        let p = ctx.recent_win_rate;
+        let edge = p * odds - (1.0 - p);
+        let fraction = (edge / odds).clamp(0.0, self.kelly_cap);
+        ctx.equity * fraction * ctx.size_factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> SizingContext {
+        SizingContext {
+            equity: 10_000.0,
+            entry_price: 100.0,
+            stop_loss: None,
+            size_factor: 1.0,
+            atr: None,
+            recent_win_rate: 0.0,
+            recent_avg_win_pct: 0.0,
+            recent_avg_loss_pct: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_fixed_fractional_reproduces_one_percent_risk() {
+        let sizer = FixedFractional::default();
+        assert_eq!(sizer.size(&ctx()), 100.0);
+    }
+
+    #[test]
+    fn test_fixed_fractional_scales_with_size_factor() {
+        let sizer = FixedFractional::default();
+        let c = SizingContext { size_factor: 0.5, ..ctx() };
+        assert_eq!(sizer.size(&c), 50.0);
+    }
+
+    #[test]
+    fn test_volatility_target_sizes_to_the_explicit_stop() {
+        let sizer = VolatilityTargetSizer { target_risk_fraction: 0.02 };
+        let c = SizingContext { stop_loss: Some(95.0), ..ctx() };
+        // 5% stop distance, 2% target risk -> 0.02 / 0.05 * equity = 0.4 * equity
+        assert!((sizer.size(&c) - 4_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volatility_target_falls_back_to_atr_without_a_stop() {
+        let sizer = VolatilityTargetSizer::default();
+        let c = SizingContext { atr: Some(2.0), ..ctx() };
+        // 2% ATR distance, 1% target risk -> 0.01 / 0.02 * equity = 0.5 * equity
+        assert!((sizer.size(&c) - 5_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volatility_target_is_zero_with_no_stop_distance_available() {
+        let sizer = VolatilityTargetSizer::default();
+        assert_eq!(sizer.size(&ctx()), 0.0);
+    }
+
+    #[test]
+    fn test_kelly_sizer_is_zero_with_no_edge() {
+        let sizer = KellySizer::default();
+        let c = SizingContext {
+            recent_win_rate: 0.4,
+            recent_avg_win_pct: 0.01,
+            recent_avg_loss_pct: 0.01,
+            ..ctx()
+        };
+        assert_eq!(sizer.size(&c), 0.0);
+    }
+
+    #[test]
+    fn test_kelly_sizer_caps_a_strong_edge_at_kelly_cap() {
+        let sizer = KellySizer { kelly_cap: 0.25 };
+        let c = SizingContext {
+            recent_win_rate: 0.9,
+            recent_avg_win_pct: 0.05,
+            recent_avg_loss_pct: 0.01,
+            ..ctx()
+        };
+        assert!((sizer.size(&c) - 2_500.0).abs() < 1e-9);
+    }
+}
@@ -16,12 +16,17 @@ use crate::regime::{
     HMMRegimeDetector, HMMConfig,
     EnsembleRegimeDetector, EnsembleConfig, EnsembleResult,
 };
+use crate::strategy::enhanced_sizing::{FixedFactor, OrderSizeStrategy};
 use crate::strategy::mean_reversion::{MeanReversionStrategy, MeanReversionConfig, Signal};
+use crate::strategy::risk_model::{RiskModel, RiskModelConfig, RiskSizing};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+/// Bars of trailing returns kept per asset for sizers like `VolatilityTarget`
+const RETURN_WINDOW: usize = 30;
 
 /// Which detection method to use
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DetectionMethod {
     /// Technical indicators (ADX, BB, ATR) - fast, rule-based
     Indicators,
@@ -56,13 +61,16 @@ pub struct EnhancedRouterConfig {
     
     /// Mean reversion strategy config
     pub mean_reversion_config: MeanReversionConfig,
-    
-    /// Position size in volatile markets
-    pub volatile_position_factor: f64,
-    
+
     /// Minimum confidence to trade
     pub min_confidence: f64,
-    
+
+    /// When `Ensemble` detection methods disagree, the confidence fed to the
+    /// `order_size_strategy` is multiplied by this factor before sizing -
+    /// lets a `ConfidenceWeighted` sizer down-weight disagreement without
+    /// the trait needing a `methods_agree` parameter of its own
+    pub ensemble_disagreement_penalty: f64,
+
     /// Log regime changes
     pub log_changes: bool,
 }
@@ -75,8 +83,8 @@ impl Default for EnhancedRouterConfig {
             hmm_config: Some(HMMConfig::crypto_optimized()),
             ensemble_config: Some(EnsembleConfig::default()),
             mean_reversion_config: MeanReversionConfig::default(),
-            volatile_position_factor: 0.5,
             min_confidence: 0.5,
+            ensemble_disagreement_penalty: 0.5,
             log_changes: true,
         }
     }
@@ -111,7 +119,17 @@ pub struct EnhancedSignal {
     pub reason: String,
     pub stop_loss: Option<f64>,
     pub take_profit: Option<f64>,
-    
+
+    /// Required margin to hold one unit of the asset for this trade
+    /// (`entry / leverage`; trend-following signals only; `None` for
+    /// mean-reversion/no-trade)
+    pub margin: Option<f64>,
+    /// Price at which this position would be force-liquidated at the
+    /// configured leverage (trend-following signals only)
+    pub liquidation_price: Option<f64>,
+    /// Leverage used to compute `margin`/`liquidation_price`
+    pub leverage: Option<f64>,
+
     /// Which detection method produced this
     pub detection_method: DetectionMethod,
     
@@ -132,6 +150,28 @@ enum Detector {
     Ensemble(EnsembleRegimeDetector),
 }
 
+/// Build a fresh `Detector` for `config.detection_method`. A free function
+/// (rather than an `EnhancedRouter` method) so `set_detection_method` can
+/// call it while holding a mutable borrow of `self.assets`.
+fn new_detector(config: &EnhancedRouterConfig) -> Detector {
+    match config.detection_method {
+        DetectionMethod::Indicators => {
+            Detector::Indicator(RegimeDetector::new(config.indicator_config.clone()))
+        }
+        DetectionMethod::HMM => {
+            let hmm_config = config.hmm_config.clone().unwrap_or_default();
+            Detector::HMM(HMMRegimeDetector::new(hmm_config))
+        }
+        DetectionMethod::Ensemble => {
+            let ens_config = config.ensemble_config.clone().unwrap_or_default();
+            Detector::Ensemble(EnsembleRegimeDetector::new(
+                ens_config,
+                config.indicator_config.clone(),
+            ))
+        }
+    }
+}
+
 /// Per-asset state
 struct AssetState {
     detector: Detector,
@@ -139,12 +179,47 @@ struct AssetState {
     current_strategy: ActiveStrategy,
     last_regime: MarketRegime,
     regime_change_count: u32,
+    /// Direction, confidence and position factor from the most recent
+    /// `EnhancedSignal`, used by `PortfolioAllocator` to size this asset
+    /// without re-running detection.
+    last_signal: Option<(Signal, f64, f64)>,
+    /// Trailing close-to-close returns, capped at `RETURN_WINDOW` bars, fed
+    /// to `order_size_strategy` for sizers like `VolatilityTarget`.
+    recent_returns: VecDeque<f64>,
+    last_close: Option<f64>,
+    /// `Command::Pause` halts `update()` for this symbol without dropping
+    /// its detector state, so `Command::Resume` picks back up where it left
+    /// off.
+    paused: bool,
+    /// Whether `is_ready()` has already been observed `true` for this
+    /// asset, so `update()` fires `EngineEvent::WarmupComplete` exactly once.
+    warmup_complete: bool,
 }
 
 /// Enhanced Strategy Router
 pub struct EnhancedRouter {
     config: EnhancedRouterConfig,
     assets: HashMap<String, AssetState>,
+    /// Optional portfolio-level allocator that throttles `position_factor`
+    /// across the whole book so correlated regime changes can't size every
+    /// asset at 100% simultaneously.
+    allocator: Option<PortfolioAllocator>,
+    /// Computes `position_factor` from regime confidence, HMM state
+    /// probabilities and trailing returns. Defaults to `FixedFactor`, which
+    /// reproduces the router's original hard-coded sizing.
+    order_size_strategy: Box<dyn OrderSizeStrategy>,
+    /// Computes margin, liquidation price and ATR-scaled stop/target for
+    /// trend-following signals
+    risk_model: RiskModel,
+    /// `Command::FlattenAll` halts every asset until individually resumed;
+    /// a per-symbol pause (`Command::Pause`) is layered on top of this.
+    paused_all: bool,
+    /// `RegimeChanged`/`WarmupComplete` events queued by `update()`, forced
+    /// holds queued by `flatten_all()`, and `Error`s queued by
+    /// `EnhancedRouterActor` for commands naming an unregistered symbol,
+    /// drained by `drain_events()` (called
+    /// directly, or by `EnhancedRouterActor::run` after every tick/command).
+    pending_events: VecDeque<EngineEvent>,
 }
 
 impl EnhancedRouter {
@@ -153,9 +228,50 @@ impl EnhancedRouter {
         Self {
             config,
             assets: HashMap::new(),
+            allocator: None,
+            order_size_strategy: Box::new(FixedFactor::default()),
+            risk_model: RiskModel::default_config(),
+            paused_all: false,
+            pending_events: VecDeque::new(),
         }
     }
-    
+
+    /// Attach a `PortfolioAllocator` so `update()` throttles each asset's
+    /// `position_factor` to stay within the allocator's book-wide limits.
+    pub fn with_portfolio_allocator(mut self, allocator: PortfolioAllocator) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Replace (or clear) the attached allocator
+    pub fn set_portfolio_allocator(&mut self, allocator: Option<PortfolioAllocator>) {
+        self.allocator = allocator;
+    }
+
+    /// Swap in a different order-sizing strategy (default: `FixedFactor`)
+    pub fn with_order_size_strategy(mut self, strategy: Box<dyn OrderSizeStrategy>) -> Self {
+        self.order_size_strategy = strategy;
+        self
+    }
+
+    /// Swap in a different order-sizing strategy (default: `FixedFactor`)
+    pub fn set_order_size_strategy(&mut self, strategy: Box<dyn OrderSizeStrategy>) {
+        self.order_size_strategy = strategy;
+    }
+
+    /// Swap in a different leverage/margin configuration (default:
+    /// `RiskModelConfig::default()`, 1x leverage)
+    pub fn with_risk_model_config(mut self, config: RiskModelConfig) -> Self {
+        self.risk_model = RiskModel::new(config);
+        self
+    }
+
+    /// Swap in a different leverage/margin configuration (default:
+    /// `RiskModelConfig::default()`, 1x leverage)
+    pub fn set_risk_model_config(&mut self, config: RiskModelConfig) {
+        self.risk_model = RiskModel::new(config);
+    }
+
     /// Create with indicator-based detection
     pub fn with_indicators() -> Self {
         Self::new(EnhancedRouterConfig {
@@ -187,58 +303,142 @@ impl EnhancedRouter {
         if self.assets.contains_key(symbol) {
             return;
         }
-        
-        let detector = match self.config.detection_method {
-            DetectionMethod::Indicators => {
-                Detector::Indicator(RegimeDetector::new(self.config.indicator_config.clone()))
-            }
-            DetectionMethod::HMM => {
-                let hmm_config = self.config.hmm_config.clone().unwrap_or_default();
-                Detector::HMM(HMMRegimeDetector::new(hmm_config))
-            }
-            DetectionMethod::Ensemble => {
-                let ens_config = self.config.ensemble_config.clone().unwrap_or_default();
-                Detector::Ensemble(EnsembleRegimeDetector::new(
-                    ens_config,
-                    self.config.indicator_config.clone(),
-                ))
-            }
-        };
-        
+
         self.assets.insert(symbol.to_string(), AssetState {
-            detector,
+            detector: new_detector(&self.config),
             mean_reversion: MeanReversionStrategy::new(self.config.mean_reversion_config.clone()),
             current_strategy: ActiveStrategy::NoTrade,
             last_regime: MarketRegime::Uncertain,
             regime_change_count: 0,
+            last_signal: None,
+            recent_returns: VecDeque::with_capacity(RETURN_WINDOW),
+            last_close: None,
+            paused: false,
+            warmup_complete: false,
         });
     }
-    
+
+    /// Drop a registered asset and all of its detector/warmup state.
+    /// `Command::UnregisterAsset` maps directly onto this.
+    pub fn unregister_asset(&mut self, symbol: &str) {
+        self.assets.remove(symbol);
+    }
+
+    /// Switch detection method at runtime. Every currently registered asset
+    /// gets a freshly built detector for the new method (existing warmup
+    /// progress is lost - `Command::SetDetectionMethod` trades continuity
+    /// for the ability to flip methods without a restart).
+    pub fn set_detection_method(&mut self, method: DetectionMethod) {
+        self.config.detection_method = method;
+        let config = self.config.clone();
+        for state in self.assets.values_mut() {
+            state.detector = new_detector(&config);
+            state.warmup_complete = false;
+        }
+    }
+
+    /// Pause a symbol: `update()` returns `None` and leaves its detector
+    /// state untouched until `resume()`.
+    pub fn pause(&mut self, symbol: &str) {
+        if let Some(state) = self.assets.get_mut(symbol) {
+            state.paused = true;
+        }
+    }
+
+    /// Clear a per-symbol pause (a `flatten_all()` pause-all still applies
+    /// until `resume_all()`).
+    pub fn resume(&mut self, symbol: &str) {
+        if let Some(state) = self.assets.get_mut(symbol) {
+            state.paused = false;
+        }
+    }
+
+    /// Whether `symbol` is currently paused, either individually or via
+    /// `flatten_all()`.
+    pub fn is_paused(&self, symbol: &str) -> bool {
+        self.paused_all || self.assets.get(symbol).map(|s| s.paused).unwrap_or(false)
+    }
+
+    /// Halt every asset and queue a forced `Signal::Hold` `EngineEvent` for
+    /// each one that was last signaling a live position, so downstream
+    /// execution flattens out. Individual assets stay halted until
+    /// `resume_all()` (or their own `resume()`, once `resume_all()` is called).
+    pub fn flatten_all(&mut self) {
+        self.paused_all = true;
+        for (symbol, state) in self.assets.iter() {
+            let Some((last_signal, confidence, _)) = state.last_signal else {
+                continue;
+            };
+            if last_signal == Signal::Hold {
+                continue;
+            }
+            self.pending_events.push_back(EngineEvent::Signal {
+                symbol: symbol.clone(),
+                signal: EnhancedSignal {
+                    signal: Signal::Hold,
+                    strategy: ActiveStrategy::NoTrade,
+                    regime: state.last_regime,
+                    confidence,
+                    position_factor: 0.0,
+                    reason: "Flattened by FlattenAll command".into(),
+                    stop_loss: None,
+                    take_profit: None,
+                    margin: None,
+                    liquidation_price: None,
+                    leverage: None,
+                    detection_method: self.config.detection_method,
+                    methods_agree: None,
+                    state_probabilities: None,
+                    expected_duration: None,
+                },
+            });
+        }
+    }
+
+    /// Clear `flatten_all()`'s pause-all flag; per-symbol pauses still apply.
+    pub fn resume_all(&mut self) {
+        self.paused_all = false;
+    }
+
+    /// Drain every `EngineEvent` queued since the last call - regime changes,
+    /// warmup-complete notifications and `flatten_all()`'s forced holds.
+    /// `EnhancedRouterActor::run` calls this after every tick/command so
+    /// nothing but the actor needs to care about the internal queue.
+    pub fn drain_events(&mut self) -> Vec<EngineEvent> {
+        self.pending_events.drain(..).collect()
+    }
+
     /// Update with new OHLC data
     pub fn update(&mut self, symbol: &str, high: f64, low: f64, close: f64) -> Option<EnhancedSignal> {
         if !self.assets.contains_key(symbol) {
             self.register_asset(symbol);
         }
-        
+
+        if self.is_paused(symbol) {
+            return None;
+        }
+
         let state = self.assets.get_mut(symbol)?;
-        
+
         // Get regime from appropriate detector
-        let (regime_result, methods_agree, state_probs, expected_duration) = match &mut state.detector {
+        let (regime_result, methods_agree, state_probs, expected_duration, atr) = match &mut state.detector {
             Detector::Indicator(det) => {
                 let result = det.update(high, low, close);
-                (result, None, None, None)
+                let atr = det.atr_value();
+                (result, None, None, None, atr)
             }
             Detector::HMM(det) => {
                 let result = det.update_ohlc(high, low, close);
                 let probs = det.state_probabilities().to_vec();
                 let duration = det.expected_regime_duration(det.current_state_index());
-                (result, None, Some(probs), Some(duration))
+                (result, None, Some(probs), Some(duration), None)
             }
             Detector::Ensemble(det) => {
                 let ens_result = det.update(high, low, close);
-                let probs = det.hmm_state_probabilities().to_vec();
+                let probs = det.hmm_state_probabilities();
                 let duration = det.expected_regime_duration();
-                (ens_result.to_regime_confidence(), Some(ens_result.methods_agree), Some(probs), Some(duration))
+                let atr = det.atr_value();
+                (ens_result.to_regime_confidence(), Some(ens_result.methods_agree), probs, duration, atr)
             }
         };
         
@@ -246,28 +446,70 @@ impl EnhancedRouter {
         if regime_result.regime != state.last_regime {
             state.regime_change_count += 1;
             if self.config.log_changes {
-                println!(
-                    "[{}] Regime change #{} ({:?}): {} → {} (conf: {:.2})",
-                    symbol,
-                    state.regime_change_count,
-                    self.config.detection_method,
-                    state.last_regime,
-                    regime_result.regime,
-                    regime_result.confidence
-                );
+                self.pending_events.push_back(EngineEvent::RegimeChanged {
+                    symbol: symbol.to_string(),
+                    from: state.last_regime,
+                    to: regime_result.regime,
+                    confidence: regime_result.confidence,
+                    detection_method: self.config.detection_method,
+                });
             }
             state.last_regime = regime_result.regime;
         }
-        
+
+        // Fire a one-time warmup-complete event the first bar each
+        // detector's own `is_ready()` flips true.
+        let is_ready_now = match &state.detector {
+            Detector::Indicator(d) => d.is_ready(),
+            Detector::HMM(d) => d.is_ready(),
+            Detector::Ensemble(d) => d.is_ready(),
+        };
+        if is_ready_now && !state.warmup_complete {
+            state.warmup_complete = true;
+            self.pending_events.push_back(EngineEvent::WarmupComplete {
+                symbol: symbol.to_string(),
+            });
+        }
+
+        // Track trailing returns for sizers like `VolatilityTarget`
+        if let Some(last_close) = state.last_close {
+            if last_close > 0.0 {
+                if state.recent_returns.len() == RETURN_WINDOW {
+                    state.recent_returns.pop_front();
+                }
+                state.recent_returns.push_back((close - last_close) / last_close);
+            }
+        }
+        state.last_close = Some(close);
+
         // Select strategy based on regime
-        let (strategy, position_factor) = self.select_strategy(&regime_result);
+        let strategy = Self::select_strategy(self.config.min_confidence, &regime_result);
         state.current_strategy = strategy;
-        
+
+        // Sizing confidence is discounted when Ensemble detection methods
+        // disagree, so a `ConfidenceWeighted` sizer naturally trades smaller
+        // without needing to know about `methods_agree` itself.
+        let sizing_confidence = match methods_agree {
+            Some(false) => regime_result.confidence * self.config.ensemble_disagreement_penalty,
+            _ => regime_result.confidence,
+        };
+        let sizing_regime = RegimeConfidence {
+            confidence: sizing_confidence,
+            ..regime_result.clone()
+        };
+        let position_factor = if strategy == ActiveStrategy::NoTrade {
+            0.0
+        } else {
+            let returns: Vec<f64> = state.recent_returns.iter().copied().collect();
+            self.order_size_strategy
+                .size(&sizing_regime, state_probs.as_deref(), &returns)
+        };
+
         // Generate signal
-        let (signal, reason, stop_loss, take_profit) = match strategy {
+        let (signal, reason, stop_loss, take_profit, risk_sizing) = match strategy {
             ActiveStrategy::TrendFollowing => {
-                // Simplified trend signal - integrate with your existing strategies
-                self.trend_signal(&regime_result, close)
+                let (signal, reason, sizing) = Self::trend_signal(&self.risk_model, &regime_result, close, atr);
+                (signal, reason, sizing.map(|s| s.stop_loss), sizing.map(|s| s.take_profit), sizing)
             }
             ActiveStrategy::MeanReversion => {
                 let mr_signal = state.mean_reversion.update(high, low, close);
@@ -275,18 +517,26 @@ impl EnhancedRouter {
                 let rsi = state.mean_reversion.last_rsi();
                 (
                     mr_signal,
-                    format!("MeanRev: %B={:.2} RSI={:.0}", 
+                    format!("MeanRev: %B={:.2} RSI={:.0}",
                             bb.map(|b| b.percent_b).unwrap_or(0.5),
                             rsi.unwrap_or(50.0)),
                     state.mean_reversion.stop_loss(),
                     state.mean_reversion.take_profit(),
+                    None,
                 )
             }
             ActiveStrategy::NoTrade => {
-                (Signal::Hold, "Uncertain - staying out".into(), None, None)
+                (Signal::Hold, "Uncertain - staying out".into(), None, None, None)
             }
         };
-        
+
+        state.last_signal = Some((signal, regime_result.confidence, position_factor));
+
+        let position_factor = match &self.allocator {
+            Some(allocator) => allocator.scale_factor(symbol, &self.assets) * position_factor,
+            None => position_factor,
+        };
+
         Some(EnhancedSignal {
             signal,
             strategy,
@@ -296,53 +546,79 @@ impl EnhancedRouter {
             reason,
             stop_loss,
             take_profit,
+            margin: risk_sizing.map(|s| s.margin),
+            liquidation_price: risk_sizing.map(|s| s.liquidation_price),
+            leverage: risk_sizing.map(|s| s.leverage),
             detection_method: self.config.detection_method,
             methods_agree,
             state_probabilities: state_probs,
             expected_duration,
         })
     }
+
+    /// Rebalance `target_net_value` across every registered asset using the
+    /// attached `PortfolioAllocator`. Returns an empty map if no allocator is
+    /// attached.
+    pub fn rebalance(&mut self, target_net_value: f64) -> HashMap<String, f64> {
+        match &self.allocator {
+            Some(allocator) => allocator.rebalance(target_net_value, &self.assets),
+            None => HashMap::new(),
+        }
+    }
     
-    /// Select strategy based on regime
-    fn select_strategy(&self, regime: &RegimeConfidence) -> (ActiveStrategy, f64) {
-        if regime.confidence < self.config.min_confidence {
-            return (ActiveStrategy::NoTrade, 0.0);
+    /// Select strategy based on regime. Position sizing is a separate
+    /// concern handled by `order_size_strategy`.
+    ///
+    /// Takes `min_confidence` directly (rather than `&self`) so `update()`
+    /// can call this while holding a mutable borrow of `self.assets`.
+    fn select_strategy(min_confidence: f64, regime: &RegimeConfidence) -> ActiveStrategy {
+        if regime.confidence < min_confidence {
+            return ActiveStrategy::NoTrade;
         }
-        
+
         match regime.regime {
-            MarketRegime::Trending(_) => (ActiveStrategy::TrendFollowing, 1.0),
-            MarketRegime::MeanReverting => (ActiveStrategy::MeanReversion, 1.0),
-            MarketRegime::Volatile => (ActiveStrategy::MeanReversion, self.config.volatile_position_factor),
-            MarketRegime::Uncertain => (ActiveStrategy::NoTrade, 0.0),
+            MarketRegime::Trending(_) => ActiveStrategy::TrendFollowing,
+            MarketRegime::MeanReverting => ActiveStrategy::MeanReversion,
+            MarketRegime::Volatile => ActiveStrategy::MeanReversion,
+            MarketRegime::Squeeze => ActiveStrategy::NoTrade,
+            MarketRegime::Uncertain => ActiveStrategy::NoTrade,
         }
     }
     
-    /// Generate trend following signal
+    /// Generate a trend following signal. `atr` is the detector's real ATR
+    /// reading when available (the indicator/ensemble detection paths
+    /// already track it); falls back to a `close * 0.02` stand-in when the
+    /// active detector doesn't compute one (e.g. pure `HMM`).
+    ///
+    /// Takes `risk_model` directly (rather than `&self`) so `update()` can
+    /// call this while holding a mutable borrow of `self.assets`.
     fn trend_signal(
-        &self,
+        risk_model: &RiskModel,
         regime: &RegimeConfidence,
         close: f64,
-    ) -> (Signal, String, Option<f64>, Option<f64>) {
+        atr: Option<f64>,
+    ) -> (Signal, String, Option<RiskSizing>) {
+        let atr = atr.unwrap_or(close * 0.02);
+
         // Simplified - integrate with your existing Golden Cross/EMA Pullback
         match regime.regime {
             MarketRegime::Trending(TrendDirection::Bullish) if regime.confidence > 0.6 => {
-                let atr_estimate = close * 0.02;
+                let sizing = risk_model.size(close, TrendDirection::Bullish, atr);
                 (
                     Signal::Buy,
                     format!("Bullish Trend (conf: {:.0}%)", regime.confidence * 100.0),
-                    Some(close - atr_estimate * 2.0),
-                    Some(close + atr_estimate * 3.0),
+                    Some(sizing),
                 )
             }
             MarketRegime::Trending(TrendDirection::Bearish) if regime.confidence > 0.6 => {
+                let sizing = risk_model.size(close, TrendDirection::Bearish, atr);
                 (
                     Signal::Sell,
                     format!("Bearish Trend (conf: {:.0}%)", regime.confidence * 100.0),
-                    None,
-                    None,
+                    Some(sizing),
                 )
             }
-            _ => (Signal::Hold, "Trend unclear".into(), None, None),
+            _ => (Signal::Hold, "Trend unclear".into(), None),
         }
     }
     
@@ -376,6 +652,298 @@ impl EnhancedRouter {
     pub fn regime_changes(&self, symbol: &str) -> u32 {
         self.assets.get(symbol).map(|s| s.regime_change_count).unwrap_or(0)
     }
+
+    /// Get the active detector's current ATR reading for an asset - `None`
+    /// for `DetectionMethod::HMM`, which doesn't track one, or before the
+    /// detector warms up
+    pub fn atr_value(&self, symbol: &str) -> Option<f64> {
+        match &self.assets.get(symbol)?.detector {
+            Detector::Indicator(d) => d.atr_value(),
+            Detector::HMM(_) => None,
+            Detector::Ensemble(d) => d.atr_value(),
+        }
+    }
+}
+
+/// A single tick of market data for one symbol, consumed by
+/// `EnhancedRouterActor::run`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketEvent {
+    Ohlc {
+        symbol: String,
+        high: f64,
+        low: f64,
+        close: f64,
+    },
+}
+
+/// Out-of-band instructions accepted by `EnhancedRouterActor::run`, covering
+/// asset lifecycle and book-wide controls that don't fit the per-tick
+/// `MarketEvent` stream.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Start tracking a new symbol (idempotent, as `register_asset` is)
+    RegisterAsset(String),
+    /// Drop a symbol and its detector/warmup state
+    UnregisterAsset(String),
+    /// Switch every asset to a new `DetectionMethod`, rebuilding detectors
+    SetDetectionMethod(DetectionMethod),
+    /// Halt every asset and queue a forced `Signal::Hold` for any with an
+    /// open position, until `ResumeAll`
+    FlattenAll,
+    /// Clear `FlattenAll`'s halt (per-symbol pauses still apply)
+    ResumeAll,
+    /// Halt a single symbol until `Resume`
+    Pause(String),
+    /// Clear a single symbol's `Pause`
+    Resume(String),
+}
+
+/// Output published by `EnhancedRouterActor::run`: every `EnhancedSignal`
+/// `update()` produces, the regime-change notifications that used to be a
+/// hardcoded `println!`, and warmup/error notifications, so a listener can
+/// log, persist, or trigger orders without polling `get_strategy`/`get_regime`.
+#[derive(Debug, Clone)]
+pub enum EngineEvent {
+    /// A non-`None` `EnhancedSignal` from `update()`
+    Signal { symbol: String, signal: EnhancedSignal },
+    /// A symbol's detected regime changed (replaces the old `println!` in
+    /// `update()`; only emitted when `EnhancedRouterConfig::log_changes` is set)
+    RegimeChanged {
+        symbol: String,
+        from: MarketRegime,
+        to: MarketRegime,
+        confidence: f64,
+        detection_method: DetectionMethod,
+    },
+    /// A symbol's detector became ready to trade for the first time
+    WarmupComplete { symbol: String },
+    /// A command referenced a symbol or state the router couldn't act on
+    Error { message: String },
+}
+
+/// Drives an `EnhancedRouter` as an event-sourced actor: consumes
+/// `MarketEvent` ticks and `Command`s off input channels and publishes
+/// `EngineEvent`s on an output channel. This decouples the router from
+/// stdout and lets a live deployment subscribe to signals instead of
+/// polling `get_strategy`/`get_regime`, and the same `MarketEvent` stream
+/// can be replayed through the backtester.
+pub struct EnhancedRouterActor {
+    router: EnhancedRouter,
+}
+
+impl EnhancedRouterActor {
+    pub fn new(router: EnhancedRouter) -> Self {
+        Self { router }
+    }
+
+    /// Consume `market_rx`/`command_rx` until both channels close, applying
+    /// each `MarketEvent`/`Command` to the wrapped router and forwarding
+    /// every resulting `EngineEvent` to `event_tx`. A closed `event_tx` just
+    /// means no one's listening - events are dropped silently, same as the
+    /// `println!` this replaces would have been if no one were watching stdout.
+    pub async fn run(
+        mut self,
+        mut market_rx: tokio::sync::mpsc::Receiver<MarketEvent>,
+        mut command_rx: tokio::sync::mpsc::Receiver<Command>,
+        event_tx: tokio::sync::mpsc::Sender<EngineEvent>,
+    ) {
+        loop {
+            tokio::select! {
+                // A closed channel yields `None`, which disables its branch;
+                // once both are disabled the `else` branch ends the loop.
+                Some(MarketEvent::Ohlc { symbol, high, low, close }) = market_rx.recv() => {
+                    let signal = self.router.update(&symbol, high, low, close);
+                    self.flush_events(&event_tx).await;
+                    if let Some(signal) = signal {
+                        let _ = event_tx.send(EngineEvent::Signal { symbol, signal }).await;
+                    }
+                }
+                Some(command) = command_rx.recv() => {
+                    self.apply_command(command);
+                    self.flush_events(&event_tx).await;
+                }
+                else => break,
+            }
+        }
+    }
+
+    fn apply_command(&mut self, command: Command) {
+        if let Command::UnregisterAsset(symbol) | Command::Pause(symbol) | Command::Resume(symbol) = &command {
+            if !self.router.assets.contains_key(symbol) {
+                self.router.pending_events.push_back(EngineEvent::Error {
+                    message: format!("{:?}: symbol {} is not registered", command, symbol),
+                });
+                return;
+            }
+        }
+        match command {
+            Command::RegisterAsset(symbol) => self.router.register_asset(&symbol),
+            Command::UnregisterAsset(symbol) => self.router.unregister_asset(&symbol),
+            Command::SetDetectionMethod(method) => self.router.set_detection_method(method),
+            Command::FlattenAll => self.router.flatten_all(),
+            Command::ResumeAll => self.router.resume_all(),
+            Command::Pause(symbol) => self.router.pause(&symbol),
+            Command::Resume(symbol) => self.router.resume(&symbol),
+        }
+    }
+
+    async fn flush_events(&mut self, event_tx: &tokio::sync::mpsc::Sender<EngineEvent>) {
+        for event in self.router.drain_events() {
+            let _ = event_tx.send(event).await;
+        }
+    }
+}
+
+/// Configuration for `PortfolioAllocator`
+#[derive(Debug, Clone)]
+pub struct PortfolioAllocatorConfig {
+    /// Target weight per symbol; assets without an explicit entry fall back
+    /// to `default_weight`
+    pub target_weights: HashMap<String, f64>,
+    /// Weight used for assets with no entry in `target_weights`
+    pub default_weight: f64,
+    /// Book size used to throttle `position_factor` inside `update()`;
+    /// `rebalance()` can still be called with a different total capital
+    pub total_capital: f64,
+    /// Allocations smaller than this absolute dollar amount are skipped to
+    /// avoid churning tiny rebalances
+    pub min_trade_volume: f64,
+}
+
+impl Default for PortfolioAllocatorConfig {
+    fn default() -> Self {
+        Self {
+            target_weights: HashMap::new(),
+            default_weight: 0.2,
+            total_capital: 100_000.0,
+            min_trade_volume: 10.0,
+        }
+    }
+}
+
+/// Per-asset strict dollar-value bounds computed by the bottom-up pass
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AssetLimit {
+    min_value: f64,
+    max_value: f64,
+}
+
+impl AssetLimit {
+    /// The single-sided bound implied by the signal's direction: the
+    /// positive max for a long, the negative min for a short, zero for Hold.
+    fn bound(self) -> f64 {
+        if self.max_value > 0.0 {
+            self.max_value
+        } else {
+            self.min_value
+        }
+    }
+}
+
+/// Rebalances `position_factor` across every registered asset so that
+/// correlated regime changes can't size the whole book at once.
+///
+/// Runs as a classic two-pass rebalance: a bottom-up pass turns each asset's
+/// configured weight, signal confidence and `position_factor` into a strict
+/// min/max dollar-value limit, then a top-down pass distributes a fixed
+/// `target_net_value` across assets subject to those limits, scaling
+/// everything down proportionally when demand exceeds the target and
+/// skipping allocations below `min_trade_volume` to avoid churn.
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioAllocator {
+    config: PortfolioAllocatorConfig,
+}
+
+impl PortfolioAllocator {
+    pub fn new(config: PortfolioAllocatorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(PortfolioAllocatorConfig::default())
+    }
+
+    fn weight(&self, symbol: &str) -> f64 {
+        self.config
+            .target_weights
+            .get(symbol)
+            .copied()
+            .unwrap_or(self.config.default_weight)
+    }
+
+    /// Bottom-up pass: per-asset strict min/max dollar-value limit from its
+    /// configured weight and the confidence/position_factor of its most
+    /// recent signal. Assets with no signal yet are left out.
+    fn asset_limits(
+        &self,
+        total_capital: f64,
+        assets: &HashMap<String, AssetState>,
+    ) -> HashMap<String, AssetLimit> {
+        assets
+            .iter()
+            .filter_map(|(symbol, state)| {
+                let (signal, confidence, position_factor) = state.last_signal?;
+                let bound = total_capital * self.weight(symbol) * confidence * position_factor;
+                let limit = match signal {
+                    Signal::Buy => AssetLimit { min_value: 0.0, max_value: bound },
+                    Signal::Sell => AssetLimit { min_value: -bound, max_value: 0.0 },
+                    Signal::Hold => AssetLimit { min_value: 0.0, max_value: 0.0 },
+                };
+                Some((symbol.clone(), limit))
+            })
+            .collect()
+    }
+
+    /// Top-down pass: distribute `target_net_value` across assets subject to
+    /// their bottom-up limits, scaling proportionally if aggregate demand
+    /// exceeds the target and skipping allocations below `min_trade_volume`.
+    fn rebalance(
+        &self,
+        target_net_value: f64,
+        assets: &HashMap<String, AssetState>,
+    ) -> HashMap<String, f64> {
+        let limits = self.asset_limits(target_net_value, assets);
+
+        let demand: f64 = limits.values().map(|l| l.bound().abs()).sum();
+        let scale = if demand > target_net_value.abs() && demand > 0.0 {
+            target_net_value.abs() / demand
+        } else {
+            1.0
+        };
+
+        limits
+            .into_iter()
+            .filter_map(|(symbol, limit)| {
+                let allocated = limit.bound() * scale;
+                if allocated.abs() < self.config.min_trade_volume {
+                    None
+                } else {
+                    Some((symbol, allocated))
+                }
+            })
+            .collect()
+    }
+
+    /// Fraction in `[0.0, 1.0]` by which `symbol`'s `position_factor` should
+    /// be scaled so its allocation stays within the book-wide limit. Returns
+    /// `1.0` (no throttling) for an asset with no signal yet or no bound.
+    fn scale_factor(&self, symbol: &str, assets: &HashMap<String, AssetState>) -> f64 {
+        let limits = self.asset_limits(self.config.total_capital, assets);
+        let bound = match limits.get(symbol) {
+            Some(limit) if limit.bound().abs() > f64::EPSILON => limit.bound(),
+            _ => return 1.0,
+        };
+
+        let allocated = self
+            .rebalance(self.config.total_capital, assets)
+            .get(symbol)
+            .copied()
+            .unwrap_or(0.0);
+
+        (allocated / bound).clamp(0.0, 1.0)
+    }
 }
 
 impl std::fmt::Display for EnhancedSignal {
@@ -428,9 +996,75 @@ mod tests {
         let mut router = EnhancedRouter::with_ensemble();
         router.register_asset("BTC/USD");
         router.register_asset("ETH/USD");
-        
+
         assert!(router.get_regime("BTC/USD").is_some());
         assert!(router.get_regime("ETH/USD").is_some());
         assert!(router.get_regime("SOL/USD").is_none());
     }
+
+    #[test]
+    fn test_unregister_asset_drops_state() {
+        let mut router = EnhancedRouter::with_indicators();
+        router.register_asset("BTC/USD");
+        assert!(router.get_regime("BTC/USD").is_some());
+
+        router.unregister_asset("BTC/USD");
+        assert!(router.get_regime("BTC/USD").is_none());
+    }
+
+    #[test]
+    fn test_pause_halts_updates_until_resumed() {
+        let mut router = EnhancedRouter::with_indicators();
+        router.register_asset("BTC/USD");
+
+        router.pause("BTC/USD");
+        assert!(router.is_paused("BTC/USD"));
+        assert!(router.update("BTC/USD", 101.0, 99.0, 100.0).is_none());
+
+        router.resume("BTC/USD");
+        assert!(!router.is_paused("BTC/USD"));
+        assert!(router.update("BTC/USD", 101.0, 99.0, 100.0).is_some());
+    }
+
+    #[test]
+    fn test_flatten_all_pauses_every_asset() {
+        let mut router = EnhancedRouter::with_indicators();
+        router.register_asset("BTC/USD");
+        router.register_asset("ETH/USD");
+
+        router.flatten_all();
+        assert!(router.is_paused("BTC/USD"));
+        assert!(router.is_paused("ETH/USD"));
+
+        router.resume_all();
+        assert!(!router.is_paused("BTC/USD"));
+        assert!(!router.is_paused("ETH/USD"));
+    }
+
+    #[test]
+    fn test_set_detection_method_switches_existing_assets() {
+        let mut router = EnhancedRouter::with_indicators();
+        router.register_asset("BTC/USD");
+
+        router.set_detection_method(DetectionMethod::HMM);
+        assert_eq!(router.detection_method(), DetectionMethod::HMM);
+        // Still tracked, just warmed up from scratch under the new method
+        assert!(router.get_regime("BTC/USD").is_some());
+    }
+
+    #[test]
+    fn test_regime_change_emits_event_instead_of_printing() {
+        let mut router = EnhancedRouter::with_indicators();
+        router.register_asset("BTC/USD");
+
+        for i in 0..60 {
+            let close = 100.0 + i as f64;
+            router.update("BTC/USD", close + 1.0, close - 1.0, close);
+        }
+
+        let events = router.drain_events();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, EngineEvent::RegimeChanged { symbol, .. } if symbol == "BTC/USD")));
+    }
 }
@@ -0,0 +1,170 @@
+//! Order Sizing for EnhancedRouter
+//!
+//! `EnhancedRouter::select_strategy` previously hard-coded position sizing -
+//! `1.0` for trending/mean-reverting regimes, a constant `volatile_position_factor`
+//! in `Volatile`. `OrderSizeStrategy` generalizes this into a pluggable
+//! multiplier computed from the regime confidence, HMM state probabilities,
+//! and trailing returns, so the router can swap in volatility-targeting or
+//! conviction-scaled sizing without forking regime-selection logic.
+
+use crate::regime::{MarketRegime, RegimeConfidence};
+
+/// Produces the `position_factor` applied to an `EnhancedSignal`
+pub trait OrderSizeStrategy: std::fmt::Debug {
+    /// Compute the position-size factor for the current bar
+    fn size(
+        &self,
+        regime: &RegimeConfidence,
+        state_probabilities: Option<&[f64]>,
+        recent_returns: &[f64],
+    ) -> f64;
+}
+
+/// Reproduces the router's original hard-coded sizing: full size in
+/// trending/mean-reverting regimes, `volatile_position_factor` in `Volatile`.
+/// This is the default strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedFactor {
+    pub volatile_position_factor: f64,
+}
+
+impl Default for FixedFactor {
+    fn default() -> Self {
+        Self {
+            volatile_position_factor: 0.5,
+        }
+    }
+}
+
+impl OrderSizeStrategy for FixedFactor {
+    fn size(
+        &self,
+        regime: &RegimeConfidence,
+        _state_probabilities: Option<&[f64]>,
+        _recent_returns: &[f64],
+    ) -> f64 {
+        match regime.regime {
+            MarketRegime::Volatile | MarketRegime::Squeeze => self.volatile_position_factor,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Scales position size toward a target annualized volatility:
+/// `size = target_annual_vol / realized_vol`, clamped to `[0, max_leverage]`.
+///
+/// Realized volatility is the sample standard deviation of `recent_returns`,
+/// annualized with the crate's standard 252-period convention (the same
+/// factor `regime::hmm`'s annualized regime stats use).
+#[derive(Debug, Clone, Copy)]
+pub struct VolatilityTarget {
+    pub target_annual_vol: f64,
+    pub max_leverage: f64,
+}
+
+impl Default for VolatilityTarget {
+    fn default() -> Self {
+        Self {
+            target_annual_vol: 0.6,
+            max_leverage: 1.0,
+        }
+    }
+}
+
+impl OrderSizeStrategy for VolatilityTarget {
+    fn size(
+        &self,
+        _regime: &RegimeConfidence,
+        _state_probabilities: Option<&[f64]>,
+        recent_returns: &[f64],
+    ) -> f64 {
+        let realized_vol = annualized_vol(recent_returns);
+        if realized_vol <= 0.0 {
+            return self.max_leverage;
+        }
+        (self.target_annual_vol / realized_vol).clamp(0.0, self.max_leverage)
+    }
+}
+
+fn annualized_vol(returns: &[f64]) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+        / (returns.len() - 1) as f64;
+    variance.sqrt() * 252.0_f64.sqrt()
+}
+
+/// Scales the base position size by regime confidence, so conviction - not
+/// just regime type - drives size. `EnhancedRouter::update` pre-discounts
+/// the confidence it passes in here when `Ensemble` detection methods
+/// disagree, so this sizer naturally down-weights disagreement without
+/// needing to know about `methods_agree` itself.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfidenceWeighted;
+
+impl OrderSizeStrategy for ConfidenceWeighted {
+    fn size(
+        &self,
+        regime: &RegimeConfidence,
+        _state_probabilities: Option<&[f64]>,
+        _recent_returns: &[f64],
+    ) -> f64 {
+        regime.confidence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regime(kind: MarketRegime, confidence: f64) -> RegimeConfidence {
+        RegimeConfidence::new(kind, confidence)
+    }
+
+    #[test]
+    fn test_fixed_factor_full_size_outside_volatile() {
+        let sizer = FixedFactor::default();
+        let r = regime(MarketRegime::MeanReverting, 0.8);
+        assert_eq!(sizer.size(&r, None, &[]), 1.0);
+    }
+
+    #[test]
+    fn test_fixed_factor_reduced_in_volatile() {
+        let sizer = FixedFactor { volatile_position_factor: 0.3 };
+        let r = regime(MarketRegime::Volatile, 0.8);
+        assert_eq!(sizer.size(&r, None, &[]), 0.3);
+    }
+
+    #[test]
+    fn test_volatility_target_scales_down_high_vol() {
+        let sizer = VolatilityTarget { target_annual_vol: 0.5, max_leverage: 2.0 };
+        let high_vol_returns = vec![0.05, -0.05, 0.06, -0.04, 0.05, -0.06];
+        let r = regime(MarketRegime::Trending(crate::regime::TrendDirection::Bullish), 0.9);
+        let size = sizer.size(&r, None, &high_vol_returns);
+        assert!(size > 0.0 && size < 2.0);
+    }
+
+    #[test]
+    fn test_volatility_target_caps_at_max_leverage() {
+        let sizer = VolatilityTarget { target_annual_vol: 5.0, max_leverage: 1.5 };
+        let low_vol_returns = vec![0.0001, -0.0001, 0.0001, -0.0001];
+        let r = regime(MarketRegime::MeanReverting, 0.9);
+        assert_eq!(sizer.size(&r, None, &low_vol_returns), 1.5);
+    }
+
+    #[test]
+    fn test_volatility_target_with_insufficient_history_uses_max_leverage() {
+        let sizer = VolatilityTarget::default();
+        let r = regime(MarketRegime::MeanReverting, 0.9);
+        assert_eq!(sizer.size(&r, None, &[0.01]), sizer.max_leverage);
+    }
+
+    #[test]
+    fn test_confidence_weighted_scales_with_confidence() {
+        let sizer = ConfidenceWeighted;
+        let r = regime(MarketRegime::Trending(crate::regime::TrendDirection::Bullish), 0.6);
+        assert_eq!(sizer.size(&r, None, &[]), 0.6);
+    }
+}
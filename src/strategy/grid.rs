@@ -0,0 +1,260 @@
+//! Grid Trading Strategy
+//!
+//! The router's only tools for ranging markets were trend-following (wrong
+//! regime) and `MeanReversionStrategy` (bets on a single round trip back to
+//! the mean). `GridStrategy` is the canonical maker strategy for sideways
+//! markets instead: it lays a ladder of staggered buy levels below a center
+//! price and sell levels above it, and every time price crosses a level that
+//! level's side flips (a filled buy becomes a resting sell and vice versa),
+//! so the ladder keeps harvesting round trips as long as price stays
+//! range-bound. The grid recenters around the latest close whenever price
+//! drifts outside its outer levels.
+
+use serde::{Deserialize, Serialize};
+
+use crate::regime::ATR;
+use crate::strategy::mean_reversion::Signal;
+
+/// How spacing between adjacent grid levels is computed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GridSpacing {
+    /// Fixed percentage of the center price, e.g. `0.005` for 0.5%
+    Percent(f64),
+    /// A multiple of the current ATR reading, normalized by the center price
+    AtrMultiple(f64),
+}
+
+/// Configuration for the grid-trading strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridConfig {
+    /// Spacing between adjacent levels, as a percentage of center or an
+    /// ATR multiple
+    pub spacing: GridSpacing,
+    /// ATR period, used only when `spacing` is `AtrMultiple`
+    pub atr_period: usize,
+    /// Number of buy levels below center and sell levels above it
+    pub levels_per_side: usize,
+    /// Fraction of total grid capital held in quote currency (cash) at
+    /// setup, funding the buy-side levels; the remainder is assumed already
+    /// held in the base asset, funding the sell-side levels
+    pub quote_allocation_pct: f64,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            spacing: GridSpacing::Percent(0.005), // 0.5% per level
+            atr_period: 14,
+            levels_per_side: 5,
+            quote_allocation_pct: 0.5,
+        }
+    }
+}
+
+/// A single resting order in the grid ladder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridLevel {
+    pub price: f64,
+    /// Side of the order currently resting at `price` - flips each time the
+    /// level fills
+    pub side: Signal,
+}
+
+/// A level that filled on this bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridFill {
+    pub price: f64,
+    pub side: Signal,
+}
+
+/// Ladder-of-orders grid strategy for ranging regimes
+#[derive(Debug)]
+pub struct GridStrategy {
+    config: GridConfig,
+    atr: ATR,
+    levels: Vec<GridLevel>,
+    last_fills: Vec<GridFill>,
+}
+
+impl GridStrategy {
+    pub fn new(config: GridConfig) -> Self {
+        Self {
+            atr: ATR::new(config.atr_period),
+            config,
+            levels: Vec::new(),
+            last_fills: Vec::new(),
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(GridConfig::default())
+    }
+
+    /// Update with a new OHLC bar. Returns `Signal::Buy`/`Signal::Sell` if
+    /// any level filled this bar (if several levels were crossed in one bar,
+    /// the first in ladder order, which runs outward from the center with
+    /// the buy level before the sell level at each step), `Signal::Hold`
+    /// otherwise.
+    /// See [`Self::last_fills`] for every level that filled.
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        let atr = self.atr.update(high, low, close);
+        self.last_fills.clear();
+
+        let spacing_pct = match self.config.spacing {
+            GridSpacing::Percent(pct) => Some(pct),
+            GridSpacing::AtrMultiple(mult) if close > 0.0 => atr.map(|a| mult * a / close),
+            GridSpacing::AtrMultiple(_) => None,
+        };
+        let Some(spacing_pct) = spacing_pct else {
+            return Signal::Hold;
+        };
+
+        if self.levels.is_empty() || self.is_outside_grid(close) {
+            self.recenter(close, spacing_pct);
+            return Signal::Hold;
+        }
+
+        for level in &mut self.levels {
+            if level.price >= low && level.price <= high {
+                let filled_side = level.side;
+                level.side = match level.side {
+                    Signal::Buy => Signal::Sell,
+                    Signal::Sell => Signal::Buy,
+                    Signal::Hold => Signal::Hold,
+                };
+                self.last_fills.push(GridFill {
+                    price: level.price,
+                    side: filled_side,
+                });
+            }
+        }
+
+        self.last_fills.first().map(|f| f.side).unwrap_or(Signal::Hold)
+    }
+
+    /// Whether `price` has drifted past the grid's outer levels, triggering
+    /// a recenter
+    fn is_outside_grid(&self, price: f64) -> bool {
+        let top = self.levels.iter().map(|l| l.price).fold(f64::MIN, f64::max);
+        let bottom = self.levels.iter().map(|l| l.price).fold(f64::MAX, f64::min);
+        price > top || price < bottom
+    }
+
+    /// Rebuild the ladder around `center`: `levels_per_side` buy levels
+    /// below it, `levels_per_side` sell levels above it, each `spacing_pct`
+    /// apart
+    fn recenter(&mut self, center: f64, spacing_pct: f64) {
+        self.levels.clear();
+        for i in 1..=self.config.levels_per_side {
+            let offset = spacing_pct * i as f64;
+            self.levels.push(GridLevel {
+                price: center * (1.0 - offset),
+                side: Signal::Buy,
+            });
+            self.levels.push(GridLevel {
+                price: center * (1.0 + offset),
+                side: Signal::Sell,
+            });
+        }
+    }
+
+    /// The current ladder of resting orders
+    pub fn levels(&self) -> &[GridLevel] {
+        &self.levels
+    }
+
+    /// Every level that filled on the most recent `update` call
+    pub fn last_fills(&self) -> &[GridFill] {
+        &self.last_fills
+    }
+
+    /// Whether the strategy has enough data to generate signals
+    pub fn is_ready(&self) -> bool {
+        !self.levels.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_bar_seeds_the_grid_without_a_signal() {
+        let mut grid = GridStrategy::new(GridConfig {
+            levels_per_side: 3,
+            ..GridConfig::default()
+        });
+
+        let signal = grid.update(101.0, 99.0, 100.0);
+        assert_eq!(signal, Signal::Hold);
+        assert!(grid.is_ready());
+        assert_eq!(grid.levels().len(), 6);
+    }
+
+    #[test]
+    fn test_price_drop_fills_a_buy_level_and_flips_it_to_sell() {
+        let mut grid = GridStrategy::new(GridConfig {
+            spacing: GridSpacing::Percent(0.01),
+            levels_per_side: 2,
+            ..GridConfig::default()
+        });
+
+        grid.update(100.5, 99.5, 100.0); // seeds grid at center 100.0
+
+        // Nearest buy level sits at 99.0 (1% below center)
+        let signal = grid.update(99.5, 98.5, 99.0);
+        assert_eq!(signal, Signal::Buy);
+        assert_eq!(grid.last_fills().len(), 1);
+        assert!(grid
+            .levels()
+            .iter()
+            .any(|l| (l.price - 99.0).abs() < 1e-9 && l.side == Signal::Sell));
+    }
+
+    #[test]
+    fn test_flipped_level_fills_as_a_sell_on_the_way_back_up() {
+        let mut grid = GridStrategy::new(GridConfig {
+            spacing: GridSpacing::Percent(0.01),
+            levels_per_side: 2,
+            ..GridConfig::default()
+        });
+
+        grid.update(100.5, 99.5, 100.0);
+        grid.update(99.5, 98.5, 99.0); // buy fills, flips to sell at 99.0
+        let signal = grid.update(99.5, 98.8, 99.2); // price revisits 99.0
+        assert_eq!(signal, Signal::Sell);
+    }
+
+    #[test]
+    fn test_price_drifting_past_outer_levels_recenters_the_grid() {
+        let mut grid = GridStrategy::new(GridConfig {
+            spacing: GridSpacing::Percent(0.01),
+            levels_per_side: 2,
+            ..GridConfig::default()
+        });
+
+        grid.update(100.5, 99.5, 100.0);
+        let old_levels = grid.levels().to_vec();
+
+        // Far above the top level (100 * 1.02) - should recenter, not fill
+        let signal = grid.update(110.0, 109.0, 110.0);
+        assert_eq!(signal, Signal::Hold);
+        assert_ne!(grid.levels(), old_levels.as_slice());
+        // The new ladder's nearest buy level sits 1% below the new 110 center
+        assert!(grid.levels().iter().any(|l| (l.price - 110.0 * 0.99).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_atr_spacing_holds_off_until_atr_is_ready() {
+        let mut grid = GridStrategy::new(GridConfig {
+            spacing: GridSpacing::AtrMultiple(2.0),
+            atr_period: 5,
+            levels_per_side: 2,
+            ..GridConfig::default()
+        });
+
+        assert_eq!(grid.update(101.0, 99.0, 100.0), Signal::Hold);
+        assert!(!grid.is_ready(), "grid shouldn't seed before ATR warms up");
+    }
+}
@@ -0,0 +1,229 @@
+//! Mean-Reversion Strategy
+//!
+//! Bollinger Bands mark the range a price is expected to oscillate within;
+//! RSI confirms the move is exhausted rather than the start of a breakout.
+//! Buys when price closes at or below the lower band with RSI oversold,
+//! and exits back to flat once price reverts to the middle band or RSI
+//! turns overbought. Stops and targets are sized from ATR, the same as
+//! `TrendFollowingStrategy`. Long-only: a bet that a ranging market snaps
+//! back to its mean doesn't have the asymmetric short-side payoff a
+//! confirmed downtrend does, so unlike `TrendFollowingStrategy` there's no
+//! `allow_short` knob here.
+
+use serde::{Deserialize, Serialize};
+
+use crate::regime::{BollingerBands, BollingerBandsValues, ATR, RSI};
+
+/// A strategy's trading signal for the current bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Buy,
+    Sell,
+    Hold,
+}
+
+/// Configuration for the mean-reversion strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeanReversionConfig {
+    /// Bollinger Bands lookback period
+    pub bb_period: usize,
+    /// Bollinger Bands width, in standard deviations
+    pub bb_std_dev: f64,
+    /// RSI lookback period
+    pub rsi_period: usize,
+    /// Entries require RSI at or below this (oversold)
+    pub rsi_oversold: f64,
+    /// Exits require RSI at or above this (overbought), in addition to the
+    /// %B reversion exit
+    pub rsi_overbought: f64,
+    /// ATR period for stop/target sizing
+    pub atr_period: usize,
+    /// Stop-loss distance as a multiple of ATR
+    pub atr_stop_loss_mult: f64,
+    /// Take-profit distance as a multiple of ATR
+    pub atr_take_profit_mult: f64,
+}
+
+impl Default for MeanReversionConfig {
+    fn default() -> Self {
+        Self {
+            bb_period: 20,
+            bb_std_dev: 2.0,
+            rsi_period: 14,
+            rsi_oversold: 30.0,
+            rsi_overbought: 70.0,
+            atr_period: 14,
+            atr_stop_loss_mult: 1.5,
+            atr_take_profit_mult: 2.0,
+        }
+    }
+}
+
+/// Bundled output of one `update` call, for callers that want the signal
+/// and its stop/target together instead of chaining three accessors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyResult {
+    pub signal: Signal,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+}
+
+/// Bollinger Bands / RSI mean-reversion strategy
+#[derive(Debug)]
+pub struct MeanReversionStrategy {
+    config: MeanReversionConfig,
+
+    bb: BollingerBands,
+    rsi: RSI,
+    atr: ATR,
+
+    /// Whether a long is currently open
+    position_open: bool,
+    last_bb: Option<BollingerBandsValues>,
+    last_rsi: Option<f64>,
+    last_stop_loss: Option<f64>,
+    last_take_profit: Option<f64>,
+    last_result: StrategyResult,
+}
+
+impl MeanReversionStrategy {
+    pub fn new(config: MeanReversionConfig) -> Self {
+        Self {
+            bb: BollingerBands::new(config.bb_period, config.bb_std_dev),
+            rsi: RSI::new(config.rsi_period),
+            atr: ATR::new(config.atr_period),
+            position_open: false,
+            last_bb: None,
+            last_rsi: None,
+            last_stop_loss: None,
+            last_take_profit: None,
+            last_result: StrategyResult {
+                signal: Signal::Hold,
+                stop_loss: None,
+                take_profit: None,
+            },
+            config,
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(MeanReversionConfig::default())
+    }
+
+    /// Update with a new OHLC bar and get a trading signal
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        let bb = self.bb.update(close);
+        let rsi = self.rsi.update(close);
+        let atr = self.atr.update(high, low, close);
+        self.last_bb = bb;
+        self.last_rsi = rsi;
+
+        let signal = 'signal: {
+            let (Some(bb), Some(rsi), Some(atr)) = (bb, rsi, atr) else {
+                break 'signal Signal::Hold;
+            };
+
+            if !self.position_open {
+                if bb.is_oversold() && rsi <= self.config.rsi_oversold {
+                    self.position_open = true;
+                    self.last_stop_loss = Some(close - atr * self.config.atr_stop_loss_mult);
+                    self.last_take_profit = Some(close + atr * self.config.atr_take_profit_mult);
+                    break 'signal Signal::Buy;
+                }
+            } else if bb.percent_b >= 0.5 || rsi >= self.config.rsi_overbought {
+                self.position_open = false;
+                self.last_stop_loss = None;
+                self.last_take_profit = None;
+                break 'signal Signal::Sell;
+            }
+
+            Signal::Hold
+        };
+
+        self.last_result = StrategyResult {
+            signal,
+            stop_loss: self.last_stop_loss,
+            take_profit: self.last_take_profit,
+        };
+
+        signal
+    }
+
+    /// Last Bollinger Bands reading, for reason reporting
+    pub fn last_bb_values(&self) -> Option<BollingerBandsValues> {
+        self.last_bb
+    }
+
+    /// Last RSI value, for reason reporting
+    pub fn last_rsi(&self) -> Option<f64> {
+        self.last_rsi
+    }
+
+    /// Stop-loss level for the current trade
+    pub fn stop_loss(&self) -> Option<f64> {
+        self.last_stop_loss
+    }
+
+    /// Take-profit level for the current trade
+    pub fn take_profit(&self) -> Option<f64> {
+        self.last_take_profit
+    }
+
+    /// Bundled signal, stop-loss and take-profit from the most recent `update`
+    pub fn last_result(&self) -> StrategyResult {
+        self.last_result
+    }
+
+    /// Whether the strategy has enough data to generate signals
+    pub fn is_ready(&self) -> bool {
+        self.bb.is_ready() && self.rsi.is_ready() && self.atr.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_ready_holds() {
+        let mut strategy = MeanReversionStrategy::default_config();
+        let signal = strategy.update(101.0, 99.0, 100.0);
+        assert_eq!(signal, Signal::Hold);
+        assert!(!strategy.is_ready());
+    }
+
+    #[test]
+    fn test_oversold_dip_buys_then_reversion_sells() {
+        let mut strategy = MeanReversionStrategy::new(MeanReversionConfig {
+            bb_period: 10,
+            rsi_period: 5,
+            ..MeanReversionConfig::default()
+        });
+
+        // Calm range to warm up the indicators
+        let mut price = 100.0;
+        for _ in 0..15 {
+            strategy.update(price + 0.2, price - 0.2, price);
+        }
+
+        // Sharp dip pushes price below the lower band, oversold on RSI
+        let mut signals = Vec::new();
+        for _ in 0..5 {
+            price -= 3.0;
+            signals.push(strategy.update(price + 0.2, price - 0.2, price));
+        }
+        assert!(signals.contains(&Signal::Buy));
+        assert!(strategy.stop_loss().is_some());
+        assert!(strategy.take_profit().is_some());
+
+        // Reversion back toward the middle band closes the long
+        let mut reversion_signals = Vec::new();
+        for _ in 0..10 {
+            price += 1.0;
+            reversion_signals.push(strategy.update(price + 0.2, price - 0.2, price));
+        }
+        assert!(reversion_signals.contains(&Signal::Sell));
+        assert!(strategy.stop_loss().is_none());
+    }
+}
@@ -10,8 +10,28 @@
 pub mod mean_reversion;
 pub mod router;
 pub mod enhanced_router;
+pub mod enhanced_sizing;
+pub mod trend_following;
+pub mod grid;
+pub mod registry;
+pub mod position_sizing;
+pub mod risk_guard;
+pub mod risk_model;
+pub mod pairs;
 
 // Re-export main types
 pub use mean_reversion::{MeanReversionStrategy, MeanReversionConfig, Signal, StrategyResult};
 pub use router::{StrategyRouter, StrategyRouterConfig, RoutedSignal, ActiveStrategy, RouterStats};
-pub use enhanced_router::{EnhancedRouter, EnhancedRouterConfig, EnhancedSignal, DetectionMethod};
+pub use enhanced_router::{
+    EnhancedRouter, EnhancedRouterConfig, EnhancedSignal, DetectionMethod,
+    PortfolioAllocator, PortfolioAllocatorConfig,
+    EnhancedRouterActor, MarketEvent, Command, EngineEvent,
+};
+pub use enhanced_sizing::{OrderSizeStrategy, FixedFactor, VolatilityTarget, ConfidenceWeighted};
+pub use trend_following::{TrendFollowingStrategy, TrendFollowingConfig};
+pub use grid::{GridStrategy, GridConfig, GridSpacing, GridLevel, GridFill};
+pub use registry::{Strategy, StrategyRegistry, StrategyMetadata, StrategyKind, StrategyParameter, TradeFrequency};
+pub use position_sizing::{PositionSizer, SizingContext, FixedFactorSizer, VolatilityTargetSizer, FractionalKellySizer};
+pub use risk_guard::{RiskGuard, RiskGuardConfig};
+pub use risk_model::{RiskModel, RiskModelConfig, RiskSizing};
+pub use pairs::{PairsStrategy, PairsConfig, PairPosition};
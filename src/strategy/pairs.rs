@@ -0,0 +1,433 @@
+//! Pairs / Cointegration Strategy
+//!
+//! Every other strategy in this module operates on one symbol's OHLC
+//! stream, but the crate advertises multi-asset support (BTC/ETH/SOL).
+//! `PairsStrategy` trades the relationship between two of them instead of
+//! either one in isolation: it estimates a hedge ratio β via OLS of one
+//! log-price on the other, forms the spread `log(P_a) - β·log(P_b)`, and
+//! checks that spread for stationarity with an Augmented Dickey-Fuller
+//! test. While the pair tests as cointegrated, it trades the z-scored
+//! spread back toward its mean - short the spread (short A / long B) when
+//! it's too wide, long the spread (long A / short B) when it's too narrow
+//! - and flattens if the ADF p-value degrades past `stop_adf_pvalue`,
+//! treating that as the pair decointegrating rather than a normal
+//! excursion to ride out.
+//!
+//! β and the cointegration test are both recomputed from scratch on every
+//! update over a trailing `window` of bars, so the hedge ratio tracks
+//! drift in the relationship instead of locking in a stale one.
+//!
+//! This doesn't implement the single-symbol `Strategy` trait - its
+//! `update` takes two price series, not one OHLC bar - so `StrategyRouter`
+//! can't route to it directly. A caller holding two correlated symbols
+//! runs it alongside the router, feeding it each symbol's regime so it
+//! only trades while the pair (not just one leg) looks range-bound.
+
+use std::collections::VecDeque;
+
+use crate::regime::MarketRegime;
+
+/// Configuration for the pairs strategy
+#[derive(Debug, Clone, Copy)]
+pub struct PairsConfig {
+    /// Trailing bars used to re-estimate the hedge ratio and re-run the
+    /// ADF test on every update
+    pub window: usize,
+    /// Enter when the spread z-score's absolute value exceeds this
+    pub entry_zscore: f64,
+    /// Exit once the spread z-score's absolute value falls back below this
+    pub exit_zscore: f64,
+    /// Required ADF p-value (at or below) to open a new position -
+    /// evidence the spread is stationary
+    pub max_adf_pvalue: f64,
+    /// ADF p-value (at or above) that forces an open position flat -
+    /// evidence the pair has decointegrated
+    pub stop_adf_pvalue: f64,
+}
+
+impl Default for PairsConfig {
+    fn default() -> Self {
+        Self {
+            window: 90,
+            entry_zscore: 2.0,
+            exit_zscore: 0.5,
+            max_adf_pvalue: 0.05,
+            stop_adf_pvalue: 0.10,
+        }
+    }
+}
+
+/// Which side of the spread the strategy currently holds. A position on
+/// one leg always implies the opposite position on the other, since the
+/// two are only ever traded as a single spread unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairPosition {
+    Flat,
+    /// Spread is too wide and expected to fall: short A, long B
+    ShortSpread,
+    /// Spread is too narrow (or negative) and expected to rise: long A, short B
+    LongSpread,
+}
+
+/// Cointegration-based pairs strategy trading the spread between two
+/// price series
+#[derive(Debug)]
+pub struct PairsStrategy {
+    config: PairsConfig,
+    log_a: VecDeque<f64>,
+    log_b: VecDeque<f64>,
+    position: PairPosition,
+    hedge_ratio: Option<f64>,
+    adf_pvalue: Option<f64>,
+    spread_zscore: Option<f64>,
+}
+
+impl PairsStrategy {
+    pub fn new(config: PairsConfig) -> Self {
+        Self {
+            log_a: VecDeque::with_capacity(config.window),
+            log_b: VecDeque::with_capacity(config.window),
+            config,
+            position: PairPosition::Flat,
+            hedge_ratio: None,
+            adf_pvalue: None,
+            spread_zscore: None,
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(PairsConfig::default())
+    }
+
+    /// Update with the latest close for each leg and the regime reported
+    /// for the pair, returning the resulting position.
+    ///
+    /// `regime` gates entries: the strategy only trades while the pair
+    /// looks range-bound (`MeanReverting` or `Squeeze`) - a trending or
+    /// volatile regime is exactly when a hedge ratio estimated over the
+    /// trailing window is least trustworthy, so any open position is held
+    /// through it rather than added to, and a forced decointegration exit
+    /// still fires regardless of regime.
+    pub fn update(&mut self, regime: MarketRegime, price_a: f64, price_b: f64) -> PairPosition {
+        self.push(price_a, price_b);
+        if !self.is_ready() {
+            return self.position;
+        }
+
+        let log_a: Vec<f64> = self.log_a.iter().copied().collect();
+        let log_b: Vec<f64> = self.log_b.iter().copied().collect();
+
+        let beta = hedge_ratio(&log_a, &log_b);
+        let spread: Vec<f64> = log_a.iter().zip(&log_b).map(|(a, b)| a - beta * b).collect();
+        let pvalue = adf_pvalue(&spread);
+        let zscore = zscore(&spread);
+
+        self.hedge_ratio = Some(beta);
+        self.adf_pvalue = Some(pvalue);
+        self.spread_zscore = zscore;
+
+        let Some(z) = zscore else {
+            return self.position;
+        };
+
+        if self.position != PairPosition::Flat && pvalue >= self.config.stop_adf_pvalue {
+            self.position = PairPosition::Flat;
+            return self.position;
+        }
+
+        self.position = match self.position {
+            PairPosition::Flat if Self::ranging(regime) && pvalue <= self.config.max_adf_pvalue => {
+                if z > self.config.entry_zscore {
+                    PairPosition::ShortSpread
+                } else if z < -self.config.entry_zscore {
+                    PairPosition::LongSpread
+                } else {
+                    PairPosition::Flat
+                }
+            }
+            PairPosition::ShortSpread if z <= self.config.exit_zscore => PairPosition::Flat,
+            PairPosition::LongSpread if z >= -self.config.exit_zscore => PairPosition::Flat,
+            other => other,
+        };
+
+        self.position
+    }
+
+    fn push(&mut self, price_a: f64, price_b: f64) {
+        self.log_a.push_back(price_a.ln());
+        self.log_b.push_back(price_b.ln());
+        if self.log_a.len() > self.config.window {
+            self.log_a.pop_front();
+            self.log_b.pop_front();
+        }
+    }
+
+    /// `MarketRegime` variants this strategy treats as range-bound enough
+    /// to trust a rolling hedge ratio - narrower than
+    /// `MeanReversionStrategy`'s suited regimes since `Volatile` widens the
+    /// spread's own variance enough to make the z-score unreliable.
+    fn ranging(regime: MarketRegime) -> bool {
+        matches!(regime, MarketRegime::MeanReverting | MarketRegime::Squeeze)
+    }
+
+    /// Whether enough bars have accumulated to estimate β and run the ADF test
+    pub fn is_ready(&self) -> bool {
+        self.log_a.len() >= self.config.window
+    }
+
+    /// Current position held on the spread
+    pub fn position(&self) -> PairPosition {
+        self.position
+    }
+
+    /// Most recently estimated hedge ratio β
+    pub fn hedge_ratio(&self) -> Option<f64> {
+        self.hedge_ratio
+    }
+
+    /// Most recent ADF test p-value for the spread; low values are
+    /// evidence the pair is cointegrated
+    pub fn adf_pvalue(&self) -> Option<f64> {
+        self.adf_pvalue
+    }
+
+    /// Most recent z-score of the spread relative to its trailing window mean
+    pub fn spread_zscore(&self) -> Option<f64> {
+        self.spread_zscore
+    }
+}
+
+/// OLS hedge ratio β minimizing `sum((log_a - beta * log_b)^2)` - no
+/// intercept, matching the spread definition `log(P_a) - β·log(P_b)`.
+fn hedge_ratio(log_a: &[f64], log_b: &[f64]) -> f64 {
+    let mut sxy = 0.0;
+    let mut sxx = 0.0;
+    for (&a, &b) in log_a.iter().zip(log_b) {
+        sxy += a * b;
+        sxx += b * b;
+    }
+    if sxx > 0.0 {
+        sxy / sxx
+    } else {
+        1.0
+    }
+}
+
+/// Population z-score of the last element of `series` against the mean
+/// and standard deviation of the whole series
+fn zscore(series: &[f64]) -> Option<f64> {
+    let n = series.len() as f64;
+    if n < 2.0 {
+        return None;
+    }
+    let mean = series.iter().sum::<f64>() / n;
+    let variance = series.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let std_dev = variance.sqrt();
+    if std_dev <= 0.0 {
+        return None;
+    }
+    Some((series.last().unwrap() - mean) / std_dev)
+}
+
+/// Approximate Augmented Dickey-Fuller test (one lag, with intercept) on
+/// `series`: regresses `delta[t] = series[t] - series[t-1]` on
+/// `series[t-1]` and tests whether that slope is zero (unit root, i.e.
+/// non-stationary) via its t-statistic. Returns an approximate p-value -
+/// not an exact response-surface fit, just enough resolution to gate
+/// entries against `max_adf_pvalue`/`stop_adf_pvalue`.
+fn adf_pvalue(series: &[f64]) -> f64 {
+    if series.len() < 3 {
+        return 1.0;
+    }
+
+    let level: Vec<f64> = series[..series.len() - 1].to_vec();
+    let delta: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+
+    let n = level.len() as f64;
+    let mean_x = level.iter().sum::<f64>() / n;
+    let mean_y = delta.iter().sum::<f64>() / n;
+
+    let mut sxy = 0.0;
+    let mut sxx = 0.0;
+    for (&x, &y) in level.iter().zip(&delta) {
+        sxy += (x - mean_x) * (y - mean_y);
+        sxx += (x - mean_x) * (x - mean_x);
+    }
+    if sxx <= 0.0 {
+        return 1.0;
+    }
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+
+    let residual_ss: f64 = level
+        .iter()
+        .zip(&delta)
+        .map(|(&x, &y)| {
+            let resid = y - intercept - slope * x;
+            resid * resid
+        })
+        .sum();
+    let dof = n - 2.0;
+    if dof <= 0.0 {
+        return 1.0;
+    }
+    let sigma2 = residual_ss / dof;
+    let se_slope = (sigma2 / sxx).sqrt();
+    if se_slope <= 0.0 {
+        return 1.0;
+    }
+    let t_stat = slope / se_slope;
+
+    adf_tstat_to_pvalue(t_stat)
+}
+
+/// Maps an ADF test statistic to an approximate p-value by linear
+/// interpolation over MacKinnon's tabulated critical values for the
+/// constant-only case (no trend term), extended with rough tail points so
+/// every t-statistic lands somewhere in `[0, 1]`.
+fn adf_tstat_to_pvalue(t_stat: f64) -> f64 {
+    const POINTS: [(f64, f64); 7] = [
+        (-6.0, 0.0001),
+        (-3.43, 0.01),
+        (-2.86, 0.05),
+        (-2.57, 0.10),
+        (-1.0, 0.55),
+        (0.0, 0.85),
+        (2.0, 0.99),
+    ];
+
+    if t_stat <= POINTS[0].0 {
+        return POINTS[0].1;
+    }
+    if t_stat >= POINTS[POINTS.len() - 1].0 {
+        return POINTS[POINTS.len() - 1].1;
+    }
+
+    for window in POINTS.windows(2) {
+        let (t0, p0) = window[0];
+        let (t1, p1) = window[1];
+        if t_stat >= t0 && t_stat <= t1 {
+            let frac = (t_stat - t0) / (t1 - t0);
+            return p0 + frac * (p1 - p0);
+        }
+    }
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two series moving in lockstep (b tracks a^2/100, and with prices near
+    /// 100 both logs sit near ln 100, so the no-intercept beta comes out
+    /// near 1.0) with a tiny mean-reverting wobble layered on
+    /// top should test as cointegrated and eventually trade the wobble.
+    fn cointegrated_pair(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut a = Vec::with_capacity(n);
+        let mut b = Vec::with_capacity(n);
+        let mut price = 100.0;
+        for i in 0..n {
+            price *= 1.0 + 0.001 * ((i % 7) as f64 - 3.0);
+            let wobble = 0.02 * ((i as f64 * 0.7).sin());
+            a.push(price * (1.0 + wobble));
+            b.push(price * price / 100.0 * (1.0 - wobble));
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn test_not_ready_before_window_fills() {
+        let mut strat = PairsStrategy::new(PairsConfig {
+            window: 20,
+            ..PairsConfig::default()
+        });
+        for _ in 0..19 {
+            let pos = strat.update(MarketRegime::MeanReverting, 100.0, 50.0);
+            assert_eq!(pos, PairPosition::Flat);
+            assert!(!strat.is_ready());
+        }
+        strat.update(MarketRegime::MeanReverting, 100.0, 50.0);
+        assert!(strat.is_ready());
+    }
+
+    #[test]
+    fn test_identical_series_hedge_ratio_is_one() {
+        let mut strat = PairsStrategy::new(PairsConfig {
+            window: 30,
+            ..PairsConfig::default()
+        });
+        for i in 0..30 {
+            let price = 100.0 + i as f64;
+            strat.update(MarketRegime::MeanReverting, price, price);
+        }
+        assert!((strat.hedge_ratio().unwrap() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_trending_regime_blocks_new_entries() {
+        let mut strat = PairsStrategy::new(PairsConfig {
+            window: 30,
+            entry_zscore: 0.0, // make entry trivial to hit so the gate is what blocks it
+            max_adf_pvalue: 1.0,
+            ..PairsConfig::default()
+        });
+        let (a, b) = cointegrated_pair(30);
+        for i in 0..30 {
+            let pos = strat.update(MarketRegime::Trending(crate::regime::TrendDirection::Bullish), a[i], b[i]);
+            assert_eq!(pos, PairPosition::Flat, "no entry should open outside a ranging regime");
+        }
+    }
+
+    #[test]
+    fn test_wide_spread_opens_short_spread_in_ranging_regime() {
+        let mut strat = PairsStrategy::new(PairsConfig {
+            window: 20,
+            entry_zscore: 0.5,
+            max_adf_pvalue: 1.0, // ignore the cointegration gate to isolate z-score entry logic
+            ..PairsConfig::default()
+        });
+        for _ in 0..20 {
+            strat.update(MarketRegime::MeanReverting, 100.0, 100.0);
+        }
+        // One bar where A spikes far above B widens the spread sharply
+        let pos = strat.update(MarketRegime::MeanReverting, 140.0, 100.0);
+        assert_eq!(pos, PairPosition::ShortSpread);
+    }
+
+    #[test]
+    fn test_decointegration_forces_flat_regardless_of_zscore() {
+        let mut strat = PairsStrategy::new(PairsConfig {
+            window: 20,
+            entry_zscore: 0.1,
+            max_adf_pvalue: 1.0,
+            stop_adf_pvalue: 0.0, // any measured p-value trips the decointegration stop
+            ..PairsConfig::default()
+        });
+        for _ in 0..20 {
+            strat.update(MarketRegime::MeanReverting, 100.0, 100.0);
+        }
+        strat.update(MarketRegime::MeanReverting, 140.0, 100.0);
+        let pos = strat.update(MarketRegime::MeanReverting, 140.0, 100.0);
+        assert_eq!(pos, PairPosition::Flat);
+    }
+
+    #[test]
+    fn test_adf_pvalue_is_low_for_a_clearly_mean_reverting_spread() {
+        let oscillating: Vec<f64> = (0..200)
+            .map(|i| 10.0 * ((i as f64 * 0.3).sin()))
+            .collect();
+        assert!(adf_pvalue(&oscillating) < 0.10);
+    }
+
+    #[test]
+    fn test_adf_pvalue_is_high_for_a_random_walk() {
+        let mut walk = Vec::with_capacity(200);
+        let mut v = 0.0;
+        for i in 0..200 {
+            v += if i % 2 == 0 { 1.0 } else { -0.8 };
+            walk.push(v);
+        }
+        assert!(adf_pvalue(&walk) > 0.10);
+    }
+}
@@ -0,0 +1,210 @@
+//! Position Sizing
+//!
+//! `StrategyRouter` previously hard-coded a single `volatile_position_size_factor`
+//! applied only in `Volatile` regimes. `PositionSizer` generalizes this into a
+//! pluggable multiplier applied to every routed signal's base regime factor,
+//! so the router can swap in a volatility-targeting or Kelly-based sizer
+//! without touching regime-selection logic.
+
+use crate::strategy::router::RouterStats;
+
+/// Inputs available to a `PositionSizer` when computing a sizing multiplier
+#[derive(Debug, Clone, Copy)]
+pub struct SizingContext<'a> {
+    /// Base position-size factor selected by regime (e.g. reduced in `Volatile`)
+    pub base_factor: f64,
+    /// Current ATR for the asset, if the detector has warmed up
+    pub atr: Option<f64>,
+    /// Current close price, used to normalize ATR into a volatility fraction
+    pub price: f64,
+    /// Router-wide trade/signal history, for sizers that learn from outcomes
+    pub stats: &'a RouterStats,
+}
+
+/// Produces the final `position_size_factor` applied to a `RoutedSignal`
+pub trait PositionSizer: std::fmt::Debug {
+    /// Compute the position-size factor for the current bar
+    fn size_factor(&self, ctx: &SizingContext) -> f64;
+}
+
+/// Passes the regime-selected base factor through unchanged
+///
+/// This reproduces the router's original behavior and is the default sizer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedFactorSizer;
+
+impl PositionSizer for FixedFactorSizer {
+    fn size_factor(&self, ctx: &SizingContext) -> f64 {
+        ctx.base_factor
+    }
+}
+
+/// Scales the base factor so realized volatility matches a daily target
+///
+/// `size = target_daily_vol / realized_vol`, where realized volatility is
+/// approximated as ATR normalized by price (a percent-range proxy for daily
+/// vol), capped at 1.0 so the sizer never levers up beyond the base factor.
+#[derive(Debug, Clone, Copy)]
+pub struct VolatilityTargetSizer {
+    pub target_daily_vol: f64,
+}
+
+impl Default for VolatilityTargetSizer {
+    fn default() -> Self {
+        Self {
+            target_daily_vol: 0.02, // 2% daily vol target
+        }
+    }
+}
+
+impl PositionSizer for VolatilityTargetSizer {
+    fn size_factor(&self, ctx: &SizingContext) -> f64 {
+        let Some(atr) = ctx.atr else {
+            return ctx.base_factor;
+        };
+        if ctx.price <= 0.0 {
+            return ctx.base_factor;
+        }
+
+        let realized_vol = atr / ctx.price;
+        if realized_vol <= 0.0 {
+            return ctx.base_factor;
+        }
+
+        let scale = (self.target_daily_vol / realized_vol).min(1.0);
+        ctx.base_factor * scale
+    }
+}
+
+/// Fractional-Kelly sizer driven by the router's running win rate and payoff
+///
+/// `f = win_rate - (1 - win_rate) / payoff`, where `payoff` is the average
+/// win divided by the average loss. The raw Kelly fraction is clamped to
+/// `[0.0, 1.0]` and scaled by `kelly_fraction` (a "half-Kelly" style haircut)
+/// before multiplying the regime-selected base factor. Falls back to the
+/// base factor until `RouterStats` has a positive `avg_loss`, i.e. at least
+/// one losing trade with a nonzero return.
+#[derive(Debug, Clone, Copy)]
+pub struct FractionalKellySizer {
+    pub kelly_fraction: f64,
+}
+
+impl Default for FractionalKellySizer {
+    fn default() -> Self {
+        Self { kelly_fraction: 0.5 }
+    }
+}
+
+impl PositionSizer for FractionalKellySizer {
+    fn size_factor(&self, ctx: &SizingContext) -> f64 {
+        let trades = ctx.stats.wins + ctx.stats.losses;
+        if trades == 0 || ctx.stats.avg_loss <= 0.0 {
+            return ctx.base_factor;
+        }
+
+        let win_rate = ctx.stats.wins as f64 / trades as f64;
+        let payoff = ctx.stats.avg_win / ctx.stats.avg_loss;
+        if payoff <= 0.0 {
+            return 0.0;
+        }
+
+        let kelly = win_rate - (1.0 - win_rate) / payoff;
+        let kelly = kelly.clamp(0.0, 1.0) * self.kelly_fraction;
+        ctx.base_factor * kelly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats_with_outcomes(wins: u64, losses: u64, avg_win: f64, avg_loss: f64) -> RouterStats {
+        RouterStats {
+            wins,
+            losses,
+            avg_win,
+            avg_loss,
+            ..RouterStats::default()
+        }
+    }
+
+    #[test]
+    fn test_fixed_factor_sizer_passes_through() {
+        let stats = RouterStats::default();
+        let ctx = SizingContext {
+            base_factor: 0.5,
+            atr: Some(10.0),
+            price: 100.0,
+            stats: &stats,
+        };
+        assert_eq!(FixedFactorSizer.size_factor(&ctx), 0.5);
+    }
+
+    #[test]
+    fn test_volatility_target_sizer_scales_down_high_vol() {
+        let stats = RouterStats::default();
+        let sizer = VolatilityTargetSizer { target_daily_vol: 0.02 };
+        // ATR/price = 0.04, double the 2% target -> scale to 0.5
+        let ctx = SizingContext {
+            base_factor: 1.0,
+            atr: Some(4.0),
+            price: 100.0,
+            stats: &stats,
+        };
+        assert!((sizer.size_factor(&ctx) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volatility_target_sizer_caps_at_base_factor() {
+        let stats = RouterStats::default();
+        let sizer = VolatilityTargetSizer { target_daily_vol: 0.02 };
+        // Low vol would scale above 1.0, but the cap keeps it at base_factor
+        let ctx = SizingContext {
+            base_factor: 1.0,
+            atr: Some(0.5),
+            price: 100.0,
+            stats: &stats,
+        };
+        assert_eq!(sizer.size_factor(&ctx), 1.0);
+    }
+
+    #[test]
+    fn test_kelly_sizer_with_no_history_passes_through() {
+        let stats = RouterStats::default();
+        let sizer = FractionalKellySizer::default();
+        let ctx = SizingContext {
+            base_factor: 0.8,
+            atr: None,
+            price: 100.0,
+            stats: &stats,
+        };
+        assert_eq!(sizer.size_factor(&ctx), 0.8);
+    }
+
+    #[test]
+    fn test_kelly_sizer_scales_with_edge() {
+        let stats = stats_with_outcomes(7, 3, 2.0, 1.0);
+        let sizer = FractionalKellySizer { kelly_fraction: 1.0 };
+        // win_rate=0.7, payoff=2.0 -> kelly = 0.7 - 0.3/2.0 = 0.55
+        let ctx = SizingContext {
+            base_factor: 1.0,
+            atr: None,
+            price: 100.0,
+            stats: &stats,
+        };
+        assert!((sizer.size_factor(&ctx) - 0.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kelly_sizer_negative_edge_floors_at_zero() {
+        let stats = stats_with_outcomes(2, 8, 1.0, 1.0);
+        let sizer = FractionalKellySizer { kelly_fraction: 1.0 };
+        let ctx = SizingContext {
+            base_factor: 1.0,
+            atr: None,
+            price: 100.0,
+            stats: &stats,
+        };
+        assert_eq!(sizer.size_factor(&ctx), 0.0);
+    }
+}
@@ -0,0 +1,311 @@
+//! Strategy Registry
+//!
+//! `Strategy` is the common interface every pluggable strategy implements,
+//! plus metadata describing it (name, type, suited regimes, whether it
+//! supports backtesting, its tunable parameters) independent of any one
+//! instance's live state. `StrategyRegistry` catalogs that metadata so
+//! `StrategyRouter` can pick a strategy by regime suitability, and so
+//! callers can enumerate/filter the available strategies by regime, type,
+//! or trade frequency without reaching into the router's internals.
+
+use std::mem::discriminant;
+
+use crate::regime::MarketRegime;
+use crate::strategy::grid::GridStrategy;
+use crate::strategy::mean_reversion::{MeanReversionStrategy, Signal};
+use crate::strategy::router::ActiveStrategy;
+use crate::strategy::trend_following::TrendFollowingStrategy;
+
+/// How a strategy makes its money, for categorizing it in a registry listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    /// Provides resting liquidity rather than crossing the spread (e.g. a grid ladder)
+    Maker,
+    /// Crosses the spread to enter and exit (e.g. trend-following, mean reversion)
+    Taker,
+    /// Can hold simultaneous long and short legs (e.g. a pairs strategy)
+    LongShort,
+}
+
+/// How often a strategy is expected to turn over positions, for filtering
+/// a `StrategyRegistry` listing by trade frequency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TradeFrequency {
+    Low,
+    Medium,
+    High,
+}
+
+/// One tunable parameter a strategy's config exposes, for generic
+/// introspection by a registry consumer that doesn't know the concrete
+/// config type
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyParameter {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Static metadata a `Strategy` declares about itself, independent of any
+/// instance's live state
+#[derive(Debug, Clone)]
+pub struct StrategyMetadata {
+    pub name: &'static str,
+    pub kind: StrategyKind,
+    pub frequency: TradeFrequency,
+    /// `MarketRegime` variants this strategy is suited for - see
+    /// [`StrategyMetadata::suits`].
+    pub suited_regimes: Vec<MarketRegime>,
+    pub supports_backtesting: bool,
+    pub parameters: Vec<StrategyParameter>,
+}
+
+impl StrategyMetadata {
+    /// Whether `regime` is one of `suited_regimes`, comparing by variant
+    /// only - a strategy suited for `Trending` is suited for both trend
+    /// directions without needing to list each one.
+    pub fn suits(&self, regime: MarketRegime) -> bool {
+        self.suited_regimes
+            .iter()
+            .any(|suited| discriminant(suited) == discriminant(&regime))
+    }
+}
+
+/// Common interface every pluggable strategy implements, so `StrategyRouter`
+/// (or any other consumer) can reason about a strategy through its
+/// metadata rather than a hard-coded `ActiveStrategy` match.
+pub trait Strategy: std::fmt::Debug {
+    /// Metadata describing this strategy, independent of its live state
+    fn metadata(&self) -> StrategyMetadata;
+
+    /// Update with a new OHLC bar and return the resulting signal
+    fn update(&mut self, high: f64, low: f64, close: f64) -> Signal;
+
+    /// Whether the strategy has enough history to generate a signal
+    fn is_ready(&self) -> bool;
+
+    /// Current stop-loss level, if the strategy tracks one
+    fn stop_loss(&self) -> Option<f64> {
+        None
+    }
+
+    /// Current take-profit level, if the strategy tracks one
+    fn take_profit(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl Strategy for MeanReversionStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        StrategyMetadata {
+            name: "Mean Reversion",
+            kind: StrategyKind::Taker,
+            frequency: TradeFrequency::Medium,
+            suited_regimes: vec![MarketRegime::MeanReverting, MarketRegime::Volatile, MarketRegime::Squeeze],
+            supports_backtesting: true,
+            parameters: vec![
+                StrategyParameter { name: "bb_period", description: "Bollinger Bands lookback period" },
+                StrategyParameter { name: "rsi_period", description: "RSI lookback period" },
+            ],
+        }
+    }
+
+    fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        MeanReversionStrategy::update(self, high, low, close)
+    }
+
+    fn is_ready(&self) -> bool {
+        MeanReversionStrategy::is_ready(self)
+    }
+
+    fn stop_loss(&self) -> Option<f64> {
+        MeanReversionStrategy::stop_loss(self)
+    }
+
+    fn take_profit(&self) -> Option<f64> {
+        MeanReversionStrategy::take_profit(self)
+    }
+}
+
+impl Strategy for TrendFollowingStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        StrategyMetadata {
+            name: "Trend Following",
+            kind: StrategyKind::Taker,
+            frequency: TradeFrequency::Low,
+            suited_regimes: vec![MarketRegime::Trending(crate::regime::TrendDirection::Bullish)],
+            supports_backtesting: true,
+            parameters: vec![
+                StrategyParameter { name: "ema_fast_period", description: "Fast EMA period" },
+                StrategyParameter { name: "ema_slow_period", description: "Slow EMA period" },
+                StrategyParameter { name: "rsi_period", description: "RSI confirmation period" },
+            ],
+        }
+    }
+
+    fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        TrendFollowingStrategy::update(self, high, low, close)
+    }
+
+    fn is_ready(&self) -> bool {
+        TrendFollowingStrategy::is_ready(self)
+    }
+
+    fn stop_loss(&self) -> Option<f64> {
+        TrendFollowingStrategy::stop_loss(self)
+    }
+
+    fn take_profit(&self) -> Option<f64> {
+        TrendFollowingStrategy::take_profit(self)
+    }
+}
+
+impl Strategy for GridStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        StrategyMetadata {
+            name: "Grid",
+            kind: StrategyKind::Maker,
+            frequency: TradeFrequency::High,
+            suited_regimes: vec![MarketRegime::MeanReverting],
+            supports_backtesting: false,
+            parameters: vec![
+                StrategyParameter { name: "spacing", description: "Spacing between adjacent grid levels" },
+                StrategyParameter { name: "levels_per_side", description: "Number of buy/sell levels on each side of center" },
+            ],
+        }
+    }
+
+    fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        GridStrategy::update(self, high, low, close)
+    }
+
+    fn is_ready(&self) -> bool {
+        GridStrategy::is_ready(self)
+    }
+}
+
+/// Catalog of `StrategyMetadata` for every strategy `StrategyRouter` knows
+/// how to run, keyed by the `ActiveStrategy` it corresponds to so a
+/// regime-based lookup hands back something the router can act on
+/// directly rather than just a display name.
+#[derive(Debug, Clone)]
+pub struct StrategyRegistry {
+    entries: Vec<(ActiveStrategy, StrategyMetadata)>,
+}
+
+impl StrategyRegistry {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Registry covering the router's three built-in strategies
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(ActiveStrategy::TrendFollowing, TrendFollowingStrategy::default_config().metadata());
+        registry.register(ActiveStrategy::MeanReversion, MeanReversionStrategy::default_config().metadata());
+        registry.register(ActiveStrategy::Grid, GridStrategy::default_config().metadata());
+        registry
+    }
+
+    pub fn register(&mut self, strategy: ActiveStrategy, metadata: StrategyMetadata) {
+        self.entries.push((strategy, metadata));
+    }
+
+    /// Every registered strategy whose metadata is suited for `regime`, in
+    /// registration order
+    pub fn suited_for(&self, regime: MarketRegime) -> Vec<ActiveStrategy> {
+        self.entries
+            .iter()
+            .filter(|(_, metadata)| metadata.suits(regime))
+            .map(|(strategy, _)| *strategy)
+            .collect()
+    }
+
+    /// Every registered strategy of `kind`
+    pub fn by_kind(&self, kind: StrategyKind) -> Vec<ActiveStrategy> {
+        self.entries
+            .iter()
+            .filter(|(_, metadata)| metadata.kind == kind)
+            .map(|(strategy, _)| *strategy)
+            .collect()
+    }
+
+    /// Every registered strategy at or below `max_frequency`
+    pub fn by_frequency(&self, max_frequency: TradeFrequency) -> Vec<ActiveStrategy> {
+        self.entries
+            .iter()
+            .filter(|(_, metadata)| metadata.frequency <= max_frequency)
+            .map(|(strategy, _)| *strategy)
+            .collect()
+    }
+
+    /// Metadata for a specific registered strategy
+    pub fn metadata(&self, strategy: ActiveStrategy) -> Option<&StrategyMetadata> {
+        self.entries.iter().find(|(s, _)| *s == strategy).map(|(_, metadata)| metadata)
+    }
+
+    /// Every registered `(ActiveStrategy, StrategyMetadata)` pair
+    pub fn all(&self) -> &[(ActiveStrategy, StrategyMetadata)] {
+        &self.entries
+    }
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regime::TrendDirection;
+
+    #[test]
+    fn test_with_builtins_registers_all_three_strategies() {
+        let registry = StrategyRegistry::with_builtins();
+        assert!(registry.metadata(ActiveStrategy::TrendFollowing).is_some());
+        assert!(registry.metadata(ActiveStrategy::MeanReversion).is_some());
+        assert!(registry.metadata(ActiveStrategy::Grid).is_some());
+    }
+
+    #[test]
+    fn test_suited_for_matches_trending_regardless_of_direction() {
+        let registry = StrategyRegistry::with_builtins();
+
+        let bullish = registry.suited_for(MarketRegime::Trending(TrendDirection::Bullish));
+        let bearish = registry.suited_for(MarketRegime::Trending(TrendDirection::Bearish));
+
+        assert_eq!(bullish, vec![ActiveStrategy::TrendFollowing]);
+        assert_eq!(bearish, vec![ActiveStrategy::TrendFollowing]);
+    }
+
+    #[test]
+    fn test_suited_for_mean_reverting_returns_both_grid_and_mean_reversion() {
+        let registry = StrategyRegistry::with_builtins();
+        let candidates = registry.suited_for(MarketRegime::MeanReverting);
+
+        assert!(candidates.contains(&ActiveStrategy::Grid));
+        assert!(candidates.contains(&ActiveStrategy::MeanReversion));
+    }
+
+    #[test]
+    fn test_by_kind_filters_maker_vs_taker() {
+        let registry = StrategyRegistry::with_builtins();
+
+        assert_eq!(registry.by_kind(StrategyKind::Maker), vec![ActiveStrategy::Grid]);
+        let takers = registry.by_kind(StrategyKind::Taker);
+        assert!(takers.contains(&ActiveStrategy::TrendFollowing));
+        assert!(takers.contains(&ActiveStrategy::MeanReversion));
+    }
+
+    #[test]
+    fn test_by_frequency_excludes_higher_frequency_strategies() {
+        let registry = StrategyRegistry::with_builtins();
+
+        // Grid is High frequency, so a Medium cap excludes it
+        let medium_or_lower = registry.by_frequency(TradeFrequency::Medium);
+        assert!(!medium_or_lower.contains(&ActiveStrategy::Grid));
+        assert!(medium_or_lower.contains(&ActiveStrategy::MeanReversion));
+        assert!(medium_or_lower.contains(&ActiveStrategy::TrendFollowing));
+    }
+}
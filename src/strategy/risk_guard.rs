@@ -0,0 +1,219 @@
+//! Risk Guard
+//!
+//! Tracks rolling equity per asset and portfolio-wide, and halts trading
+//! when relative drawdown breaches a configurable limit, inspired by the
+//! relative-drawdown risk controls common in prop-trading risk desks.
+//! Relative drawdown is `(peak - equity) / peak`; once it crosses
+//! `max_relative_drawdown` the guard stays halted until equity recovers
+//! above `re_entry_fraction` of the peak. Operators can also halt assets
+//! manually via `force_exit`/`force_exit_all`, independent of drawdown.
+
+use std::collections::HashMap;
+
+/// Configuration for `RiskGuard`
+#[derive(Debug, Clone)]
+pub struct RiskGuardConfig {
+    /// Relative drawdown (0.0-1.0) at which trading halts
+    pub max_relative_drawdown: f64,
+    /// Fraction of peak equity that must be recovered before resuming
+    pub re_entry_fraction: f64,
+}
+
+impl Default for RiskGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_relative_drawdown: 0.2,
+            re_entry_fraction: 0.95,
+        }
+    }
+}
+
+/// Peak/current equity tracker with drawdown-triggered hysteresis
+#[derive(Debug, Clone)]
+struct EquityTrack {
+    peak: f64,
+    current: f64,
+    halted: bool,
+}
+
+impl EquityTrack {
+    fn new(starting_equity: f64) -> Self {
+        Self {
+            peak: starting_equity,
+            current: starting_equity,
+            halted: false,
+        }
+    }
+
+    fn relative_drawdown(&self) -> f64 {
+        if self.peak <= 0.0 {
+            0.0
+        } else {
+            (self.peak - self.current) / self.peak
+        }
+    }
+
+    fn update(&mut self, equity: f64, config: &RiskGuardConfig) {
+        self.current = equity;
+        self.peak = f64::max(self.peak, equity);
+
+        if self.relative_drawdown() >= config.max_relative_drawdown {
+            self.halted = true;
+        } else if self.halted && self.current >= self.peak * config.re_entry_fraction {
+            self.halted = false;
+        }
+    }
+}
+
+/// Drawdown-aware kill-switch shared by every asset `StrategyRouter` tracks
+#[derive(Debug)]
+pub struct RiskGuard {
+    config: RiskGuardConfig,
+    portfolio: EquityTrack,
+    assets: HashMap<String, EquityTrack>,
+    forced: HashMap<String, bool>,
+    forced_all: bool,
+}
+
+impl RiskGuard {
+    pub fn new(config: RiskGuardConfig) -> Self {
+        Self {
+            config,
+            portfolio: EquityTrack::new(1.0),
+            assets: HashMap::new(),
+            forced: HashMap::new(),
+            forced_all: false,
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(RiskGuardConfig::default())
+    }
+
+    pub fn config(&self) -> &RiskGuardConfig {
+        &self.config
+    }
+
+    /// Update an asset's equity mark, recomputing its drawdown halt state
+    pub fn update_asset_equity(&mut self, symbol: &str, equity: f64) {
+        self.assets
+            .entry(symbol.to_string())
+            .or_insert_with(|| EquityTrack::new(equity))
+            .update(equity, &self.config);
+    }
+
+    /// Update the shared portfolio-wide equity mark
+    pub fn update_portfolio_equity(&mut self, equity: f64) {
+        self.portfolio.update(equity, &self.config);
+    }
+
+    /// Whether `symbol` should be force-flattened and kept out of the market
+    pub fn is_halted(&self, symbol: &str) -> bool {
+        self.forced_all
+            || self.forced.get(symbol).copied().unwrap_or(false)
+            || self.portfolio.halted
+            || self.assets.get(symbol).map(|t| t.halted).unwrap_or(false)
+    }
+
+    /// Relative drawdown for a single asset, if it has been tracked
+    pub fn asset_drawdown(&self, symbol: &str) -> Option<f64> {
+        self.assets.get(symbol).map(|t| t.relative_drawdown())
+    }
+
+    /// Relative drawdown of the shared portfolio equity mark
+    pub fn portfolio_drawdown(&self) -> f64 {
+        self.portfolio.relative_drawdown()
+    }
+
+    /// Manually halt and flatten a single asset, regardless of drawdown
+    pub fn force_exit(&mut self, symbol: &str) {
+        self.forced.insert(symbol.to_string(), true);
+    }
+
+    /// Manually halt and flatten every asset, regardless of drawdown
+    pub fn force_exit_all(&mut self) {
+        self.forced_all = true;
+    }
+
+    /// Clear a manual halt on a single asset (drawdown-triggered halts still apply)
+    pub fn resume(&mut self, symbol: &str) {
+        self.forced.remove(symbol);
+    }
+
+    /// Clear the manual halt-all flag (drawdown-triggered halts still apply)
+    pub fn resume_all(&mut self) {
+        self.forced_all = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_halts_when_drawdown_exceeds_limit() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            max_relative_drawdown: 0.1,
+            re_entry_fraction: 0.95,
+        });
+
+        guard.update_asset_equity("BTC/USD", 100.0);
+        assert!(!guard.is_halted("BTC/USD"));
+
+        guard.update_asset_equity("BTC/USD", 85.0); // 15% drawdown
+        assert!(guard.is_halted("BTC/USD"));
+    }
+
+    #[test]
+    fn test_resumes_after_recovering_past_re_entry_fraction() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            max_relative_drawdown: 0.1,
+            re_entry_fraction: 0.95,
+        });
+
+        guard.update_asset_equity("BTC/USD", 100.0);
+        guard.update_asset_equity("BTC/USD", 85.0);
+        assert!(guard.is_halted("BTC/USD"));
+
+        guard.update_asset_equity("BTC/USD", 94.0); // below re-entry fraction of peak
+        assert!(guard.is_halted("BTC/USD"));
+
+        guard.update_asset_equity("BTC/USD", 96.0); // above re-entry fraction of peak
+        assert!(!guard.is_halted("BTC/USD"));
+    }
+
+    #[test]
+    fn test_portfolio_wide_drawdown_halts_every_asset() {
+        let mut guard = RiskGuard::default_config();
+        guard.update_asset_equity("BTC/USD", 100.0);
+        guard.update_asset_equity("ETH/USD", 100.0);
+
+        guard.update_portfolio_equity(1000.0);
+        guard.update_portfolio_equity(750.0); // 25% portfolio drawdown
+
+        assert!(guard.is_halted("BTC/USD"));
+        assert!(guard.is_halted("ETH/USD"));
+    }
+
+    #[test]
+    fn test_force_exit_overrides_drawdown_state() {
+        let mut guard = RiskGuard::default_config();
+        guard.update_asset_equity("BTC/USD", 100.0);
+        guard.update_asset_equity("ETH/USD", 100.0);
+
+        guard.force_exit("BTC/USD");
+        assert!(guard.is_halted("BTC/USD"));
+        assert!(!guard.is_halted("ETH/USD"));
+
+        guard.resume("BTC/USD");
+        assert!(!guard.is_halted("BTC/USD"));
+
+        guard.force_exit_all();
+        assert!(guard.is_halted("BTC/USD"));
+        assert!(guard.is_halted("ETH/USD"));
+
+        guard.resume_all();
+        assert!(!guard.is_halted("BTC/USD"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Leverage-Aware Risk Sizing
+//!
+//! `EnhancedRouter::trend_signal` used to fabricate a stop/target from a
+//! crude `close * 0.02` ATR guess and left bearish trades with no
+//! stop/target at all. `RiskModel` replaces that guesswork: given an entry
+//! price, trend direction, configured leverage and maintenance-margin
+//! fraction, it computes the required margin, the liquidation price for a
+//! long or short position, and an ATR-scaled stop/target whose stop never
+//! lies beyond the liquidation price (it is clamped to that price) so a
+//! trade can never be sized past the point where the exchange would
+//! force-close it first.
+
+use crate::regime::TrendDirection;
+
+/// Configuration for `RiskModel`
+#[derive(Debug, Clone, Copy)]
+pub struct RiskModelConfig {
+    /// Leverage applied to the notional position, e.g. `3.0` for 3x
+    pub leverage: f64,
+    /// Maintenance-margin fraction of notional the exchange requires before
+    /// force-liquidating, e.g. `0.005` for 0.5%
+    pub maintenance_margin: f64,
+    /// Stop-loss distance in ATR multiples
+    pub atr_stop_multiple: f64,
+    /// Take-profit distance in ATR multiples
+    pub atr_target_multiple: f64,
+}
+
+impl Default for RiskModelConfig {
+    fn default() -> Self {
+        Self {
+            leverage: 1.0,
+            maintenance_margin: 0.005,
+            atr_stop_multiple: 2.0,
+            atr_target_multiple: 3.0,
+        }
+    }
+}
+
+/// Margin, liquidation and stop/target levels for a single trade
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskSizing {
+    /// Required margin to hold one unit of the asset at the configured
+    /// leverage (`entry / leverage`)
+    pub margin: f64,
+    /// Price at which the position is force-liquidated
+    pub liquidation_price: f64,
+    /// Leverage used to compute this sizing
+    pub leverage: f64,
+    /// Stop-loss price, never beyond the liquidation price (clamped to it)
+    pub stop_loss: f64,
+    /// Take-profit price
+    pub take_profit: f64,
+}
+
+/// Computes margin, liquidation price and ATR-scaled stop/target for a
+/// leveraged position, so a stop can be checked against the liquidation
+/// boundary before the trade is sized.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskModel {
+    config: RiskModelConfig,
+}
+
+impl RiskModel {
+    pub fn new(config: RiskModelConfig) -> Self {
+        Self { config }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(RiskModelConfig::default())
+    }
+
+    pub fn config(&self) -> &RiskModelConfig {
+        &self.config
+    }
+
+    /// Margin required to hold one unit of the asset opened at `entry`, at
+    /// the configured leverage
+    pub fn margin(&self, entry: f64) -> f64 {
+        entry / self.config.leverage
+    }
+
+    /// Price at which a position opened at `entry` in `direction` is
+    /// force-liquidated: long ≈ `entry·(1 - 1/leverage + maint)`, short ≈
+    /// `entry·(1 + 1/leverage - maint)`
+    pub fn liquidation_price(&self, entry: f64, direction: TrendDirection) -> f64 {
+        let inv_leverage = 1.0 / self.config.leverage;
+        let maint = self.config.maintenance_margin;
+        match direction {
+            TrendDirection::Bullish => entry * (1.0 - inv_leverage + maint),
+            TrendDirection::Bearish => entry * (1.0 + inv_leverage - maint),
+        }
+    }
+
+    /// Margin, liquidation price and an ATR-scaled stop/target for a trade
+    /// opened at `entry` in `direction`. The stop is clamped to the
+    /// liquidation price when the ATR-scaled stop would lie beyond it, so a
+    /// trade can never carry a stop the exchange would never let it reach.
+    pub fn size(&self, entry: f64, direction: TrendDirection, atr: f64) -> RiskSizing {
+        let liquidation_price = self.liquidation_price(entry, direction);
+        let stop_distance = atr * self.config.atr_stop_multiple;
+        let target_distance = atr * self.config.atr_target_multiple;
+
+        let (stop_loss, take_profit) = match direction {
+            TrendDirection::Bullish => {
+                let raw_stop = entry - stop_distance;
+                (raw_stop.max(liquidation_price), entry + target_distance)
+            }
+            TrendDirection::Bearish => {
+                let raw_stop = entry + stop_distance;
+                (raw_stop.min(liquidation_price), entry - target_distance)
+            }
+        };
+
+        RiskSizing {
+            margin: self.margin(entry),
+            liquidation_price,
+            leverage: self.config.leverage,
+            stop_loss,
+            take_profit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_margin_scales_with_leverage() {
+        let model = RiskModel::new(RiskModelConfig { leverage: 4.0, ..Default::default() });
+        assert_eq!(model.margin(1000.0), 250.0);
+    }
+
+    #[test]
+    fn test_long_liquidation_below_entry() {
+        let model = RiskModel::new(RiskModelConfig {
+            leverage: 5.0,
+            maintenance_margin: 0.01,
+            ..Default::default()
+        });
+        let liq = model.liquidation_price(100.0, TrendDirection::Bullish);
+        assert!(liq < 100.0);
+        assert!((liq - 81.0).abs() < 1e-9); // 100 * (1 - 0.2 + 0.01)
+    }
+
+    #[test]
+    fn test_short_liquidation_above_entry() {
+        let model = RiskModel::new(RiskModelConfig {
+            leverage: 5.0,
+            maintenance_margin: 0.01,
+            ..Default::default()
+        });
+        let liq = model.liquidation_price(100.0, TrendDirection::Bearish);
+        assert!(liq > 100.0);
+        assert!((liq - 119.0).abs() < 1e-9); // 100 * (1 + 0.2 - 0.01)
+    }
+
+    #[test]
+    fn test_stop_clamped_inside_liquidation_boundary() {
+        // High leverage + wide ATR would push the raw stop past liquidation
+        let model = RiskModel::new(RiskModelConfig {
+            leverage: 10.0,
+            maintenance_margin: 0.005,
+            atr_stop_multiple: 5.0,
+            ..Default::default()
+        });
+        let sizing = model.size(100.0, TrendDirection::Bullish, 3.0);
+        assert!(sizing.stop_loss >= sizing.liquidation_price);
+        assert_eq!(sizing.stop_loss, sizing.liquidation_price);
+    }
+
+    #[test]
+    fn test_bearish_sizing_populates_stop_and_target() {
+        let model = RiskModel::default_config();
+        let sizing = model.size(100.0, TrendDirection::Bearish, 1.5);
+        assert!(sizing.stop_loss > 100.0);
+        assert!(sizing.take_profit < 100.0);
+        assert!(sizing.liquidation_price > 100.0);
+    }
+}
@@ -8,8 +8,13 @@
 //! - Using mean reversion in ranging markets
 //! - Reducing exposure in volatile/choppy markets
 
-use crate::regime::{MarketRegime, RegimeConfidence, RegimeConfig, RegimeDetector, TrendDirection};
+use crate::regime::{MarketRegime, PositionDirection, RegimeConfidence, RegimeConfig, RegimeDetector};
+use crate::strategy::grid::{GridConfig, GridStrategy};
 use crate::strategy::mean_reversion::{MeanReversionConfig, MeanReversionStrategy, Signal};
+use crate::strategy::position_sizing::{FixedFactorSizer, PositionSizer, SizingContext};
+use crate::strategy::registry::StrategyRegistry;
+use crate::strategy::risk_guard::{RiskGuard, RiskGuardConfig};
+use crate::strategy::trend_following::{TrendFollowingConfig, TrendFollowingStrategy};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -22,6 +27,15 @@ pub struct StrategyRouterConfig {
     /// Mean reversion strategy config
     pub mean_reversion_config: MeanReversionConfig,
 
+    /// Grid-trading strategy config, used when `use_grid_for_ranging` is set
+    pub grid_config: GridConfig,
+
+    /// Select `ActiveStrategy::Grid` instead of `MeanReversion` in a
+    /// `MeanReverting` regime once ADX drops below `regime_config`'s
+    /// `adx_ranging_threshold` - the chop is calm enough for a maker ladder
+    /// rather than a single round-trip bet on reversion to the mean.
+    pub use_grid_for_ranging: bool,
+
     /// Position size reduction factor when in volatile regime
     pub volatile_position_size_factor: f64,
 
@@ -34,6 +48,12 @@ pub struct StrategyRouterConfig {
     /// EMA periods for trend following (your existing Golden Cross)
     pub trend_ema_short: usize,
     pub trend_ema_long: usize,
+
+    /// Allow opening short positions in bearish trending regimes
+    pub can_short: bool,
+
+    /// Maximum leverage multiplier applied to routed position sizing
+    pub max_leverage: f64,
 }
 
 impl Default for StrategyRouterConfig {
@@ -41,11 +61,15 @@ impl Default for StrategyRouterConfig {
         Self {
             regime_config: RegimeConfig::crypto_optimized(),
             mean_reversion_config: MeanReversionConfig::default(),
+            grid_config: GridConfig::default(),
+            use_grid_for_ranging: false,
             volatile_position_size_factor: 0.5, // Half position in volatile markets
             min_regime_confidence: 0.5,
             log_regime_changes: true,
             trend_ema_short: 50,
             trend_ema_long: 200,
+            can_short: false,
+            max_leverage: 1.0,
         }
     }
 }
@@ -76,15 +100,26 @@ pub struct RoutedSignal {
 
     /// Take profit level if applicable
     pub take_profit: Option<f64>,
+
+    /// Direction of the position this signal opens or holds
+    pub direction: PositionDirection,
+
+    /// Leverage multiplier to apply to position sizing
+    pub leverage: f64,
+
+    /// Whether `RiskGuard` forced this signal (drawdown halt or manual `force_exit`)
+    pub risk_halted: bool,
 }
 
 /// Currently active strategy
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ActiveStrategy {
     /// EMA Golden Cross / Pullback - trend following
     TrendFollowing,
     /// Bollinger Bands mean reversion
     MeanReversion,
+    /// Staggered buy/sell ladder around a center price
+    Grid,
     /// Staying out of market
     NoTrade,
 }
@@ -94,6 +129,7 @@ impl std::fmt::Display for ActiveStrategy {
         match self {
             ActiveStrategy::TrendFollowing => write!(f, "Trend Following"),
             ActiveStrategy::MeanReversion => write!(f, "Mean Reversion"),
+            ActiveStrategy::Grid => write!(f, "Grid"),
             ActiveStrategy::NoTrade => write!(f, "No Trade"),
         }
     }
@@ -104,6 +140,8 @@ impl std::fmt::Display for ActiveStrategy {
 struct AssetState {
     regime_detector: RegimeDetector,
     mean_reversion: MeanReversionStrategy,
+    trend_following: TrendFollowingStrategy,
+    grid: GridStrategy,
     current_strategy: ActiveStrategy,
     last_regime: MarketRegime,
     regime_change_count: u32,
@@ -114,6 +152,13 @@ impl AssetState {
         Self {
             regime_detector: RegimeDetector::new(config.regime_config.clone()),
             mean_reversion: MeanReversionStrategy::new(config.mean_reversion_config.clone()),
+            trend_following: TrendFollowingStrategy::new(TrendFollowingConfig {
+                ema_fast_period: config.trend_ema_short,
+                ema_slow_period: config.trend_ema_long,
+                allow_short: config.can_short,
+                ..TrendFollowingConfig::default()
+            }),
+            grid: GridStrategy::new(config.grid_config.clone()),
             current_strategy: ActiveStrategy::NoTrade,
             last_regime: MarketRegime::Uncertain,
             regime_change_count: 0,
@@ -128,6 +173,14 @@ impl AssetState {
 pub struct StrategyRouter {
     config: StrategyRouterConfig,
     assets: HashMap<String, AssetState>,
+    position_sizer: Box<dyn PositionSizer>,
+    stats: RouterStats,
+    risk_guard: RiskGuard,
+    /// Catalog of the strategies this router can run, consulted by
+    /// `select_strategy` for regime suitability instead of a hard-coded
+    /// `MarketRegime -> ActiveStrategy` match, and available to callers
+    /// that want to enumerate/filter the available strategies themselves.
+    registry: StrategyRegistry,
 }
 
 impl StrategyRouter {
@@ -135,14 +188,80 @@ impl StrategyRouter {
         Self {
             config,
             assets: HashMap::new(),
+            position_sizer: Box::new(FixedFactorSizer),
+            stats: RouterStats::default(),
+            risk_guard: RiskGuard::default_config(),
+            registry: StrategyRegistry::with_builtins(),
         }
     }
 
+    /// The strategy catalog this router selects from - enumerate/filter it
+    /// by regime suitability, type, or trade frequency
+    pub fn registry(&self) -> &StrategyRegistry {
+        &self.registry
+    }
+
     /// Create with default config
     pub fn default_config() -> Self {
         Self::new(StrategyRouterConfig::default())
     }
 
+    /// Swap in a different position-sizing strategy (default: `FixedFactorSizer`)
+    pub fn set_position_sizer(&mut self, sizer: Box<dyn PositionSizer>) {
+        self.position_sizer = sizer;
+    }
+
+    /// Router-wide signal/trade-outcome history, consumed by adaptive sizers
+    pub fn stats(&self) -> &RouterStats {
+        &self.stats
+    }
+
+    /// Record the realized return of a closed trade, for sizers that learn
+    /// from win rate and payoff (e.g. `FractionalKellySizer`)
+    pub fn record_trade_outcome(&mut self, return_pct: f64) {
+        self.stats.record_trade_outcome(return_pct);
+    }
+
+    /// Swap in a different drawdown-halt configuration (default: `RiskGuardConfig::default()`)
+    pub fn set_risk_guard_config(&mut self, config: RiskGuardConfig) {
+        self.risk_guard = RiskGuard::new(config);
+    }
+
+    /// Update an asset's equity mark for drawdown-halt tracking
+    pub fn update_asset_equity(&mut self, symbol: &str, equity: f64) {
+        self.risk_guard.update_asset_equity(symbol, equity);
+    }
+
+    /// Update the shared portfolio-wide equity mark for drawdown-halt tracking
+    pub fn update_portfolio_equity(&mut self, equity: f64) {
+        self.risk_guard.update_portfolio_equity(equity);
+    }
+
+    /// Manually halt and flatten a single asset, regardless of drawdown
+    pub fn force_exit(&mut self, symbol: &str) {
+        self.risk_guard.force_exit(symbol);
+    }
+
+    /// Manually halt and flatten every asset, regardless of drawdown
+    pub fn force_exit_all(&mut self) {
+        self.risk_guard.force_exit_all();
+    }
+
+    /// Clear a manual halt on a single asset (drawdown-triggered halts still apply)
+    pub fn resume(&mut self, symbol: &str) {
+        self.risk_guard.resume(symbol);
+    }
+
+    /// Clear the manual halt-all flag (drawdown-triggered halts still apply)
+    pub fn resume_all(&mut self) {
+        self.risk_guard.resume_all();
+    }
+
+    /// Whether `symbol` is currently forced out of the market by `RiskGuard`
+    pub fn is_risk_halted(&self, symbol: &str) -> bool {
+        self.risk_guard.is_halted(symbol)
+    }
+
     /// Register an asset (e.g., "BTC/USD", "ETH/USD", "SOL/USD")
     pub fn register_asset(&mut self, symbol: &str) {
         if !self.assets.contains_key(symbol) {
@@ -186,21 +305,60 @@ impl StrategyRouter {
             state.last_regime = regime_result.regime;
         }
 
+        // RiskGuard overrides regime-based routing: force NoTrade and a
+        // flattening signal while drawdown (or a manual force_exit) is active
+        if self.risk_guard.is_halted(symbol) {
+            state.current_strategy = ActiveStrategy::NoTrade;
+            let routed_signal = RoutedSignal {
+                signal: Signal::Sell,
+                source_strategy: ActiveStrategy::NoTrade,
+                regime: regime_result.regime,
+                confidence: regime_result.confidence,
+                position_size_factor: 0.0,
+                reason: format!(
+                    "RiskHalt: max_relative_drawdown ({:.1}%) breached or force_exit active",
+                    self.risk_guard.config().max_relative_drawdown * 100.0
+                ),
+                stop_loss: None,
+                take_profit: None,
+                direction: PositionDirection::Long,
+                leverage: 1.0,
+                risk_halted: true,
+            };
+            self.stats.record_signal(&routed_signal);
+            return Some(routed_signal);
+        }
+
         // Determine active strategy based on regime
         let (active_strategy, position_factor) = Self::select_strategy(
             &regime_result,
             config.min_regime_confidence,
             config.volatile_position_size_factor,
+            config.use_grid_for_ranging,
+            config.regime_config.adx_ranging_threshold,
+            &self.registry,
         );
 
         state.current_strategy = active_strategy;
 
         // Generate signal based on active strategy
-        let (signal, reason, stop_loss, take_profit) = match active_strategy {
+        let (signal, reason, stop_loss, take_profit, direction) = match active_strategy {
             ActiveStrategy::TrendFollowing => {
-                // Your existing Golden Cross / EMA Pullback logic would go here
-                // For now, returning Hold - integrate with your existing strategies
-                Self::trend_following_signal(&state.regime_detector, close)
+                let tf_signal = state.trend_following.update(high, low, close);
+                let reason = format!(
+                    "Trend Following: RSI={:.1}",
+                    state.trend_following.last_rsi().unwrap_or(50.0)
+                );
+                (
+                    tf_signal,
+                    reason,
+                    state.trend_following.stop_loss(),
+                    state.trend_following.take_profit(),
+                    state
+                        .trend_following
+                        .position_direction()
+                        .unwrap_or(PositionDirection::Long),
+                )
             }
             ActiveStrategy::MeanReversion => {
                 let mr_signal = state.mean_reversion.update(high, low, close);
@@ -218,33 +376,68 @@ impl StrategyRouter {
                     reason,
                     state.mean_reversion.stop_loss(),
                     state.mean_reversion.take_profit(),
+                    PositionDirection::Long,
                 )
             }
+            ActiveStrategy::Grid => {
+                let grid_signal = state.grid.update(high, low, close);
+                let reason = format!("Grid: {} levels", state.grid.levels().len());
+                (grid_signal, reason, None, None, PositionDirection::Long)
+            }
             ActiveStrategy::NoTrade => (
                 Signal::Hold,
                 "Volatile/Uncertain - staying out".to_string(),
                 None,
                 None,
+                PositionDirection::Long,
             ),
         };
 
-        Some(RoutedSignal {
+        let sizing_ctx = SizingContext {
+            base_factor: position_factor,
+            atr: state.regime_detector.atr_value(),
+            price: close,
+            stats: &self.stats,
+        };
+        let sized_factor = self.position_sizer.size_factor(&sizing_ctx);
+
+        let routed_signal = RoutedSignal {
             signal,
             source_strategy: active_strategy,
             regime: regime_result.regime,
             confidence: regime_result.confidence,
-            position_size_factor: position_factor,
+            position_size_factor: sized_factor,
             reason,
             stop_loss,
             take_profit,
-        })
+            direction,
+            leverage: config.max_leverage,
+            risk_halted: false,
+        };
+        self.stats.record_signal(&routed_signal);
+
+        Some(routed_signal)
     }
 
-    /// Select strategy based on regime
+    /// Select strategy based on regime. The strategy itself is picked from
+    /// `registry`'s suitability listing for `regime.regime` rather than a
+    /// hard-coded variant - a `Trending` regime picks whichever registered
+    /// strategy suits it (`TrendFollowing`), and a `MeanReverting` regime
+    /// picks between `Grid` and `MeanReversion` depending on
+    /// `use_grid_for_ranging`/`adx_ranging_threshold` (ADX below that
+    /// threshold favors the grid ladder - the chop is calm enough that a
+    /// maker ladder harvests more round trips than one mean-reversion swing
+    /// would). Position sizing for `Volatile`/`Squeeze` stays a fixed
+    /// de-risking stance regardless of which strategies are registered,
+    /// since it isn't a suitability choice - those regimes always trade a
+    /// reduced size (or not at all), never a different strategy.
     fn select_strategy(
         regime: &RegimeConfidence,
         min_confidence: f64,
         volatile_factor: f64,
+        use_grid_for_ranging: bool,
+        adx_ranging_threshold: f64,
+        registry: &StrategyRegistry,
     ) -> (ActiveStrategy, f64) {
         // If confidence too low, stay out
         if regime.confidence < min_confidence {
@@ -252,57 +445,39 @@ impl StrategyRouter {
         }
 
         match regime.regime {
-            MarketRegime::Trending(_) => (ActiveStrategy::TrendFollowing, 1.0),
-            MarketRegime::MeanReverting => (ActiveStrategy::MeanReversion, 1.0),
+            MarketRegime::Trending(_) => {
+                let strategy = registry
+                    .suited_for(regime.regime)
+                    .into_iter()
+                    .find(|s| *s == ActiveStrategy::TrendFollowing)
+                    .unwrap_or(ActiveStrategy::TrendFollowing);
+                (strategy, 1.0)
+            }
+            MarketRegime::MeanReverting => {
+                let candidates = registry.suited_for(regime.regime);
+                let strategy = if use_grid_for_ranging
+                    && regime.adx_value < adx_ranging_threshold
+                    && candidates.contains(&ActiveStrategy::Grid)
+                {
+                    ActiveStrategy::Grid
+                } else {
+                    ActiveStrategy::MeanReversion
+                };
+                (strategy, 1.0)
+            }
             MarketRegime::Volatile => {
                 // Still trade but with reduced size
                 // Use mean reversion with tight stops in volatile markets
                 (ActiveStrategy::MeanReversion, volatile_factor)
             }
-            MarketRegime::Uncertain => (ActiveStrategy::NoTrade, 0.0),
-        }
-    }
-
-    /// Simple trend following signal based on EMA alignment
-    /// (Placeholder - integrate with your existing Golden Cross strategy)
-    fn trend_following_signal(
-        detector: &RegimeDetector,
-        close: f64,
-    ) -> (Signal, String, Option<f64>, Option<f64>) {
-        let adx = detector.adx_value().unwrap_or(0.0);
-        let atr = detector.atr_value().unwrap_or(close * 0.02);
-
-        // This is a simplified version - integrate with your existing EMA strategies
-        let regime = detector.current_regime();
-
-        match regime {
-            MarketRegime::Trending(TrendDirection::Bullish) if adx > 25.0 => {
-                let stop_loss = close - (atr * 2.0);
-                let take_profit = close + (atr * 3.0); // 1.5 R:R
-                (
-                    Signal::Buy,
-                    format!("Trend Buy: Bullish trend, ADX={:.1}", adx),
-                    Some(stop_loss),
-                    Some(take_profit),
-                )
-            }
-            MarketRegime::Trending(TrendDirection::Bearish) if adx > 25.0 => {
-                // In spot trading, we'd sell/exit here, not short
-                let stop_loss = close + (atr * 2.0);
-                let take_profit = close - (atr * 3.0);
-                (
-                    Signal::Sell,
-                    format!("Trend Sell: Bearish trend, ADX={:.1}", adx),
-                    Some(stop_loss),
-                    Some(take_profit),
-                )
+            MarketRegime::Squeeze => {
+                // Pre-breakout coiling - mean reversion inside the bands is
+                // about to stop working, but the breakout direction isn't
+                // confirmed yet either, so trade smaller still than a
+                // confirmed volatile regime
+                (ActiveStrategy::MeanReversion, volatile_factor * 0.5)
             }
-            _ => (
-                Signal::Hold,
-                "Trend: Waiting for stronger signal".to_string(),
-                None,
-                None,
-            ),
+            MarketRegime::Uncertain => (ActiveStrategy::NoTrade, 0.0),
         }
     }
 
@@ -348,6 +523,22 @@ pub struct RouterStats {
     pub mean_reversion_signals: u64,
     pub no_trade_periods: u64,
     pub regime_changes: u64,
+    /// Signals sourced from the grid-trading strategy
+    pub grid_signals: u64,
+    /// Signals that opened or held a long position
+    pub long_signals: u64,
+    /// Signals that opened or held a short position
+    pub short_signals: u64,
+    /// Closed trades with a positive return, recorded via `record_trade_outcome`
+    pub wins: u64,
+    /// Closed trades with a non-positive return
+    pub losses: u64,
+    /// Signals forced by `RiskGuard` (drawdown halt or manual `force_exit`)
+    pub risk_halts: u64,
+    /// Running mean return of winning trades (e.g. 0.02 == 2%)
+    pub avg_win: f64,
+    /// Running mean |return| of losing trades
+    pub avg_loss: f64,
 }
 
 impl RouterStats {
@@ -356,14 +547,37 @@ impl RouterStats {
         match signal.source_strategy {
             ActiveStrategy::TrendFollowing => self.trend_following_signals += 1,
             ActiveStrategy::MeanReversion => self.mean_reversion_signals += 1,
+            ActiveStrategy::Grid => self.grid_signals += 1,
             ActiveStrategy::NoTrade => self.no_trade_periods += 1,
         }
+        if signal.signal != Signal::Hold {
+            match signal.direction {
+                PositionDirection::Long => self.long_signals += 1,
+                PositionDirection::Short => self.short_signals += 1,
+            }
+        }
+        if signal.risk_halted {
+            self.risk_halts += 1;
+        }
+    }
+
+    /// Record the realized return of a closed trade as a win or loss,
+    /// updating the running averages `FractionalKellySizer` reads from
+    pub fn record_trade_outcome(&mut self, return_pct: f64) {
+        if return_pct > 0.0 {
+            self.wins += 1;
+            self.avg_win += (return_pct - self.avg_win) / self.wins as f64;
+        } else {
+            self.losses += 1;
+            self.avg_loss += (return_pct.abs() - self.avg_loss) / self.losses as f64;
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::regime::TrendDirection;
 
     #[test]
     fn test_router_registration() {
@@ -403,20 +617,170 @@ mod tests {
     #[test]
     fn test_regime_based_strategy_selection() {
         let _config = StrategyRouterConfig::default();
+        let registry = StrategyRegistry::with_builtins();
 
         // Trending regime should select TrendFollowing
         let trending = RegimeConfidence::new(MarketRegime::Trending(TrendDirection::Bullish), 0.8);
-        let (strategy, _) = StrategyRouter::select_strategy(&trending, 0.5, 0.5);
+        let (strategy, _) = StrategyRouter::select_strategy(&trending, 0.5, 0.5, false, 20.0, &registry);
         assert_eq!(strategy, ActiveStrategy::TrendFollowing);
 
         // Mean reverting should select MeanReversion
         let ranging = RegimeConfidence::new(MarketRegime::MeanReverting, 0.8);
-        let (strategy, _) = StrategyRouter::select_strategy(&ranging, 0.5, 0.5);
+        let (strategy, _) = StrategyRouter::select_strategy(&ranging, 0.5, 0.5, false, 20.0, &registry);
         assert_eq!(strategy, ActiveStrategy::MeanReversion);
 
         // Low confidence should be NoTrade
         let uncertain = RegimeConfidence::new(MarketRegime::Trending(TrendDirection::Bullish), 0.3);
-        let (strategy, _) = StrategyRouter::select_strategy(&uncertain, 0.5, 0.5);
+        let (strategy, _) = StrategyRouter::select_strategy(&uncertain, 0.5, 0.5, false, 20.0, &registry);
         assert_eq!(strategy, ActiveStrategy::NoTrade);
     }
+
+    #[test]
+    fn test_grid_selected_for_calm_ranging_regime_when_enabled() {
+        let registry = StrategyRegistry::with_builtins();
+        let mut low_adx = RegimeConfidence::new(MarketRegime::MeanReverting, 0.8);
+        low_adx.adx_value = 10.0;
+
+        // Disabled by default - falls back to MeanReversion
+        let (strategy, _) = StrategyRouter::select_strategy(&low_adx, 0.5, 0.5, false, 20.0, &registry);
+        assert_eq!(strategy, ActiveStrategy::MeanReversion);
+
+        // Enabled, and ADX is below the ranging threshold - selects Grid
+        let (strategy, _) = StrategyRouter::select_strategy(&low_adx, 0.5, 0.5, true, 20.0, &registry);
+        assert_eq!(strategy, ActiveStrategy::Grid);
+
+        // Enabled, but ADX is too high for a calm ladder - stays on MeanReversion
+        let mut high_adx = RegimeConfidence::new(MarketRegime::MeanReverting, 0.8);
+        high_adx.adx_value = 25.0;
+        let (strategy, _) = StrategyRouter::select_strategy(&high_adx, 0.5, 0.5, true, 20.0, &registry);
+        assert_eq!(strategy, ActiveStrategy::MeanReversion);
+    }
+
+    #[test]
+    fn test_router_routes_to_grid_strategy_in_calm_ranges() {
+        let config = StrategyRouterConfig {
+            use_grid_for_ranging: true,
+            ..StrategyRouterConfig::default()
+        };
+        let mut router = StrategyRouter::new(config);
+
+        // Choppy sideways data with shrinking range - low ADX
+        let mut result = None;
+        let mut price = 50000.0;
+        for i in 0..300 {
+            price += if i % 2 == 0 { 5.0 } else { -5.0 };
+            result = router.update("BTC/USD", price + 10.0, price - 10.0, price);
+        }
+
+        if let Some(signal) = result {
+            if signal.regime == MarketRegime::MeanReverting {
+                assert_eq!(signal.source_strategy, ActiveStrategy::Grid);
+            }
+        }
+    }
+
+    #[test]
+    fn test_routed_signal_defaults_to_long_without_shorting() {
+        let mut router = StrategyRouter::default_config();
+
+        let mut result = None;
+        for i in 0..250 {
+            let price = 50000.0 - (i as f64 * 10.0); // Trending down
+            let high = price + 50.0;
+            let low = price - 50.0;
+            result = router.update("BTC/USD", high, low, price);
+        }
+
+        let signal = result.unwrap();
+        assert_eq!(signal.direction, PositionDirection::Long);
+        assert_eq!(signal.leverage, 1.0);
+    }
+
+    #[test]
+    fn test_short_enabled_config_passes_through_to_leverage() {
+        let config = StrategyRouterConfig {
+            can_short: true,
+            max_leverage: 2.0,
+            ..StrategyRouterConfig::default()
+        };
+        let mut router = StrategyRouter::new(config);
+
+        let mut result = None;
+        for i in 0..250 {
+            let price = 50000.0 - (i as f64 * 10.0); // Trending down
+            let high = price + 50.0;
+            let low = price - 50.0;
+            result = router.update("BTC/USD", high, low, price);
+        }
+
+        let signal = result.unwrap();
+        assert_eq!(signal.leverage, 2.0);
+    }
+
+    #[test]
+    fn test_custom_position_sizer_scales_signal_factor() {
+        use crate::strategy::position_sizing::VolatilityTargetSizer;
+
+        let mut router = StrategyRouter::default_config();
+        router.set_position_sizer(Box::new(VolatilityTargetSizer {
+            target_daily_vol: 0.0001,
+        }));
+
+        let mut result = None;
+        for i in 0..250 {
+            let price = 50000.0 + (i as f64 * 10.0); // Trending up
+            let high = price + 50.0;
+            let low = price - 50.0;
+            result = router.update("BTC/USD", high, low, price);
+        }
+
+        let signal = result.unwrap();
+        // A tiny vol target against real ATR should scale sizing well below 1.0
+        assert!(signal.position_size_factor < 1.0);
+    }
+
+    #[test]
+    fn test_router_stats_tracks_trade_outcomes() {
+        let mut router = StrategyRouter::default_config();
+        router.record_trade_outcome(0.02);
+        router.record_trade_outcome(-0.01);
+
+        assert_eq!(router.stats().wins, 1);
+        assert_eq!(router.stats().losses, 1);
+        assert!((router.stats().avg_win - 0.02).abs() < 1e-9);
+        assert!((router.stats().avg_loss - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drawdown_breach_forces_risk_halt() {
+        let mut router = StrategyRouter::default_config();
+        router.set_risk_guard_config(crate::strategy::risk_guard::RiskGuardConfig {
+            max_relative_drawdown: 0.1,
+            re_entry_fraction: 0.95,
+        });
+
+        router.update_asset_equity("BTC/USD", 100.0);
+        router.update_asset_equity("BTC/USD", 85.0); // 15% drawdown, breaches 10%
+
+        let signal = router.update("BTC/USD", 50100.0, 49900.0, 50000.0).unwrap();
+
+        assert!(signal.risk_halted);
+        assert_eq!(signal.source_strategy, ActiveStrategy::NoTrade);
+        assert_eq!(signal.position_size_factor, 0.0);
+        assert_eq!(router.get_active_strategy("BTC/USD"), Some(ActiveStrategy::NoTrade));
+        assert_eq!(router.stats().risk_halts, 1);
+    }
+
+    #[test]
+    fn test_manual_force_exit_halts_until_resumed() {
+        let mut router = StrategyRouter::default_config();
+
+        router.force_exit("BTC/USD");
+        let signal = router.update("BTC/USD", 50100.0, 49900.0, 50000.0).unwrap();
+        assert!(signal.risk_halted);
+        assert!(router.is_risk_halted("BTC/USD"));
+
+        router.resume("BTC/USD");
+        assert!(!router.is_risk_halted("BTC/USD"));
+    }
 }
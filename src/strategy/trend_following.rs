@@ -0,0 +1,243 @@
+//! Trend-Following Strategy
+//!
+//! A fast/slow EMA crossover for entries, using the slow EMA as the trend
+//! filter (only long above it, only exit below it), with an RSI filter that
+//! blocks longs unless momentum confirms and blocks exits unless momentum has
+//! turned, to cut whipsaws. Stops and targets are sized from ATR. When
+//! `allow_short` is enabled, the same crossover mirrors into genuine short
+//! entries below the filter. Mirrors `MeanReversionStrategy`'s API so the
+//! router can report reasons uniformly regardless of which strategy fired.
+
+use crate::regime::{ATR, EMA, RSI};
+use crate::regime::PositionDirection;
+use crate::strategy::mean_reversion::Signal;
+
+/// Configuration for the trend-following strategy
+#[derive(Debug, Clone)]
+pub struct TrendFollowingConfig {
+    /// Fast EMA period (crossover leg)
+    pub ema_fast_period: usize,
+    /// Slow EMA period - doubles as crossover leg and trend filter
+    pub ema_slow_period: usize,
+    /// RSI period for the momentum filter
+    pub rsi_period: usize,
+    /// Longs require RSI above this threshold
+    pub rsi_buy_threshold: f64,
+    /// Exits require RSI below this threshold
+    pub rsi_sell_threshold: f64,
+    /// ATR period for stop/target sizing
+    pub atr_period: usize,
+    /// Stop-loss distance as a multiple of ATR
+    pub atr_stop_loss_mult: f64,
+    /// Take-profit distance as a multiple of ATR
+    pub atr_take_profit_mult: f64,
+    /// Allow opening short positions in bearish trends (spot-only when false)
+    pub allow_short: bool,
+}
+
+impl Default for TrendFollowingConfig {
+    fn default() -> Self {
+        Self {
+            ema_fast_period: 50,
+            ema_slow_period: 200,
+            rsi_period: 14,
+            rsi_buy_threshold: 55.0,
+            rsi_sell_threshold: 45.0,
+            atr_period: 14,
+            atr_stop_loss_mult: 2.0,
+            atr_take_profit_mult: 3.0,
+            allow_short: false,
+        }
+    }
+}
+
+/// EMA crossover trend-following strategy with an RSI momentum filter
+#[derive(Debug)]
+pub struct TrendFollowingStrategy {
+    config: TrendFollowingConfig,
+
+    ema_fast: EMA,
+    ema_slow: EMA,
+    rsi: RSI,
+    atr: ATR,
+
+    /// Currently open position direction, or `None` if flat
+    position: Option<PositionDirection>,
+    last_rsi: Option<f64>,
+    last_stop_loss: Option<f64>,
+    last_take_profit: Option<f64>,
+}
+
+impl TrendFollowingStrategy {
+    pub fn new(config: TrendFollowingConfig) -> Self {
+        Self {
+            ema_fast: EMA::new(config.ema_fast_period),
+            ema_slow: EMA::new(config.ema_slow_period),
+            rsi: RSI::new(config.rsi_period),
+            atr: ATR::new(config.atr_period),
+            position: None,
+            last_rsi: None,
+            last_stop_loss: None,
+            last_take_profit: None,
+            config,
+        }
+    }
+
+    /// Create with default config
+    pub fn default_config() -> Self {
+        Self::new(TrendFollowingConfig::default())
+    }
+
+    /// Update with new OHLC bar and get a trading signal
+    pub fn update(&mut self, high: f64, low: f64, close: f64) -> Signal {
+        let fast = self.ema_fast.update(close);
+        let slow = self.ema_slow.update(close);
+        let rsi = self.rsi.update(close);
+        let atr = self.atr.update(high, low, close);
+        self.last_rsi = rsi;
+
+        let (Some(fast), Some(slow), Some(rsi), Some(atr)) = (fast, slow, rsi, atr) else {
+            return Signal::Hold;
+        };
+
+        let bullish_cross = fast > slow;
+        let above_filter = close > slow;
+        let below_filter = close < slow;
+
+        match self.position {
+            None => {
+                if bullish_cross && above_filter && rsi > self.config.rsi_buy_threshold {
+                    self.position = Some(PositionDirection::Long);
+                    self.last_stop_loss = Some(close - atr * self.config.atr_stop_loss_mult);
+                    self.last_take_profit = Some(close + atr * self.config.atr_take_profit_mult);
+                    return Signal::Buy;
+                }
+                if self.config.allow_short
+                    && !bullish_cross
+                    && below_filter
+                    && rsi < self.config.rsi_sell_threshold
+                {
+                    self.position = Some(PositionDirection::Short);
+                    self.last_stop_loss = Some(close + atr * self.config.atr_stop_loss_mult);
+                    self.last_take_profit = Some(close - atr * self.config.atr_take_profit_mult);
+                    return Signal::Sell;
+                }
+            }
+            Some(PositionDirection::Long) => {
+                if (!bullish_cross || below_filter) && rsi < self.config.rsi_sell_threshold {
+                    self.position = None;
+                    return Signal::Sell;
+                }
+            }
+            Some(PositionDirection::Short) => {
+                if (bullish_cross || above_filter) && rsi > self.config.rsi_buy_threshold {
+                    self.position = None;
+                    return Signal::Buy;
+                }
+            }
+        }
+
+        Signal::Hold
+    }
+
+    /// Direction of the currently open position, if any
+    pub fn position_direction(&self) -> Option<PositionDirection> {
+        self.position
+    }
+
+    /// Last RSI value, for reason reporting
+    pub fn last_rsi(&self) -> Option<f64> {
+        self.last_rsi
+    }
+
+    /// Stop-loss level for the current/last trade
+    pub fn stop_loss(&self) -> Option<f64> {
+        self.last_stop_loss
+    }
+
+    /// Take-profit level for the current/last trade
+    pub fn take_profit(&self) -> Option<f64> {
+        self.last_take_profit
+    }
+
+    /// Whether the strategy has enough data to generate signals
+    pub fn is_ready(&self) -> bool {
+        self.ema_slow.is_ready() && self.rsi.is_ready() && self.atr.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uptrend_eventually_buys() {
+        let mut strategy = TrendFollowingStrategy::new(TrendFollowingConfig {
+            ema_fast_period: 5,
+            ema_slow_period: 10,
+            rsi_period: 5,
+            ..TrendFollowingConfig::default()
+        });
+
+        let mut price = 100.0;
+        let mut signals = Vec::new();
+        for _ in 0..60 {
+            price += 1.0;
+            signals.push(strategy.update(price + 0.5, price - 0.5, price));
+        }
+
+        assert!(signals.contains(&Signal::Buy));
+        assert!(strategy.stop_loss().is_some());
+        assert!(strategy.take_profit().is_some());
+    }
+
+    #[test]
+    fn test_downtrend_with_shorting_enabled_opens_short() {
+        let mut strategy = TrendFollowingStrategy::new(TrendFollowingConfig {
+            ema_fast_period: 5,
+            ema_slow_period: 10,
+            rsi_period: 5,
+            allow_short: true,
+            ..TrendFollowingConfig::default()
+        });
+
+        let mut price = 200.0;
+        let mut signals = Vec::new();
+        for _ in 0..60 {
+            price -= 1.0;
+            signals.push(strategy.update(price + 0.5, price - 0.5, price));
+        }
+
+        assert_eq!(strategy.position_direction(), Some(PositionDirection::Short));
+        assert!(signals.contains(&Signal::Sell));
+        // Shorts flip the stop/target sides relative to a long
+        assert!(strategy.stop_loss().unwrap() > strategy.take_profit().unwrap());
+    }
+
+    #[test]
+    fn test_downtrend_without_shorting_stays_flat() {
+        let mut strategy = TrendFollowingStrategy::new(TrendFollowingConfig {
+            ema_fast_period: 5,
+            ema_slow_period: 10,
+            rsi_period: 5,
+            allow_short: false,
+            ..TrendFollowingConfig::default()
+        });
+
+        let mut price = 200.0;
+        for _ in 0..60 {
+            price -= 1.0;
+            strategy.update(price + 0.5, price - 0.5, price);
+        }
+
+        assert_eq!(strategy.position_direction(), None);
+    }
+
+    #[test]
+    fn test_not_ready_holds() {
+        let mut strategy = TrendFollowingStrategy::default_config();
+        let signal = strategy.update(101.0, 99.0, 100.0);
+        assert_eq!(signal, Signal::Hold);
+        assert!(!strategy.is_ready());
+    }
+}